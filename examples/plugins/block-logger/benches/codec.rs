@@ -0,0 +1,40 @@
+//! JSON against MessagePack for decoding a stream of 1000 `block_break` payloads.
+
+use block_logger_plugin::bench::{self, Encoding};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use serde_json::{json, Value};
+use std::hint::black_box;
+
+const STREAM_LEN: usize = 1000;
+const BLOCKS: &[&str] = &["minecraft:stone", "minecraft:dirt", "minecraft:oak_log", "minecraft:diamond_ore", "minecraft:sand"];
+
+fn block_break(i: usize) -> Value {
+    let x = (i % 64) as i32 - 32;
+    let z = (i / 64) as i32;
+    json!({
+        "player": {
+            "uuid": format!("00000000-0000-4000-8000-{:012x}", i % 20),
+            "name": format!("player{}", i % 20),
+            "position": { "x": f64::from(x) + 0.5, "y": 65.0, "z": f64::from(z) + 1.5 },
+            "yaw": (i % 360) as f32,
+            "pitch": 30.0,
+        },
+        "block": { "block_type": BLOCKS[i % BLOCKS.len()], "position": { "x": x, "y": 64, "z": z, "dimension": "overworld" } },
+    })
+}
+
+fn decode_stream(c: &mut Criterion) {
+    let events: Vec<Value> = (0..STREAM_LEN).map(block_break).collect();
+
+    let mut group = c.benchmark_group("decode_1000_block_breaks");
+    group.throughput(Throughput::Elements(STREAM_LEN as u64));
+    for encoding in [Encoding::Json, Encoding::MessagePack] {
+        let stream: Vec<Vec<u8>> = events.iter().map(|ev| bench::encode_as(encoding, ev)).collect();
+        assert!(stream.iter().all(|payload| bench::decode_block_break_as(encoding, payload)), "{encoding:?} fixtures do not decode");
+        group.bench_function(format!("{encoding:?}"), |b| b.iter(|| stream.iter().filter(|payload| bench::decode_block_break_as(encoding, black_box(payload))).count()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, decode_stream);
+criterion_main!(benches);
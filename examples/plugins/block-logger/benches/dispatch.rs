@@ -0,0 +1,61 @@
+//! Regression baseline for the `handle_event` hot path, reported in events per second.
+
+use block_logger_plugin::bench;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use serde_json::{json, Value};
+use std::hint::black_box;
+
+fn player() -> Value {
+    json!({
+        "uuid": "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+        "name": "Notch",
+        "position": { "x": 128.5, "y": 64.0, "z": -240.5 },
+        "yaw": 90.0,
+        "pitch": 12.5,
+    })
+}
+
+/// Realistic payloads for the three busiest events.
+fn fixtures() -> Vec<(&'static str, Value)> {
+    vec![
+        ("block_break", json!({ "player": player(), "block": { "block_type": "minecraft:stone", "position": { "x": 129, "y": 63, "z": -241, "dimension": "overworld" } } })),
+        ("block_place", json!({ "player": player(), "block": { "block_type": "minecraft:oak_planks", "position": { "x": 129, "y": 64, "z": -242, "dimension": "overworld" }, "properties": { "axis": "y" } } })),
+        ("player_chat", json!({ "player": player(), "message": "anyone selling iron? meet at spawn" })),
+    ]
+}
+
+fn split_envelope(c: &mut Criterion) {
+    let (event, payload) = &fixtures()[0];
+    let envelope = bench::envelope(event, &bench::encode_payload(payload));
+
+    let mut group = c.benchmark_group("split_envelope");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function(format!("{}_bytes", envelope.len()), |b| b.iter(|| bench::split_envelope(black_box(&envelope))));
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Elements(1));
+    for (event, payload) in fixtures() {
+        assert!(bench::DECODABLE_EVENTS.contains(&event));
+        let payload = bench::encode_payload(&payload);
+        assert!(bench::decode_event(event, &payload), "{event} fixture does not decode");
+        group.bench_function(event, |b| b.iter(|| bench::decode_event(event, black_box(&payload))));
+    }
+    group.finish();
+}
+
+fn handle_event(c: &mut Criterion) {
+    let mut group = c.benchmark_group("handle_event");
+    group.throughput(Throughput::Elements(1));
+    for (event, payload) in fixtures() {
+        let envelope = bench::envelope(event, &bench::encode_payload(&payload));
+        assert_ne!(bench::handle_event(&envelope)[0], bench::ERROR_RESULT_MAGIC, "{event} handler failed");
+        group.bench_function(event, |b| b.iter(|| bench::handle_event(black_box(&envelope))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, split_envelope, decode, handle_event);
+criterion_main!(benches);
@@ -0,0 +1,39 @@
+//! Decoding and handling a `block_explode` that hits 200 blocks, about what a
+//! charged creeper or a few stacked TNT leave behind.
+
+use block_logger_plugin::bench;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use serde_json::{json, Value};
+use std::hint::black_box;
+
+const BLOCK_COUNT: i32 = 200;
+
+fn explosion() -> Value {
+    let blocks: Vec<Value> = (0..BLOCK_COUNT)
+        .map(|i| {
+            let block_type = match i % 10 {
+                0 => "minecraft:diamond_ore",
+                1..=3 => "minecraft:dirt",
+                _ => "minecraft:stone",
+            };
+            json!({ "block_type": block_type, "position": { "x": i % 6 - 3, "y": 60 + i / 36, "z": i / 6 % 6 - 3, "dimension": "overworld" } })
+        })
+        .collect();
+    json!({ "blocks": blocks, "cause": "minecraft:creeper", "power": 6.0, "epicenter": { "x": 0, "y": 62, "z": 0, "dimension": "overworld" } })
+}
+
+fn block_explode(c: &mut Criterion) {
+    let payload = bench::encode_payload(&explosion());
+    assert!(bench::decode_event("block_explode", &payload), "explosion fixture does not decode");
+    let envelope = bench::envelope("block_explode", &payload);
+    assert_ne!(bench::handle_event(&envelope)[0], bench::ERROR_RESULT_MAGIC, "block_explode handler failed");
+
+    let mut group = c.benchmark_group("block_explode_200");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("decode", |b| b.iter(|| bench::decode_event("block_explode", black_box(&payload))));
+    group.bench_function("handle_event", |b| b.iter(|| bench::handle_event(black_box(&envelope))));
+    group.finish();
+}
+
+criterion_group!(benches, block_explode);
+criterion_main!(benches);
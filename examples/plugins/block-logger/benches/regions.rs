@@ -0,0 +1,49 @@
+//! R-tree lookups against a linear scan: 1000 random positions queried
+//! against 10,000 regions.
+
+use block_logger_plugin::bench::RegionSet;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use std::hint::black_box;
+
+const REGION_COUNT: usize = 10_000;
+const QUERY_COUNT: usize = 1000;
+const WORLD_RADIUS: i32 = 5000;
+
+/// Deterministic xorshift so every run queries the same positions.
+struct Positions(u64);
+
+impl Positions {
+    fn next(&mut self, bound: i32) -> i32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % (2 * bound as u64)) as i32 - bound
+    }
+
+    fn point(&mut self) -> [i32; 3] {
+        [self.next(WORLD_RADIUS), self.next(64) + 64, self.next(WORLD_RADIUS)]
+    }
+}
+
+fn regions(c: &mut Criterion) {
+    let mut rng = Positions(0x2545_F491_4F6C_DD1D);
+    let bounds: Vec<([i32; 3], [i32; 3])> = (0..REGION_COUNT)
+        .map(|_| {
+            let [x, y, z] = rng.point();
+            let (w, h, d) = (rng.next(50) + 51, rng.next(16) + 17, rng.next(50) + 51);
+            ([x, y, z], [x + w, y + h, z + d])
+        })
+        .collect();
+    let set = RegionSet::new(&bounds);
+    let queries: Vec<[i32; 3]> = (0..QUERY_COUNT).map(|_| rng.point()).collect();
+    assert!(queries.iter().all(|&q| set.query_index(q) == set.query_linear(q)), "R-tree and linear scan disagree");
+
+    let mut group = c.benchmark_group("regions_10k");
+    group.throughput(Throughput::Elements(QUERY_COUNT as u64));
+    group.bench_function("rtree", |b| b.iter(|| queries.iter().map(|&q| set.query_index(black_box(q))).sum::<usize>()));
+    group.bench_function("linear_scan", |b| b.iter(|| queries.iter().map(|&q| set.query_linear(black_box(q))).sum::<usize>()));
+    group.finish();
+}
+
+criterion_group!(benches, regions);
+criterion_main!(benches);
@@ -0,0 +1,67 @@
+//! Generates the `extern "ExtismHost"` declarations from `wit/dragonfly.wit`.
+//!
+//! Extism plugins are core wasm modules rather than components, so the
+//! interface is read with `wit-parser` and lowered to Extism host functions:
+//! `list<u8>` parameters become `&[u8]`, `list<u8>` results `Vec<u8>`, and
+//! integer results the matching Rust integer.
+//!
+//! The host imports only exist on wasm32, so native builds (tests and benches)
+//! use a second generated file of stubs with the same signatures. Each stub
+//! hands its name and payload to `host::stub`, which fails outside tests and
+//! answers from `host::mock` inside them.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use wit_parser::{Resolve, Type, TypeDefKind};
+
+const WIT_PATH: &str = "wit/dragonfly.wit";
+
+fn main() {
+    println!("cargo:rerun-if-changed={WIT_PATH}");
+
+    let mut resolve = Resolve::default();
+    let pkg = resolve.push_file(WIT_PATH).unwrap_or_else(|e| panic!("failed to parse {WIT_PATH}: {e:?}"));
+
+    let mut out = String::from("#[host_fn]\nextern \"ExtismHost\" {\n");
+    let mut stubs = String::new();
+    for (_, &iface) in &resolve.packages[pkg].interfaces {
+        for func in resolve.interfaces[iface].functions.values() {
+            let name = func.name.replace('-', "_");
+            let params: Vec<(String, &str)> = func.params.iter().map(|p| (p.name.replace('-', "_"), rust_type(&resolve, &p.ty, true))).collect();
+            let ret = func.result.as_ref().map(|t| rust_type(&resolve, t, false));
+
+            let decl: Vec<String> = params.iter().map(|(n, ty)| format!("{n}: {ty}")).collect();
+            let arrow = ret.map(|r| format!(" -> {r}")).unwrap_or_default();
+            writeln!(out, "    pub(crate) fn {name}({}){arrow};", decl.join(", ")).unwrap();
+
+            let data = match params.as_slice() {
+                [] => "&[]".to_string(),
+                [(n, "&[u8]")] => n.clone(),
+                _ => panic!("host stubs only support a single list<u8> parameter, {name} has {params:?}"),
+            };
+            // Like the imports, a plugin only calls some of the stubs.
+            writeln!(stubs, "#[allow(dead_code)]\npub(crate) unsafe fn {name}({}) -> Result<{}, Error> {{", decl.join(", "), ret.unwrap_or("()")).unwrap();
+            writeln!(stubs, "    stub(\"{name}\", {data})\n}}\n").unwrap();
+        }
+    }
+    out.push_str("}\n");
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("host.rs"), out).expect("failed to write host bindings");
+    std::fs::write(Path::new(&out_dir).join("host_stubs.rs"), stubs).expect("failed to write host stubs");
+}
+
+fn rust_type(resolve: &Resolve, ty: &Type, param: bool) -> &'static str {
+    match ty {
+        Type::U32 => "u32",
+        Type::U64 => "u64",
+        Type::S32 => "i32",
+        Type::S64 => "i64",
+        Type::Id(id) => match &resolve.types[*id].kind {
+            TypeDefKind::List(Type::U8) if param => "&[u8]",
+            TypeDefKind::List(Type::U8) => "Vec<u8>",
+            other => panic!("unsupported WIT type in host interface: {other:?}"),
+        },
+        other => panic!("unsupported WIT type in host interface: {other:?}"),
+    }
+}
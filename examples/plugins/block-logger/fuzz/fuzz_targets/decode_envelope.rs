@@ -0,0 +1,17 @@
+#![no_main]
+
+use block_logger_plugin::bench;
+use libfuzzer_sys::fuzz_target;
+
+// Any byte string is a possible `handle_event` input; none may panic, and
+// each must produce a cancel flag or an error result.
+fuzz_target!(|data: &[u8]| {
+    if let Some((_, payload_len)) = bench::split_envelope(data) {
+        assert!(payload_len < data.len());
+    }
+    for event in bench::DECODABLE_EVENTS {
+        bench::decode_event(event, data);
+    }
+    let out = bench::handle_event(data);
+    assert!(matches!(out.first(), Some(&0 | &1 | &bench::ERROR_RESULT_MAGIC)), "unexpected output {out:?}");
+});
@@ -0,0 +1,59 @@
+use crate::codec;
+use crate::error::host_rejected;
+use crate::host;
+use extism_pdk::*;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+thread_local! {
+    static GRANTED: RefCell<HashSet<(String, String)>> = RefCell::new(HashSet::new());
+}
+
+#[derive(Serialize)]
+struct AdvancementRequest {
+    player_uuid: String,
+    advancement_id: String,
+    display_toast: bool,
+}
+
+/// Grants `advancement_id` to the player once per session; later calls for
+/// the same pair return `Ok(false)` without asking the host again.
+pub(crate) fn grant_advancement(uuid: &str, advancement_id: &str, display_toast: bool) -> Result<bool, Error> {
+    let key = (uuid.to_string(), advancement_id.to_string());
+    if GRANTED.with(|g| g.borrow().contains(&key)) {
+        return Ok(false);
+    }
+    let req = AdvancementRequest { player_uuid: uuid.into(), advancement_id: advancement_id.into(), display_toast };
+    match unsafe { host::host_grant_advancement(&codec::encode(&req)?)? } {
+        1 => {
+            GRANTED.with(|g| g.borrow_mut().insert(key));
+            Ok(true)
+        }
+        code => Err(host_rejected(format!("advancement {advancement_id}"), code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::mock::{self, Reply};
+
+    #[test]
+    fn granting_twice_asks_the_host_once() {
+        mock::reply("host_grant_advancement", Reply::Code(1));
+        assert!(grant_advancement("steve", "blocklogger:first_protected_block", true).unwrap());
+        assert!(!grant_advancement("steve", "blocklogger:first_protected_block", true).unwrap());
+        assert!(grant_advancement("alex", "blocklogger:first_protected_block", false).unwrap());
+        assert_eq!(mock::calls("host_grant_advancement").len(), 2);
+    }
+
+    #[test]
+    fn rejected_grants_are_retried() {
+        mock::reply("host_grant_advancement", Reply::Code(0));
+        assert!(grant_advancement("steve", "blocklogger:first_protected_block", true).is_err());
+        mock::reply("host_grant_advancement", Reply::Code(1));
+        assert!(grant_advancement("steve", "blocklogger:first_protected_block", true).unwrap());
+        assert_eq!(mock::calls("host_grant_advancement").len(), 2);
+    }
+}
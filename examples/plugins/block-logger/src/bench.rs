@@ -0,0 +1,113 @@
+//! Entry points for the criterion benches in `benches/`, the fuzz target in
+//! `fuzz/` and the compile-fail tests in `tests/ui/`. The plugin is built as a
+//! cdylib, so these thin wrappers are its only public Rust API; they are not
+//! part of the plugin interface. Host calls made while handling an event fail
+//! immediately on native targets, so bench numbers exclude host time.
+
+pub use crate::protection::is_valid_namespaced_id;
+
+use crate::router::PluginEvent;
+use crate::{codec, error, router, BlockBreakEvent, BlockExplodeEvent, BlockPlaceEvent, PlayerChatEvent};
+use serde::de::DeserializeOwned;
+
+/// First byte of a `handle_event` output that reports a handler error.
+pub const ERROR_RESULT_MAGIC: u8 = error::ERROR_RESULT_MAGIC;
+
+/// Encodes `value` as an event payload in the plugin's encoding.
+pub fn encode_payload(value: &serde_json::Value) -> Vec<u8> {
+    codec::encode(value).expect("JSON values always encode")
+}
+
+/// Wraps `payload` in the envelope the host sends to `handle_event`.
+pub fn envelope(event: &str, payload: &[u8]) -> Vec<u8> {
+    router::envelope(event, codec::ENCODING, payload)
+}
+
+/// Splits an envelope's header off, returning the event type and payload length.
+pub fn split_envelope(envelope: &[u8]) -> Option<(&str, usize)> {
+    router::split_envelope(envelope).map(|(event, _, payload)| (event, payload.len()))
+}
+
+/// Events whose payload decoding can be benchmarked on its own.
+pub const DECODABLE_EVENTS: &[&str] = &[BlockBreakEvent::EVENT_NAME, BlockPlaceEvent::EVENT_NAME, PlayerChatEvent::EVENT_NAME, BlockExplodeEvent::EVENT_NAME];
+
+/// Decodes `payload` into the typed struct for `event`. Returns `false` if it
+/// does not decode or `event` is not in `DECODABLE_EVENTS`.
+pub fn decode_event(event: &str, payload: &[u8]) -> bool {
+    fn decodes<E: DeserializeOwned>(payload: &[u8]) -> bool {
+        codec::decode::<E>(payload).is_ok()
+    }
+    match event {
+        BlockBreakEvent::EVENT_NAME => decodes::<BlockBreakEvent>(payload),
+        BlockPlaceEvent::EVENT_NAME => decodes::<BlockPlaceEvent>(payload),
+        PlayerChatEvent::EVENT_NAME => decodes::<PlayerChatEvent>(payload),
+        BlockExplodeEvent::EVENT_NAME => decodes::<BlockExplodeEvent>(payload),
+        _ => false,
+    }
+}
+
+/// Runs `envelope` through `handle_event` and returns its output.
+pub fn handle_event(envelope: &[u8]) -> Vec<u8> {
+    crate::handle_envelope(envelope)
+}
+
+/// The same regions behind the R-tree index and in a plain list, so the
+/// `regions` bench can compare lookups.
+#[cfg(feature = "regions")]
+pub struct RegionSet {
+    index: crate::region::SpatialIndex,
+    list: Vec<crate::region::Region>,
+}
+
+#[cfg(feature = "regions")]
+impl RegionSet {
+    /// Builds one overworld region per `(min, max)` corner pair.
+    pub fn new(bounds: &[([i32; 3], [i32; 3])]) -> Self {
+        let corner = |[x, y, z]: [i32; 3]| crate::Position { x, y, z, dimension: crate::DEFAULT_DIMENSION.into() };
+        let list: Vec<crate::region::Region> = bounds
+            .iter()
+            .enumerate()
+            .map(|(i, &(min, max))| crate::region::Region { name: format!("region-{i}"), min: corner(min), max: corner(max), rules: Vec::new() })
+            .collect();
+        let mut index = crate::region::SpatialIndex::new();
+        for region in &list {
+            index.insert(region.clone());
+        }
+        Self { index, list }
+    }
+
+    /// Number of regions containing `pos`, found through the R-tree.
+    pub fn query_index(&self, [x, y, z]: [i32; 3]) -> usize {
+        self.index.query(&crate::Position { x, y, z, dimension: crate::DEFAULT_DIMENSION.into() }).len()
+    }
+
+    /// Number of regions containing `pos`, found by checking every region.
+    pub fn query_linear(&self, [x, y, z]: [i32; 3]) -> usize {
+        self.list.iter().filter(|r| (r.min.x..=r.max.x).contains(&x) && (r.min.y..=r.max.y).contains(&y) && (r.min.z..=r.max.z).contains(&z)).count()
+    }
+}
+
+/// A payload encoding the `codec` bench compares, independent of the one the plugin was built for.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy)]
+pub enum Encoding {
+    Json,
+    MessagePack,
+}
+
+#[cfg(feature = "msgpack")]
+pub fn encode_as(encoding: Encoding, value: &serde_json::Value) -> Vec<u8> {
+    match encoding {
+        Encoding::Json => serde_json::to_vec(value).expect("JSON values always encode"),
+        Encoding::MessagePack => rmp_serde::to_vec_named(value).expect("JSON values always encode"),
+    }
+}
+
+/// Decodes a `block_break` payload written with `encoding`, returning whether it parsed.
+#[cfg(feature = "msgpack")]
+pub fn decode_block_break_as(encoding: Encoding, payload: &[u8]) -> bool {
+    match encoding {
+        Encoding::Json => serde_json::from_slice::<BlockBreakEvent>(payload).is_ok(),
+        Encoding::MessagePack => rmp_serde::from_slice::<BlockBreakEvent>(payload).is_ok(),
+    }
+}
@@ -0,0 +1,71 @@
+use crate::codec;
+use crate::error::host_rejected;
+use crate::host;
+use extism_pdk::*;
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct BossBar {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    #[serde(serialize_with = "clamp_progress")]
+    pub(crate) progress: f32,
+    pub(crate) color: String,
+    pub(crate) style: String,
+    pub(crate) players: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct RemoveRequest<'a> {
+    id: &'a str,
+}
+
+fn clamp_progress<S: Serializer>(progress: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+    let clamped = if progress.is_nan() { 0.0 } else { progress.clamp(0.0, 1.0) };
+    serializer.serialize_f32(clamped)
+}
+
+pub(crate) fn create(bar: &BossBar) -> Result<(), Error> {
+    check("create", unsafe { host::host_bossbar_create(&codec::encode(bar)?)? })
+}
+
+pub(crate) fn update(bar: &BossBar) -> Result<(), Error> {
+    check("update", unsafe { host::host_bossbar_update(&codec::encode(bar)?)? })
+}
+
+pub(crate) fn remove(id: &str) -> Result<(), Error> {
+    check("remove", unsafe { host::host_bossbar_remove(&codec::encode(&RemoveRequest { id })?)? })
+}
+
+fn check(op: &str, code: i64) -> Result<(), Error> {
+    match code {
+        1 => Ok(()),
+        code => Err(host_rejected(format!("boss bar {op}"), code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded_progress(progress: f32) -> f64 {
+        let bar = BossBar { id: "bar".into(), title: String::new(), progress, color: "yellow".into(), style: "progress".into(), players: Vec::new() };
+        let value: serde_json::Value = codec::decode(&codec::encode(&bar).unwrap()).unwrap();
+        value["progress"].as_f64().unwrap()
+    }
+
+    #[test]
+    fn progress_is_clamped_to_the_unit_range() {
+        assert_eq!(encoded_progress(0.25), 0.25);
+        assert_eq!(encoded_progress(1.5), 1.0);
+        assert_eq!(encoded_progress(-3.0), 0.0);
+        assert_eq!(encoded_progress(f32::NAN), 0.0);
+        assert_eq!(encoded_progress(f32::INFINITY), 1.0);
+    }
+
+    #[test]
+    fn only_status_one_succeeds() {
+        assert!(check("create", 1).is_ok());
+        assert_eq!(check("create", 0).unwrap_err().to_string(), "host rejected boss bar create with code 0");
+    }
+}
@@ -0,0 +1,85 @@
+use crate::storage::KvStore;
+use extism_pdk::*;
+use serde::{Deserialize, Serialize};
+
+const NAMESPACE: &str = "checkpoints";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Checkpoint {
+    pub(crate) id: String,
+    pub(crate) reached_at_ms: u64,
+}
+
+/// Checkpoints each player has reached, persisted per player UUID so they
+/// survive restarts and reconnects.
+pub(crate) struct CheckpointStore {
+    store: KvStore<Vec<Checkpoint>>,
+}
+
+impl Default for CheckpointStore {
+    fn default() -> Self {
+        Self { store: KvStore::new(NAMESPACE) }
+    }
+}
+
+impl CheckpointStore {
+    pub(crate) fn all(&self, player_uuid: &str) -> Result<Vec<Checkpoint>, Error> {
+        Ok(self.store.get(player_uuid)?.unwrap_or_default())
+    }
+
+    /// Records `checkpoint_id` for the player. Returns `false` if it was already reached,
+    /// keeping the original timestamp.
+    pub(crate) fn reach(&self, player_uuid: &str, checkpoint_id: &str) -> Result<bool, Error> {
+        let mut reached = self.all(player_uuid)?;
+        if reached.iter().any(|c| c.id == checkpoint_id) {
+            return Ok(false);
+        }
+        reached.push(Checkpoint { id: checkpoint_id.into(), reached_at_ms: crate::now_ms()? });
+        self.store.set(player_uuid, &reached)?;
+        Ok(true)
+    }
+
+    /// Storage errors count as not reached.
+    pub(crate) fn has_reached(&self, player_uuid: &str, checkpoint_id: &str) -> bool {
+        self.all(player_uuid).is_ok_and(|reached| reached.iter().any(|c| c.id == checkpoint_id))
+    }
+
+    pub(crate) fn clear_all(&self, player_uuid: &str) -> Result<(), Error> {
+        self.store.delete(player_uuid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec;
+    use crate::host::mock::{self, Reply};
+
+    #[test]
+    fn reached_checkpoints_survive_a_reload() {
+        let checkpoints = CheckpointStore::default();
+        mock::reply("host_current_time_ms", Reply::Code(1_000));
+        mock::reply("host_store_data", Reply::Code(1));
+        mock::reply("host_load_data", Reply::Bytes(Vec::new()));
+        assert!(!checkpoints.has_reached("steve-uuid", "spawn"));
+        assert!(checkpoints.reach("steve-uuid", "spawn").unwrap());
+
+        // What was stored is what the host hands back on the next load.
+        let stored: serde_json::Value = codec::decode(&mock::calls("host_store_data")[0]).unwrap();
+        mock::reply("host_load_data", Reply::Bytes(serde_json::from_value(stored["value"].clone()).unwrap()));
+        assert!(checkpoints.has_reached("steve-uuid", "spawn"));
+        assert!(!checkpoints.has_reached("steve-uuid", "nether"));
+        assert_eq!(checkpoints.all("steve-uuid").unwrap(), [Checkpoint { id: "spawn".into(), reached_at_ms: 1_000 }]);
+        assert!(!checkpoints.reach("steve-uuid", "spawn").unwrap());
+
+        checkpoints.clear_all("steve-uuid").unwrap();
+        let cleared: serde_json::Value = codec::decode(mock::calls("host_store_data").last().unwrap()).unwrap();
+        assert_eq!(cleared["value"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn storage_errors_count_as_not_reached() {
+        mock::reply("host_load_data", Reply::Fail("storage offline".into()));
+        assert!(!CheckpointStore::default().has_reached("steve-uuid", "spawn"));
+    }
+}
@@ -0,0 +1,53 @@
+use extism_pdk::Error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Encoding id reported by `plugin_encoding`: `0` for JSON, `1` for MessagePack.
+#[cfg(not(feature = "msgpack"))]
+pub(crate) const ENCODING: u8 = 0;
+#[cfg(feature = "msgpack")]
+pub(crate) const ENCODING: u8 = 1;
+
+#[cfg(not(feature = "msgpack"))]
+pub(crate) fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Error> {
+    Ok(serde_json::from_slice(data)?)
+}
+
+#[cfg(feature = "msgpack")]
+pub(crate) fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Error> {
+    Ok(rmp_serde::from_slice(data)?)
+}
+
+#[cfg(not(feature = "msgpack"))]
+pub(crate) fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    Ok(serde_json::to_vec(value)?)
+}
+
+#[cfg(feature = "msgpack")]
+pub(crate) fn encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    Ok(rmp_serde::to_vec_named(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        name: String,
+        count: i32,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let sample = Sample { name: "stone".into(), count: 64, tags: vec!["ore".into()] };
+        assert_eq!(decode::<Sample>(&encode(&sample).unwrap()).unwrap(), sample);
+    }
+
+    #[test]
+    fn garbage_fails_to_decode() {
+        assert!(decode::<Sample>(&[0xC1, 0xFF]).is_err());
+    }
+}
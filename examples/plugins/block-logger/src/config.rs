@@ -0,0 +1,51 @@
+use crate::host;
+use extism_pdk::*;
+use serde::de::DeserializeOwned;
+
+pub(crate) fn load_config<T: DeserializeOwned>() -> Result<T, Error> {
+    let data = unsafe { host::host_get_config()? };
+    let text = std::str::from_utf8(&data)?;
+    Ok(toml::from_str(text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::mock::{self, Reply};
+
+    const FIXTURE: &str = r#"
+protected_blocks = ["minecraft:*_ore", "!minecraft:coal_ore"]
+max_fire_spread_y = 96
+fall_damage_multiplier = 0.5
+
+[spawn_replacements]
+"minecraft:phantom" = "minecraft:bat"
+
+[dimensions]
+mode = "allowlist"
+list = ["overworld"]
+"#;
+
+    #[test]
+    fn fixture_fills_the_plugin_config() {
+        mock::reply("host_get_config", Reply::Bytes(FIXTURE.into()));
+        let cfg: crate::PluginConfig = load_config().unwrap();
+        assert_eq!(cfg.protected_blocks, ["minecraft:*_ore", "!minecraft:coal_ore"]);
+        assert_eq!((cfg.max_fire_spread_y, cfg.fall_damage_multiplier, cfg.downgrade_thunder), (96, 0.5, false));
+        assert_eq!(cfg.spawn_replacements["minecraft:phantom"], "minecraft:bat");
+        assert!(cfg.dimensions.allows("overworld") && !cfg.dimensions.allows("nether"));
+    }
+
+    #[test]
+    fn the_packaged_config_parses() {
+        mock::reply("host_get_config", Reply::Bytes(include_bytes!("../config.toml").to_vec()));
+        let cfg: crate::PluginConfig = load_config().unwrap();
+        assert!(cfg.protected_blocks.iter().any(|b| b == "minecraft:spawner"));
+    }
+
+    #[test]
+    fn invalid_toml_is_an_error() {
+        mock::reply("host_get_config", Reply::Bytes(b"protected_blocks = [".to_vec()));
+        assert!(load_config::<crate::PluginConfig>().is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::codec;
+use crate::error::host_rejected;
+use crate::host;
+use crate::parse_host_response;
+use extism_pdk::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Difficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl TryFrom<&str> for Difficulty {
+    type Error = Error;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        match name.to_ascii_lowercase().as_str() {
+            "peaceful" => Ok(Difficulty::Peaceful),
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            other => Err(Error::msg(format!("unknown difficulty: {other}"))),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DifficultyRequest<'a> {
+    dimension: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    difficulty: Option<Difficulty>,
+}
+
+#[derive(Deserialize)]
+struct DifficultyResponse {
+    difficulty: String,
+}
+
+pub(crate) fn get_difficulty(dimension: &str) -> Result<Difficulty, Error> {
+    let req = DifficultyRequest { dimension, difficulty: None };
+    let res = unsafe { host::host_get_difficulty(&codec::encode(&req)?)? };
+    let res: DifficultyResponse = parse_host_response(&res)?;
+    Difficulty::try_from(res.difficulty.as_str())
+}
+
+pub(crate) fn set_difficulty(dimension: &str, difficulty: Difficulty) -> Result<(), Error> {
+    let req = DifficultyRequest { dimension, difficulty: Some(difficulty) };
+    match unsafe { host::host_set_difficulty(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected(format!("difficulty {difficulty:?}"), code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::mock::{self, Reply};
+
+    const ALL: [(&str, Difficulty); 4] = [("peaceful", Difficulty::Peaceful), ("easy", Difficulty::Easy), ("normal", Difficulty::Normal), ("hard", Difficulty::Hard)];
+
+    #[test]
+    fn every_variant_parses_and_round_trips() {
+        for (name, difficulty) in ALL {
+            assert_eq!(Difficulty::try_from(name).unwrap(), difficulty);
+            assert_eq!(Difficulty::try_from(name.to_ascii_uppercase().as_str()).unwrap(), difficulty);
+            mock::reply("host_get_difficulty", Reply::Bytes(codec::encode(&serde_json::json!({ "difficulty": name })).unwrap()));
+            assert_eq!(get_difficulty("overworld").unwrap(), difficulty);
+        }
+        assert!(Difficulty::try_from("nightmare").is_err());
+    }
+
+    #[test]
+    fn set_difficulty_sends_the_name() {
+        mock::reply("host_set_difficulty", Reply::Code(1));
+        for (_, difficulty) in ALL {
+            set_difficulty("nether", difficulty).unwrap();
+        }
+        let sent: Vec<serde_json::Value> = mock::calls("host_set_difficulty").iter().map(|c| codec::decode(c).unwrap()).collect();
+        assert_eq!(sent[3], serde_json::json!({ "dimension": "nether", "difficulty": "hard" }));
+        assert!(sent.iter().zip(ALL).all(|(req, (name, _))| req["difficulty"] == name));
+        mock::reply("host_set_difficulty", Reply::Code(0));
+        assert!(set_difficulty("nether", Difficulty::Easy).unwrap_err().to_string().contains("difficulty Easy"));
+    }
+}
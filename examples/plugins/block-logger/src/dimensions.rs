@@ -0,0 +1,75 @@
+use serde::{Deserialize, Deserializer};
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+thread_local! {
+    static REGISTRY: RefCell<DimensionRegistry> = RefCell::new(DimensionRegistry::default());
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DimensionMode {
+    /// Only the listed dimensions are protected.
+    Allowlist,
+    /// Every dimension except the listed ones is protected.
+    #[default]
+    Blocklist,
+}
+
+/// Which dimensions protection applies in. The default, an empty blocklist, protects every dimension.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct DimensionRegistry {
+    #[serde(rename = "list", deserialize_with = "lowercase_set")]
+    pub(crate) allowed: HashSet<String>,
+    pub(crate) mode: DimensionMode,
+}
+
+impl DimensionRegistry {
+    pub(crate) fn allows(&self, dimension: &str) -> bool {
+        let listed = self.allowed.contains(&dimension.to_ascii_lowercase());
+        match self.mode {
+            DimensionMode::Allowlist => listed,
+            DimensionMode::Blocklist => !listed,
+        }
+    }
+}
+
+fn lowercase_set<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashSet<String>, D::Error> {
+    let names = Vec::<String>::deserialize(deserializer)?;
+    Ok(names.into_iter().map(|n| n.to_ascii_lowercase()).collect())
+}
+
+pub(crate) fn set_registry(registry: DimensionRegistry) {
+    REGISTRY.with(|r| *r.borrow_mut() = registry);
+}
+
+/// Whether block protection and region rules apply in `dimension`; the router
+/// also skips world-scoped events from dimensions this rejects.
+pub(crate) fn protects(dimension: &str) -> bool {
+    REGISTRY.with(|r| r.borrow().allows(dimension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(toml: &str) -> DimensionRegistry {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn allowlist_excludes_the_nether() {
+        let r = registry("mode = \"allowlist\"\nlist = [\"Overworld\"]");
+        assert!(r.allows("overworld"));
+        assert!(!r.allows("nether"));
+    }
+
+    #[test]
+    fn blocklist_protects_everything_else() {
+        let r = registry("list = [\"nether\"]");
+        assert!(!r.allows("Nether"));
+        assert!(r.allows("end"));
+        assert!(DimensionRegistry::default().allows("nether"));
+    }
+}
@@ -0,0 +1,168 @@
+use extism_pdk::Error;
+use serde::Serialize;
+use std::fmt;
+
+/// First byte of a `handle_event` result that reports a plugin error instead of a cancel flag.
+pub(crate) const ERROR_RESULT_MAGIC: u8 = 0xFF;
+
+/// Why an event could not be handled. Each variant maps to a stable `error_code` for the host.
+#[derive(Debug)]
+pub(crate) enum PluginError {
+    /// A JSON value inside a handler could not be (de)serialized.
+    Deserialize(serde_json::Error),
+    /// A host function answered with a failure status code.
+    HostCall { call: String, code: i64 },
+    /// The event decoded but its contents make no sense, e.g. a negative stack size.
+    InvalidPayload(String),
+    /// Anything else that went wrong inside a handler, including failed host wrappers.
+    Logic(String),
+    /// The `handle_event` input is not an envelope of event type and payload.
+    MalformedEnvelope,
+    /// The envelope named `event` but its payload does not decode to that event.
+    Decode { event: String, source: Error },
+    /// The handler registered for `event` failed; `source` says why.
+    Handler { event: String, source: Box<PluginError> },
+    /// The handler's result could not be encoded for the host.
+    Encode(Error),
+}
+
+impl PluginError {
+    pub(crate) fn error_code(&self) -> u32 {
+        match self {
+            PluginError::MalformedEnvelope => 1,
+            PluginError::Decode { .. } => 2,
+            PluginError::Logic(_) => 3,
+            PluginError::Encode(_) => 4,
+            PluginError::Deserialize(_) => 5,
+            PluginError::HostCall { .. } => 6,
+            PluginError::InvalidPayload(_) => 7,
+            PluginError::Handler { source, .. } => source.error_code(),
+        }
+    }
+
+    /// Encodes the error as `0xFF` followed by an `ErrorResult` in the plugin's encoding.
+    pub(crate) fn to_output(&self) -> Vec<u8> {
+        let res = ErrorResult { error_code: self.error_code(), message: self.to_string() };
+        let mut out = vec![ERROR_RESULT_MAGIC];
+        if let Ok(body) = crate::codec::encode(&res) {
+            out.extend(body);
+        }
+        out
+    }
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginError::Deserialize(source) => write!(f, "invalid JSON: {source}"),
+            PluginError::HostCall { call, code } => write!(f, "host rejected {call} with code {code}"),
+            PluginError::InvalidPayload(msg) => write!(f, "invalid payload: {msg}"),
+            PluginError::Logic(msg) => f.write_str(msg),
+            PluginError::MalformedEnvelope => write!(f, "malformed event envelope"),
+            PluginError::Decode { event, source } => write!(f, "{event}: invalid payload: {source}"),
+            PluginError::Handler { event, source } => write!(f, "{event}: {source}"),
+            PluginError::Encode(source) => write!(f, "failed to encode event result: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for PluginError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PluginError::Deserialize(source) => Some(source),
+            PluginError::Handler { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for PluginError {
+    fn from(e: serde_json::Error) -> Self {
+        PluginError::Deserialize(e)
+    }
+}
+
+/// Lets handlers keep using `?` on the host wrappers, which return `extism_pdk::Error`.
+impl From<Error> for PluginError {
+    fn from(e: Error) -> Self {
+        let e = match e.downcast::<HostCallError>() {
+            Ok(HostCallError { call, code }) => return PluginError::HostCall { call, code },
+            Err(e) => e,
+        };
+        match e.downcast::<serde_json::Error>() {
+            Ok(json) => PluginError::Deserialize(json),
+            Err(e) => PluginError::Logic(e.to_string()),
+        }
+    }
+}
+
+/// The error host wrappers return when a host function answers with a status
+/// code they do not accept; it becomes `PluginError::HostCall` in handlers.
+#[derive(Debug)]
+pub(crate) struct HostCallError {
+    call: String,
+    code: i64,
+}
+
+impl fmt::Display for HostCallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "host rejected {} with code {}", self.call, self.code)
+    }
+}
+
+impl std::error::Error for HostCallError {}
+
+pub(crate) fn host_rejected(call: impl Into<String>, code: i64) -> Error {
+    Error::new(HostCallError { call: call.into(), code })
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ErrorResult {
+    pub(crate) error_code: u32,
+    pub(crate) message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_rejection_becomes_host_call() {
+        let err = PluginError::from(host_rejected("kick", -1));
+        assert!(matches!(&err, PluginError::HostCall { call, code: -1 } if call == "kick"));
+        assert_eq!(err.error_code(), 6);
+        assert_eq!(err.to_string(), "host rejected kick with code -1");
+    }
+
+    #[test]
+    fn other_host_errors_become_logic() {
+        assert!(matches!(PluginError::from(Error::msg("boom")), PluginError::Logic(msg) if msg == "boom"));
+    }
+
+    #[test]
+    fn error_output_starts_with_magic() {
+        let out = PluginError::MalformedEnvelope.to_output();
+        assert_eq!(out[0], ERROR_RESULT_MAGIC);
+        let body: serde_json::Value = crate::codec::decode(&out[1..]).unwrap();
+        assert_eq!(body["error_code"], 1);
+    }
+
+    #[test]
+    fn every_variant_displays_its_context() {
+        let json = || serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let json_msg = json().to_string();
+        let cases = [
+            (PluginError::Deserialize(json()), format!("invalid JSON: {json_msg}")),
+            (PluginError::HostCall { call: "set_block".into(), code: -2 }, "host rejected set_block with code -2".into()),
+            (PluginError::InvalidPayload("negative stack".into()), "invalid payload: negative stack".into()),
+            (PluginError::Logic("player offline".into()), "player offline".into()),
+            (PluginError::MalformedEnvelope, "malformed event envelope".into()),
+            (PluginError::Decode { event: "block_break".into(), source: Error::msg("missing field `block`") }, "block_break: invalid payload: missing field `block`".into()),
+            (PluginError::Handler { event: "block_break".into(), source: Box::new(PluginError::Logic("boom".into())) }, "block_break: boom".into()),
+            (PluginError::Encode(Error::msg("too deep")), "failed to encode event result: too deep".into()),
+        ];
+        for (err, expected) in cases {
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::codec;
+use crate::error::host_rejected;
+use crate::host;
+use crate::parse_host_response;
+use extism_pdk::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum GameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl GameMode {
+    /// The numeric id `host_set_player_gamemode` expects.
+    fn id(self) -> i32 {
+        match self {
+            GameMode::Survival => 0,
+            GameMode::Creative => 1,
+            GameMode::Adventure => 2,
+            GameMode::Spectator => 3,
+        }
+    }
+}
+
+impl TryFrom<&str> for GameMode {
+    type Error = Error;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        match name.to_ascii_lowercase().as_str() {
+            "survival" => Ok(GameMode::Survival),
+            "creative" => Ok(GameMode::Creative),
+            "adventure" => Ok(GameMode::Adventure),
+            "spectator" => Ok(GameMode::Spectator),
+            other => Err(Error::msg(format!("unknown game mode: {other}"))),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GameModeRequest<'a> {
+    player_uuid: &'a str,
+}
+
+#[derive(Serialize)]
+struct SetGameModeRequest<'a> {
+    player_uuid: &'a str,
+    gamemode: i32,
+}
+
+#[derive(Deserialize)]
+struct GameModeResponse {
+    game_mode: GameMode,
+}
+
+pub(crate) fn get_gamemode(uuid: &str) -> Result<GameMode, Error> {
+    let req = GameModeRequest { player_uuid: uuid };
+    let res = unsafe { host::host_get_gamemode(&codec::encode(&req)?)? };
+    parse_host_response::<GameModeResponse>(&res).map(|r| r.game_mode)
+}
+
+pub(crate) fn set_gamemode(uuid: &str, mode: GameMode) -> Result<(), Error> {
+    let req = SetGameModeRequest { player_uuid: uuid, gamemode: mode.id() };
+    match unsafe { host::host_set_player_gamemode(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected(format!("game mode {mode:?}"), code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::mock::{self, Reply};
+
+    #[test]
+    fn names_map_to_modes() {
+        assert_eq!(GameMode::try_from("creative").unwrap(), GameMode::Creative);
+        assert_eq!(GameMode::try_from("Spectator").unwrap(), GameMode::Spectator);
+        assert!(GameMode::try_from("hardcore").is_err());
+    }
+
+    #[test]
+    fn modes_are_sent_by_id() {
+        mock::reply("host_set_player_gamemode", Reply::Code(1));
+        set_gamemode("steve-uuid", GameMode::Creative).unwrap();
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_set_player_gamemode")[0]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "player_uuid": "steve-uuid", "gamemode": 1 }));
+        assert_eq!([GameMode::Survival, GameMode::Adventure, GameMode::Spectator].map(GameMode::id), [0, 2, 3]);
+    }
+}
@@ -0,0 +1,107 @@
+use extism_pdk::*;
+
+#[cfg(target_arch = "wasm32")]
+include!(concat!(env!("OUT_DIR"), "/host.rs"));
+#[cfg(not(target_arch = "wasm32"))]
+include!(concat!(env!("OUT_DIR"), "/host_stubs.rs"));
+
+#[cfg(all(not(target_arch = "wasm32"), not(test)))]
+fn stub<R>(name: &str, _data: &[u8]) -> Result<R, Error> {
+    Err(Error::msg(format!("{name} is only available inside the host")))
+}
+
+#[cfg(all(not(target_arch = "wasm32"), test))]
+use mock::call as stub;
+
+/// A stand-in host for unit tests: each host function answers with the reply
+/// queued for it on the current thread and records every payload it is sent.
+#[cfg(all(not(target_arch = "wasm32"), test))]
+pub(crate) mod mock {
+    use extism_pdk::Error;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Clone)]
+    pub(crate) enum Reply {
+        Bytes(Vec<u8>),
+        Code(i64),
+        Fail(String),
+    }
+
+    thread_local! {
+        static REPLIES: RefCell<HashMap<&'static str, Reply>> = RefCell::new(HashMap::new());
+        static CALLS: RefCell<Vec<(&'static str, Vec<u8>)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub(crate) trait FromReply: Sized {
+        fn from_reply(reply: Reply) -> Option<Self>;
+    }
+
+    impl FromReply for Vec<u8> {
+        fn from_reply(reply: Reply) -> Option<Self> {
+            match reply {
+                Reply::Bytes(bytes) => Some(bytes),
+                _ => None,
+            }
+        }
+    }
+
+    impl FromReply for i64 {
+        fn from_reply(reply: Reply) -> Option<Self> {
+            match reply {
+                Reply::Code(code) => Some(code),
+                _ => None,
+            }
+        }
+    }
+
+    impl FromReply for u64 {
+        fn from_reply(reply: Reply) -> Option<Self> {
+            match reply {
+                Reply::Code(code) => u64::try_from(code).ok(),
+                _ => None,
+            }
+        }
+    }
+
+    impl FromReply for () {
+        fn from_reply(_: Reply) -> Option<Self> {
+            Some(())
+        }
+    }
+
+    /// Functions without a queued reply fail as they would outside the host.
+    pub(crate) fn call<R: FromReply>(name: &'static str, data: &[u8]) -> Result<R, Error> {
+        CALLS.with(|c| c.borrow_mut().push((name, data.to_vec())));
+        match REPLIES.with(|r| r.borrow().get(name).cloned()) {
+            Some(Reply::Fail(msg)) => Err(Error::msg(msg)),
+            Some(reply) => R::from_reply(reply.clone()).ok_or_else(|| Error::msg(format!("mock reply {reply:?} does not fit {name}"))),
+            None => Err(Error::msg(format!("{name} is only available inside the host"))),
+        }
+    }
+
+    /// Makes every later call to `name` on this thread answer with `reply`.
+    pub(crate) fn reply(name: &'static str, reply: Reply) {
+        REPLIES.with(|r| r.borrow_mut().insert(name, reply));
+    }
+
+    /// The payloads sent to `name` on this thread, oldest first.
+    pub(crate) fn calls(name: &str) -> Vec<Vec<u8>> {
+        CALLS.with(|c| c.borrow().iter().filter(|(n, _)| *n == name).map(|(_, data)| data.clone()).collect())
+    }
+
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn replies_are_typed_per_function() {
+            reply("host_kick_player", Reply::Code(-3));
+            reply("host_get_server_info", Reply::Fail("offline".into()));
+            assert_eq!(call::<i64>("host_kick_player", b"{}").unwrap(), -3);
+            assert_eq!(call::<Vec<u8>>("host_get_server_info", &[]).unwrap_err().to_string(), "offline");
+            assert!(call::<Vec<u8>>("host_kick_player", &[]).is_err());
+            assert!(call::<i64>("host_ban_player", &[]).unwrap_err().to_string().contains("only available inside the host"));
+            assert_eq!(calls("host_kick_player"), [b"{}".to_vec(), Vec::new()]);
+        }
+    }
+}
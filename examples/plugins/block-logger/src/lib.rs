@@ -1,33 +1,323 @@
 use extism_pdk::*;
-use serde::{Deserialize, Serialize};
-use std::cell::RefCell;
+use serde::de::{self, DeserializeOwned};
+use dragonfly_wasm_macros::PluginEvent;
+use error::{host_rejected, PluginError};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::sync::LazyLock;
+use text::{Color, FormattedText};
+
+mod advancement;
+#[doc(hidden)]
+pub mod bench;
+mod bossbar;
+mod checkpoint;
+mod codec;
+mod config;
+mod difficulty;
+mod dimensions;
+mod error;
+mod gamemode;
+mod host;
+mod logger;
+#[cfg(feature = "loot")]
+mod loot;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod permission;
+mod protection;
+mod rate_limiter;
+#[cfg(feature = "regions")]
+mod region;
+mod router;
+mod scoreboard;
+mod storage;
+mod text;
+mod throttle;
+mod timeseries;
+mod title;
+mod util;
 
 thread_local! {
     static STATS: RefCell<HashMap<String, Stats>> = RefCell::new(HashMap::new());
+    static CONFIG: RefCell<PluginConfig> = RefCell::new(PluginConfig::default());
+    static TASKS: RefCell<HashMap<u32, ScheduledTask>> = RefCell::new(HashMap::new());
+    static NEXT_TASK_ID: Cell<u32> = const { Cell::new(1) };
+    static STARTED_AT_MS: Cell<Option<u64>> = const { Cell::new(None) };
+    static EVENT_COUNT: Cell<u64> = const { Cell::new(0) };
+    static ERROR_COUNT: Cell<u64> = const { Cell::new(0) };
+    static RATES: RefCell<HashMap<String, timeseries::TimeSeries>> = RefCell::new(HashMap::new());
+    #[cfg(feature = "metrics")]
+    static METRICS: RefCell<metrics::Metrics> = RefCell::new(metrics::Metrics::default());
+    static NOTIFY_LIMITER: RefCell<rate_limiter::RateLimiter> = RefCell::new(rate_limiter::RateLimiter::new(NOTIFY_BURST, NOTIFY_REFILL_PER_SEC));
+    static ROUTER: router::TypedEventRouter = event_router();
+    static NOTIFY_COOLDOWN: RefCell<util::Cooldown> = RefCell::new(util::Cooldown::new(NOTIFY_COOLDOWN_MS));
+    static SLEEPING: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    /// Where each online player last stood on the ground, so a void death can respawn them there.
+    static LAST_POSITION: RefCell<HashMap<String, Position>> = RefCell::new(HashMap::new());
+    static NOTIFY_THROTTLE: RefCell<throttle::NotificationThrottler> = RefCell::new(throttle::NotificationThrottler::new(NOTIFY_BATCH_MS));
 }
 
-const PROTECTED_BLOCKS: &[&str] = &[
-    "minecraft:diamond_ore",
-    "minecraft:deepslate_diamond_ore",
-    "minecraft:ancient_debris",
-    "minecraft:spawner",
+static LOGGER: LazyLock<logger::PluginLogger> = LazyLock::new(|| logger::PluginLogger::new(logger::LogLevel::Info, "block-logger"));
+
+const STATS_SUMMARY_TICKS: u64 = 6000;
+#[cfg(feature = "metrics")]
+const METRIC_BREAKS: &str = "blocklogger_block_break_total";
+#[cfg(feature = "metrics")]
+const METRIC_BREAKS_DENIED: &str = "blocklogger_block_break_denied_total";
+#[cfg(feature = "metrics")]
+const METRIC_PLACES: &str = "blocklogger_block_place_total";
+#[cfg(feature = "metrics")]
+const METRIC_TRACKED_PLAYERS: &str = "blocklogger_tracked_players";
+const STATS_NAMESPACE: &str = "stats";
+const PROTECTION_NAMESPACE: &str = "protection";
+const DYNAMIC_PROTECTED_KEY: &str = "dynamic";
+const SCOREBOARD_BROKEN: &str = "blocks_broken";
+const NOTIFY_COOLDOWN_MS: u64 = 30_000;
+const NOTIFY_BURST: u32 = 5;
+const NOTIFY_REFILL_PER_SEC: u32 = 1;
+const NOTIFY_BATCH_MS: u64 = 500;
+const NOTIFY_FLUSH_TICKS: u64 = 10;
+const RATE_BUCKET_MS: u64 = 10_000;
+const RATE_WINDOWS: usize = 6;
+
+/// Hosts should only fire `player_move` once a player has moved farther than this many blocks.
+const MOVEMENT_THRESHOLD: f64 = 1.0;
+const MAX_STACK_SIZE: i32 = 64;
+const MAX_ENCHANT_LEVEL: u8 = 5;
+/// Share of the experience a grindstone refunds that the plugin keeps, in percent.
+const GRINDSTONE_XP_TAX_PERCENT: i32 = 50;
+/// Amplifiers are zero-based, so this caps effects at level V like enchantments.
+const MAX_EFFECT_AMPLIFIER: u8 = 4;
+/// Highest Riptide level obtainable in vanilla.
+const MAX_RIPTIDE_LEVEL: u8 = 3;
+const SPAWN_CAP_RADIUS: f32 = 16.0;
+const SPAWN_CAP_PER_TYPE: usize = 24;
+/// Default for `max_fire_spread_y`; fire never spreads above this height.
+const MAX_FIRE_SPREAD_Y: i32 = 128;
+const SPREAD_FIRE: &str = "fire";
+/// Blocks a player can fall without taking damage.
+const SAFE_FALL_DISTANCE: f32 = 3.0;
+/// Note blocks play 25 notes, F#3 (0) to F#5 (24).
+const MAX_NOTE: u8 = 24;
+const DEFAULT_INSTRUMENT: &str = "harp";
+const INSTRUMENTS: [&str; 16] = [
+    "harp", "basedrum", "snare", "hat", "bass", "flute", "bell", "guitar", "chime", "xylophone", "iron_xylophone", "cow_bell", "didgeridoo", "bit", "banjo", "pling",
 ];
+const CONTRABAND_ITEM: &str = "minecraft:spawner";
+#[cfg(feature = "loot")]
+/// Breaking one awards `spawner_loot` wherever the break is allowed, e.g. with the bypass permission.
+const SPAWNER_BLOCK: &str = "minecraft:spawner";
+#[cfg(feature = "regions")]
+const LAVA: &str = "minecraft:lava";
+#[cfg(feature = "regions")]
+/// What lava flowing into a `no_lava` region becomes.
+const WATER: &str = "minecraft:water";
+const REDACTED_LINE: &str = "§8[redacted]";
+const DENY_PARTICLE: &str = "minecraft:block";
+/// Colour `/blockadmin fog` tints the fog with.
+const FOG_COLOR: [u8; 3] = [192, 216, 255];
+const PERM_BYPASS: &str = "blocklogger.bypass";
+const PERM_ADMIN: &str = "blocklogger.admin";
+const ADMIN_USAGE: &str = "usage: /blockadmin <block|setblock|run|where|border|time|kick|gamemode|difficulty|fog|send|grant|entities|region|resetstats|checkpoint> ...";
+/// Blocks a player must break for the milestone broadcast, title and reward.
+const MILESTONE_BLOCKS: u64 = 1000;
+const MILESTONE_BAR_PREFIX: &str = "blocklogger:milestone:";
+const MILESTONE_SOUND: &str = "block.note_block.pling";
+const MILESTONE_EXP: i32 = 10;
+/// The most experience a single `player_exp` event may award.
+const MAX_EXP_GAIN: i32 = 1000;
+/// Share of online players, in percent, that must be asleep to skip the night.
+const SLEEP_QUORUM_PERCENT: usize = 50;
+const CHANNEL_STATS: &str = "com.example.blockprotect:stats";
+/// Answers with the stats as a JSON text component, for bridges to clients that render them.
+const CHANNEL_STATS_TEXT: &str = "com.example.blockprotect:stats_text";
+const BAN_AFTER_DENIALS: u64 = 10;
+const CHECKPOINT_FIRST_DENY: &str = "first_protected_deny";
+const ADVANCEMENT_FIRST_DENY: &str = "blocklogger:first_protected_block";
+const KICK_REASON: &str = "Kicked by an administrator";
+const BAN_REASON: &str = "Repeatedly breaking protected blocks";
+
+const DEFAULT_DIMENSION: &str = "overworld";
+const VOID_DEATH_CAUSE: &str = "void";
+/// Build limits a transfer may enter at; anything outside is sent back.
+const WORLD_MIN_Y: i32 = -64;
+const WORLD_MAX_Y: i32 = 319;
+const TICKS_PER_SECOND: u64 = 20;
+const TICKS_PER_DAY: u64 = 24000;
+const MIDDAY_TICKS: u64 = 6000;
+const NIGHT_START_TICKS: u64 = 13000;
+const NIGHT_END_TICKS: u64 = 23000;
+
+fn event_router() -> router::TypedEventRouter {
+    let mut router = router::TypedEventRouter::new();
+    router
+        .register_event::<BlockBreakEvent>()
+        .register_event::<BlockPlaceEvent>()
+        .register_event::<PlayerJoinEvent>()
+        .register_event::<PlayerQuitEvent>()
+        .register_event::<PlayerDeathEvent>()
+        .register_event::<PlayerChatEvent>()
+        .register_event::<BookWriteEvent>()
+        .register_event::<PlayerBanEvent>()
+        .register_event::<PlayerExpEvent>()
+        .register_event::<SleepEvent>()
+        .register_event::<FogEvent>()
+        .register_event::<FallDamageEvent>()
+        .register_event::<EntitySpawnEvent>()
+        .register_event::<PlayerMoveEvent>()
+        .register_event::<DimensionTransferEvent>()
+        .register_event::<PlayerInteractEvent>()
+        .register_event::<ProjectileHitEvent>()
+        .register_event::<TridentThrowEvent>()
+        .register_event::<InventoryChangeEvent>()
+        .register_event::<ItemDropEvent>()
+        .register_event::<ItemPickupEvent>()
+        .register_event::<VillagerTradeEvent>()
+        .register_event::<ContainerOpenEvent>()
+        .register_event::<ContainerCloseEvent>()
+        .register_event::<BlockExplodeEvent>()
+        .register_event::<BlockSpreadEvent>()
+        .register_event::<NoteBlockEvent>()
+        .register_event::<WorldBorderEvent>()
+        .register_event::<CropGrowEvent>()
+        .register_event::<FurnaceEvent>()
+        .register_event::<EnchantItemEvent>()
+        .register_event::<GrindstoneEvent>()
+        .register_event::<WeatherChangeEvent>()
+        .register_event::<EntityDamageEvent>()
+        .register_event::<PotionEffectEvent>()
+        .register_event::<EntityModifyEvent>()
+        .register_event::<SheepDyeEvent>()
+        .register_event::<FishingEvent>()
+        .register_event::<VehicleEvent>()
+        .register_event::<CraftItemEvent>()
+        .register_event::<PortalCreateEvent>();
+    // These handlers only enforce region rules.
+    #[cfg(feature = "regions")]
+    router
+        .register_event::<LiquidFlowEvent>()
+        .register_event::<PistonExtendEvent>()
+        .register_event::<PistonRetractEvent>();
+    router
+}
+
+
+#[derive(Debug, Serialize)]
+struct PluginManifest {
+    name: String,
+    version: String,
+    author: String,
+    description: String,
+    events: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct HealthReport {
+    status: String,
+    uptime_ms: u64,
+    event_count: u64,
+    error_count: u64,
+    memory_usage_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventPriority {
+    Monitor,
+    Lowest,
+    Low,
+    Normal,
+    High,
+    Highest,
+}
+
+impl EventPriority {
+    pub fn value(self) -> i32 {
+        match self {
+            EventPriority::Lowest => -200,
+            EventPriority::Low => -100,
+            EventPriority::Normal => 0,
+            EventPriority::High => 100,
+            EventPriority::Highest => 200,
+            EventPriority::Monitor => 300,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct PluginConfig {
+    protected_blocks: Vec<String>,
+    #[cfg(feature = "regions")]
+    protected_liquids: Vec<String>,
+    #[cfg(feature = "regions")]
+    regions: Vec<region::Region>,
+    max_fire_spread_y: i32,
+    dimensions: dimensions::DimensionRegistry,
+    fall_damage_multiplier: f32,
+    /// Turns thunderstorms into rain of the same length.
+    downgrade_thunder: bool,
+    /// Entity types whose spawns are turned into another type, e.g. `"minecraft:phantom" = "minecraft:bat"`.
+    spawn_replacements: HashMap<String, String>,
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            protected_blocks: protection::DEFAULT_PATTERNS.iter().map(|b| b.to_string()).collect(),
+            #[cfg(feature = "regions")]
+            protected_liquids: Vec::new(),
+            #[cfg(feature = "regions")]
+            regions: Vec::new(),
+            max_fire_spread_y: MAX_FIRE_SPREAD_Y,
+            dimensions: dimensions::DimensionRegistry::default(),
+            fall_damage_multiplier: 1.0,
+            downgrade_thunder: false,
+            spawn_replacements: HashMap::new(),
+        }
+    }
+}
 
 #[derive(Debug, Deserialize)]
 struct Player {
     uuid: String,
     name: String,
+    #[serde(default)]
+    position: Vec3,
+    #[serde(default)]
+    yaw: f32,
+    #[serde(default)]
+    pitch: f32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Vec3 {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 struct Position {
     x: i32,
     y: i32,
     z: i32,
+    #[serde(default = "default_dimension")]
+    dimension: String,
 }
 
-#[derive(Debug, Deserialize)]
+impl Position {
+    fn distance_sq(&self, other: &Position) -> f64 {
+        let dx = f64::from(self.x) - f64::from(other.x);
+        let dy = f64::from(self.y) - f64::from(other.y);
+        let dz = f64::from(self.z) - f64::from(other.z);
+        dx * dx + dy * dy + dz * dz
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 struct Block {
     block_type: String,
     position: Position,
@@ -35,195 +325,3980 @@ struct Block {
     properties: HashMap<String, String>,
 }
 
-#[derive(Debug, Deserialize)]
+impl router::Dimension for Position {
+    fn dimension(&self) -> Option<&str> {
+        Some(&self.dimension)
+    }
+}
+
+impl router::Dimension for Block {
+    fn dimension(&self) -> Option<&str> {
+        Some(&self.position.dimension)
+    }
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "block_break", dimension = block)]
 struct BlockBreakEvent {
     player: Player,
     block: Block,
-    #[serde(default)]
-    drops: Vec<ItemStack>,
-    #[serde(default)]
-    experience: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "block_place", dimension = block)]
 struct BlockPlaceEvent {
     player: Player,
     block: Block,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "block_explode", dimension = epicenter)]
+struct BlockExplodeEvent {
+    blocks: Vec<Block>,
+    #[serde(default)]
+    cause: String,
+    #[serde(default)]
+    power: f32,
+    epicenter: Position,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "block_spread", dimension = target_position)]
+struct BlockSpreadEvent {
+    source: Block,
+    target_position: Position,
+    /// `"fire"`, `"mushroom"`, `"grass"`, ...
+    spread_type: String,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "note_block_play", dimension = position)]
+struct NoteBlockEvent {
+    position: Position,
+    instrument: String,
+    note: u8,
+    #[serde(default)]
+    powered: bool,
+}
+
+#[cfg(feature = "regions")]
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "piston_extend", handler = on_piston_extend, dimension = piston_position)]
+struct PistonExtendEvent {
+    piston_position: Position,
+    piston_facing: String,
+    /// Each moved block paired with the position it is pushed to.
+    #[serde(default)]
+    affected_blocks: Vec<(Block, Position)>,
+}
+
+#[cfg(feature = "regions")]
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "piston_retract", handler = on_piston_retract, dimension = piston_position)]
+struct PistonRetractEvent {
+    piston_position: Position,
+    piston_facing: String,
+    #[serde(default)]
+    affected_blocks: Vec<(Block, Position)>,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "world_border_change")]
+struct WorldBorderEvent {
+    old: WorldBorder,
+    new: WorldBorder,
+}
+
+#[cfg(feature = "regions")]
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "liquid_flow", dimension = to_position)]
+struct LiquidFlowEvent {
+    from_block: Block,
+    to_position: Position,
+    liquid_type: String,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "crop_grow", dimension = position)]
+struct CropGrowEvent {
+    block: Block,
+    #[serde(deserialize_with = "u8_from_number_or_string")]
+    new_age: u8,
+    #[serde(deserialize_with = "u8_from_number_or_string")]
+    max_age: u8,
+    position: Position,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "portal_create", dimension = dimension)]
+struct PortalCreateEvent {
+    #[serde(default)]
+    player: Option<Player>,
+    blocks: Vec<Block>,
+    portal_type: String,
+    #[serde(default = "default_dimension")]
+    dimension: String,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "player_join")]
 struct PlayerJoinEvent {
     player: Player,
 }
 
-#[derive(Debug, Deserialize)]
-struct ItemStack {
-    item_type: String,
-    count: i32,
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "player_quit")]
+struct PlayerQuitEvent {
+    player: Player,
+    #[serde(default)]
+    reason: String,
 }
 
-#[derive(Debug, Serialize, Default)]
-struct EventResult {
-    cancelled: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    modifications: Option<HashMap<String, String>>,
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "player_death")]
+struct PlayerDeathEvent {
+    victim: Player,
+    #[serde(default)]
+    killer: Option<Player>,
+    #[serde(default)]
+    cause: String,
+    #[serde(default)]
+    death_message: String,
+    #[serde(default)]
+    drop_items: bool,
 }
 
-#[derive(Debug, Clone, Default)]
-struct Stats {
-    broken: u64,
-    placed: u64,
-    denied: u64,
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "player_chat")]
+struct PlayerChatEvent {
+    player: Player,
+    message: String,
+    #[serde(default)]
+    recipients: Vec<String>,
 }
 
-#[derive(Serialize)]
-struct LogRequest {
-    level: &'static str,
-    message: String,
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "player_exp")]
+struct PlayerExpEvent {
+    player: Player,
+    /// Negative when experience is removed, e.g. by enchanting.
+    delta_exp: i32,
+    new_total: i32,
+    #[serde(default)]
+    level_change: i8,
 }
 
-#[derive(Serialize)]
-struct SendMessageRequest {
-    player_uuid: String,
-    message: String,
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "fall_damage")]
+struct FallDamageEvent {
+    player: Player,
+    fall_distance: f32,
+    damage: f32,
+    #[serde(default)]
+    on_ground: bool,
 }
 
-#[host_fn]
-extern "ExtismHost" {
-    fn host_log(data: &[u8]);
-    fn host_send_message(data: &[u8]) -> i64;
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "fog_change")]
+struct FogEvent {
+    player: Player,
+    fog_type: String,
+    density: f32,
+    /// RGB; payloads with more or fewer components are rejected during decoding.
+    color: [u8; 3],
 }
 
-fn log(level: &'static str, msg: String) {
-    let req = LogRequest { level, message: msg };
-    if let Ok(data) = serde_json::to_vec(&req) {
-        unsafe { host_log(&data) }.ok();
-    }
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "sleep_event", handler = on_sleep, dimension = bed_position)]
+struct SleepEvent {
+    player: Player,
+    bed_position: Position,
+    result: SleepResult,
 }
 
-fn notify(uuid: &str, msg: &str) {
-    let req = SendMessageRequest {
-        player_uuid: uuid.into(),
-        message: msg.into(),
-    };
-    if let Ok(data) = serde_json::to_vec(&req) {
-        unsafe { host_send_message(&data) }.ok();
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SleepResult {
+    Ok,
+    NotSafe,
+    TooFarAway,
+    NotNight,
+    ObstructedAbove,
 }
 
-fn get_stats(uuid: &str) -> Stats {
-    STATS.with(|s| s.borrow().get(uuid).cloned().unwrap_or_default())
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "player_ban")]
+struct PlayerBanEvent {
+    player: Player,
+    #[serde(default)]
+    reason: String,
+    #[serde(default)]
+    banned_by: String,
 }
 
-fn update_stats<F: FnOnce(&mut Stats)>(uuid: &str, f: F) {
-    STATS.with(|s| {
-        let mut map = s.borrow_mut();
-        let stats = map.entry(uuid.into()).or_default();
-        f(stats);
-    });
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "book_write")]
+struct BookWriteEvent {
+    player: Player,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author: String,
+    pages: Vec<String>,
 }
 
-fn is_protected(block_type: &str) -> bool {
-    let normalized = block_type.to_lowercase();
-    PROTECTED_BLOCKS.iter().any(|&b| normalized.contains(b) || normalized.ends_with(b.trim_start_matches("minecraft:")))
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "player_move", dimension = to)]
+struct PlayerMoveEvent {
+    player: Player,
+    from: Position,
+    to: Position,
+    #[serde(default)]
+    on_ground: bool,
 }
 
-#[plugin_fn]
-pub fn plugin_init() -> FnResult<()> {
-    log("info", "block protection initialized".into());
-    Ok(())
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "dimension_transfer", dimension = to_dimension)]
+struct DimensionTransferEvent {
+    player: Player,
+    from_dimension: String,
+    to_dimension: String,
+    entry_position: Position,
+    exit_position: Position,
 }
 
-#[plugin_fn]
-pub fn on_enable() -> FnResult<()> {
-    log("info", "block protection enabled".into());
-    Ok(())
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "weather_change", dimension = dimension)]
+struct WeatherChangeEvent {
+    dimension: String,
+    from_weather: String,
+    to_weather: String,
+    #[serde(default)]
+    duration_ticks: u64,
 }
 
-#[plugin_fn]
-pub fn on_disable() -> FnResult<()> {
-    log("info", "block protection disabled".into());
-    Ok(())
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "entity_spawn", dimension = position)]
+struct EntitySpawnEvent {
+    entity_id: u64,
+    entity_type: String,
+    position: Position,
+    #[serde(default)]
+    spawner: Option<String>,
 }
 
-#[plugin_fn]
-pub fn handle_event(envelope: Vec<u8>) -> FnResult<Vec<u8>> {
-    let sep = envelope.iter().position(|&b| b == 0).unwrap_or(envelope.len());
-    let event_type = std::str::from_utf8(&envelope[..sep]).unwrap_or("");
-    let payload = if sep < envelope.len() { &envelope[sep + 1..] } else { &[] };
-
-    let result = match event_type {
-        "block_break" => on_block_break(payload),
-        "block_place" => on_block_place(payload),
-        "player_join" => on_player_join(payload),
-        _ => Ok(EventResult::default()),
-    };
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PotionEffect {
+    id: String,
+    #[serde(default)]
+    amplifier: u8,
+    duration_ticks: u32,
+    #[serde(default)]
+    ambient: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum PotionEffectKind {
+    Apply,
+    Refresh,
+    Remove,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "potion_effect")]
+struct PotionEffectEvent {
+    entity_id: u64,
+    effect: PotionEffect,
+    #[serde(default)]
+    source: String,
+    event_kind: PotionEffectKind,
+}
+
+/// Catch-all for cosmetic mob changes; mob-specific events such as
+/// `sheep_dye` convert into it so one handler covers them all.
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "entity_modify")]
+struct EntityModifyEvent {
+    player: Player,
+    entity_id: u64,
+    entity_type: String,
+    #[serde(default)]
+    modifications: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "sheep_dye", handler = on_sheep_dye)]
+struct SheepDyeEvent {
+    player: Player,
+    entity_id: u64,
+    old_color: String,
+    new_color: String,
+}
 
-    let res = result.unwrap_or_default();
-    let mut out = vec![u8::from(res.cancelled)];
-    if let Some(ref mods) = res.modifications {
-        if let Ok(json) = serde_json::to_vec(mods) {
-            out.extend(json);
+impl From<SheepDyeEvent> for EntityModifyEvent {
+    fn from(ev: SheepDyeEvent) -> Self {
+        Self {
+            player: ev.player,
+            entity_id: ev.entity_id,
+            entity_type: "minecraft:sheep".into(),
+            modifications: HashMap::from([("old_color".into(), ev.old_color), (MOD_NEW_COLOR.into(), ev.new_color)]),
         }
     }
-    Ok(out)
 }
 
-fn on_block_break(data: &[u8]) -> Result<EventResult, Error> {
-    let ev: BlockBreakEvent = serde_json::from_slice(data)?;
-    let pos = &ev.block.position;
+#[derive(Debug, Deserialize)]
+struct Entity {
+    entity_id: u64,
+    entity_type: String,
+}
+
+#[derive(Serialize)]
+struct EntityListRequest {
+    x: f64,
+    y: f64,
+    z: f64,
+    radius: f32,
+    dimension: String,
+    /// Only return entities of this type when set.
+    filter: Option<String>,
+}
+
+/// An entry of `host_get_entity_list`; unlike `Entity`, the position is the block position.
+#[derive(Debug, Deserialize)]
+struct NearbyEntity {
+    id: u64,
+    entity_type: String,
+    position: Position,
+    #[serde(default)]
+    custom_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DamageCause {
+    Attack,
+    Projectile,
+    Fall,
+    Fire,
+    Lava,
+    Drowning,
+    Suffocation,
+    Starvation,
+    Poison,
+    Magic,
+    Explosion,
+    Void,
+    Lightning,
+    Wither,
+    Thorns,
+    Contact,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "entity_damage")]
+struct EntityDamageEvent {
+    victim: Entity,
+    #[serde(default)]
+    attacker: Option<Entity>,
+    damage: f32,
+    cause: DamageCause,
+    #[serde(default)]
+    knockback: Option<Vec3>,
+}
+
+#[derive(Debug)]
+enum InteractTarget {
+    Block(Block),
+    Entity(Entity),
+}
+
+impl<'de> Deserialize<'de> for InteractTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Tagged {
+            #[serde(rename = "type")]
+            kind: String,
+            #[serde(flatten)]
+            body: serde_json::Value,
+        }
 
-    if is_protected(&ev.block.block_type) {
-        update_stats(&ev.player.uuid, |s| s.denied += 1);
-        notify(
-            &ev.player.uuid,
-            &format!("§c§lProtected! §r§7{} cannot be mined.", extract_block_name(&ev.block.block_type)),
-        );
-        log("warn", format!("{} tried to break protected block {} at {},{},{}", ev.player.name, ev.block.block_type, pos.x, pos.y, pos.z));
-        return Ok(EventResult { cancelled: true, modifications: None });
+        let tagged = Tagged::deserialize(deserializer)?;
+        match tagged.kind.as_str() {
+            "block" => serde_json::from_value(tagged.body).map(InteractTarget::Block).map_err(de::Error::custom),
+            "entity" => serde_json::from_value(tagged.body).map(InteractTarget::Entity).map_err(de::Error::custom),
+            other => Err(de::Error::unknown_variant(other, &["block", "entity"])),
+        }
     }
+}
 
-    update_stats(&ev.player.uuid, |s| s.broken += 1);
-    let stats = get_stats(&ev.player.uuid);
+#[derive(Debug)]
+enum ProjectileTarget {
+    Entity(Entity),
+    Block(Block),
+    None,
+}
 
-    if stats.broken % 50 == 0 {
-        notify(&ev.player.uuid, &format!("§e{} §7blocks broken", stats.broken));
+impl<'de> Deserialize<'de> for ProjectileTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Tagged {
+            kind: String,
+            #[serde(flatten)]
+            body: serde_json::Value,
+        }
+
+        let tagged = Tagged::deserialize(deserializer)?;
+        match tagged.kind.as_str() {
+            "entity" => serde_json::from_value(tagged.body).map(ProjectileTarget::Entity).map_err(de::Error::custom),
+            "block" => serde_json::from_value(tagged.body).map(ProjectileTarget::Block).map_err(de::Error::custom),
+            "none" => Ok(ProjectileTarget::None),
+            other => Err(de::Error::unknown_variant(other, &["entity", "block", "none"])),
+        }
     }
+}
 
-    log("debug", format!("{} broke {} at {},{},{}", ev.player.name, ev.block.block_type, pos.x, pos.y, pos.z));
-    Ok(EventResult::default())
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "trident_throw")]
+struct TridentThrowEvent {
+    player: Player,
+    trident_item: ItemStack,
+    #[serde(default, deserialize_with = "null_as_default")]
+    riptide_level: u8,
+    #[serde(default, deserialize_with = "null_as_default")]
+    channeling: bool,
 }
 
-fn on_block_place(data: &[u8]) -> Result<EventResult, Error> {
-    let ev: BlockPlaceEvent = serde_json::from_slice(data)?;
-    let pos = &ev.block.position;
+/// Arrows, tridents, snowballs, ... hitting something or landing.
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "projectile_hit", dimension = position)]
+struct ProjectileHitEvent {
+    /// `None` for projectiles fired by dispensers or mobs.
+    #[serde(default)]
+    shooter: Option<Player>,
+    projectile_type: String,
+    target: ProjectileTarget,
+    position: Position,
+}
 
-    update_stats(&ev.player.uuid, |s| s.placed += 1);
-    let stats = get_stats(&ev.player.uuid);
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "player_interact")]
+struct PlayerInteractEvent {
+    player: Player,
+    target: InteractTarget,
+    #[serde(default)]
+    hand: String,
+}
 
-    if stats.placed % 50 == 0 {
-        notify(&ev.player.uuid, &format!("§e{} §7blocks placed", stats.placed));
-    }
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "inventory_change")]
+struct InventoryChangeEvent {
+    player: Player,
+    slot: i32,
+    #[serde(default)]
+    old_item: Option<ItemStack>,
+    #[serde(default)]
+    new_item: Option<ItemStack>,
+    #[serde(default)]
+    cause: String,
+}
 
-    log("debug", format!("{} placed {} at {},{},{}", ev.player.name, ev.block.block_type, pos.x, pos.y, pos.z));
-    Ok(EventResult::default())
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "item_drop", dimension = position)]
+struct ItemDropEvent {
+    player: Player,
+    item: ItemStack,
+    position: Position,
 }
 
-fn on_player_join(data: &[u8]) -> Result<EventResult, Error> {
-    let ev: PlayerJoinEvent = serde_json::from_slice(data)?;
-    let stats = get_stats(&ev.player.uuid);
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "villager_trade")]
+struct VillagerTradeEvent {
+    player: Player,
+    villager_id: u64,
+    trade: Trade,
+    profession: String,
+}
 
-    if stats.broken > 0 || stats.placed > 0 {
-        notify(
-            &ev.player.uuid,
-            &format!("§7Welcome back! §e{} §7broken, §e{} §7placed", stats.broken, stats.placed),
-        );
+#[derive(Debug, Clone, Deserialize)]
+struct Trade {
+    input_a: ItemStack,
+    #[serde(default)]
+    input_b: Option<ItemStack>,
+    output: ItemStack,
+    uses: u32,
+    max_uses: u32,
+    #[serde(default)]
+    xp: i32,
+}
+
+impl Trade {
+    fn is_exhausted(&self) -> bool {
+        self.uses >= self.max_uses
     }
+}
 
-    log("info", format!("{} joined", ev.player.name));
-    Ok(EventResult::default())
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "item_pickup")]
+struct ItemPickupEvent {
+    player: Player,
+    item: ItemStack,
+    entity_id: u64,
 }
 
-fn extract_block_name(full: &str) -> &str {
-    full.split(':').last().unwrap_or(full)
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "container_open", dimension = position)]
+struct ContainerOpenEvent {
+    player: Player,
+    container_type: String,
+    /// `None` for containers without a block, such as ender chests.
+    #[serde(default)]
+    position: Option<Position>,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "container_close")]
+struct ContainerCloseEvent {
+    player: Player,
+    container_type: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum FurnaceEventKind {
+    Started,
+    Finished,
+    Cancelled,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "furnace_event", dimension = block)]
+struct FurnaceEvent {
+    block: Block,
+    fuel: ItemStack,
+    input: ItemStack,
+    output: ItemStack,
+    event_kind: FurnaceEventKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum FishingEventKind {
+    Cast,
+    Reel,
+    Bite,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "fishing_event", dimension = hook_position)]
+struct FishingEvent {
+    player: Player,
+    hook_position: Position,
+    #[serde(default)]
+    caught: Option<ItemStack>,
+    #[serde(default)]
+    experience: i32,
+    event_kind: FishingEventKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum VehicleEventKind {
+    Mount,
+    Dismount,
+    Damage,
+    Destroy,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "vehicle_event")]
+struct VehicleEvent {
+    player: Player,
+    vehicle_type: String,
+    vehicle_id: u64,
+    event_kind: VehicleEventKind,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "craft_item")]
+struct CraftItemEvent {
+    player: Player,
+    recipe_id: String,
+    #[serde(default)]
+    ingredients: Vec<Option<ItemStack>>,
+    result: ItemStack,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ItemStack {
+    item_type: String,
+    count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Enchantment {
+    id: String,
+    level: u8,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "enchant_item")]
+struct EnchantItemEvent {
+    player: Player,
+    item: ItemStack,
+    enchantments: Vec<Enchantment>,
+    #[serde(default)]
+    cost: i32,
+}
+
+#[derive(Debug, Deserialize, PluginEvent)]
+#[event(name = "grindstone_use")]
+struct GrindstoneEvent {
+    player: Player,
+    input_a: ItemStack,
+    #[serde(default)]
+    input_b: Option<ItemStack>,
+    result: ItemStack,
+    #[serde(default)]
+    refunded_xp: i32,
+}
+
+#[derive(Debug, Serialize, Default)]
+struct EventResult {
+    cancelled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modifications: Option<serde_json::Value>,
+}
+
+impl EventResult {
+    fn cancel() -> Self {
+        Self { cancelled: true, modifications: None }
+    }
+
+    fn modify(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        let mods = self.modifications.get_or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()));
+        if let Some(map) = mods.as_object_mut() {
+            map.insert(key.into(), value.into());
+        }
+        self
+    }
+}
+
+/// Combines the results of several sub-handlers: any cancellation wins and
+/// the first result to set a modification key keeps it.
+#[derive(Debug, Default)]
+struct CancellationChain {
+    result: EventResult,
+}
+
+impl CancellationChain {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&mut self, result: EventResult) -> &mut Self {
+        self.result.cancelled |= result.cancelled;
+        match (&mut self.result.modifications, result.modifications) {
+            (Some(serde_json::Value::Object(merged)), Some(serde_json::Value::Object(mods))) => {
+                for (key, value) in mods {
+                    merged.entry(key).or_insert(value);
+                }
+            }
+            (merged @ None, mods) => *merged = mods,
+            _ => {}
+        }
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.result.cancelled
+    }
+
+    fn finalize(self) -> EventResult {
+        self.result
+    }
+}
+
+const MOD_RESPAWN_X: &str = "respawn_x";
+const MOD_RESPAWN_Y: &str = "respawn_y";
+const MOD_RESPAWN_Z: &str = "respawn_z";
+const MOD_MESSAGE: &str = "message";
+const MOD_TO_DIMENSION: &str = "to_dimension";
+const MOD_ENTRY_POSITION: &str = "entry_position";
+const MOD_RECIPIENTS: &str = "recipients";
+const MOD_ENTITY_TYPE: &str = "entity_type";
+const MOD_NEW_ITEM: &str = "new_item";
+const MOD_SURVIVOR_BLOCKS: &str = "survivor_blocks";
+const MOD_OUTPUT: &str = "output";
+const MOD_ENCHANTMENTS: &str = "enchantments";
+const MOD_DAMAGE: &str = "damage";
+const MOD_NEW_AGE: &str = "new_age";
+const MOD_NEW_COLOR: &str = "new_color";
+#[cfg(feature = "loot")]
+const MOD_CUSTOM_DROPS: &str = "custom_drops";
+const MOD_PAGES: &str = "pages";
+const MOD_AMPLIFIER: &str = "amplifier";
+const MOD_DENSITY: &str = "density";
+const MOD_NOTE: &str = "note";
+const MOD_INSTRUMENT: &str = "instrument";
+const MOD_RIPTIDE_LEVEL: &str = "riptide_level";
+const MOD_REFUNDED_XP: &str = "refunded_xp";
+const MOD_RESULT: &str = "result";
+const MOD_TO_WEATHER: &str = "to_weather";
+const MOD_DURATION_TICKS: &str = "duration_ticks";
+const MOD_CAUGHT: &str = "caught";
+const MOD_DELTA_EXP: &str = "delta_exp";
+#[cfg(feature = "regions")]
+const MOD_LIQUID_TYPE: &str = "liquid_type";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct Stats {
+    broken: u64,
+    placed: u64,
+    denied: u64,
+    kills: u64,
+    items_dropped: u64,
+    items_picked: u64,
+}
+
+#[derive(Serialize)]
+struct CommandRegisterRequest {
+    command: String,
+    description: String,
+    permission: String,
+    aliases: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct CommandEvent {
+    player: Player,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct SendMessageRequest {
+    player_uuid: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct BroadcastRequest {
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    permission: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TeleportRequest {
+    player_uuid: String,
+    x: f64,
+    y: f64,
+    z: f64,
+    yaw: f32,
+    pitch: f32,
+    dimension: String,
+}
+
+#[derive(Debug)]
+struct TeleportResult {
+    chunk_loaded: bool,
+}
+
+#[derive(Serialize)]
+struct KickRequest {
+    player_uuid: String,
+    reason: String,
+}
+
+#[derive(Serialize)]
+struct BanRequest {
+    player_uuid: String,
+    reason: String,
+    /// Unix time in milliseconds at which the ban lifts; `None` bans permanently.
+    expires_at: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct GiveItemRequest {
+    player_uuid: String,
+    item: ItemStack,
+    enchantments: Vec<Enchantment>,
+}
+
+#[derive(Serialize)]
+struct GiveExpRequest {
+    player_uuid: String,
+    amount: i32,
+}
+
+#[derive(Serialize)]
+struct RunCommandRequest {
+    command: String,
+    as_player: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunCommandResponse {
+    success: bool,
+    #[serde(default)]
+    output: String,
+}
+
+#[derive(Serialize)]
+struct ScheduleRequest {
+    callback_id: u32,
+    delay_ticks: u64,
+    repeat: bool,
+}
+
+struct ScheduledTask {
+    repeat: bool,
+    callback: Box<dyn Fn()>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerInfo {
+    mc_version: String,
+    server_software: String,
+    max_players: u32,
+    /// `false` on offline-mode servers, where player UUIDs are not verified.
+    online_mode: bool,
+    plugin_api_version: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct PluginInfo {
+    name: String,
+    version: String,
+    enabled: bool,
+}
+
+#[derive(Serialize)]
+struct PlayerLocationRequest {
+    player_uuid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PlayerLocation {
+    x: f64,
+    y: f64,
+    z: f64,
+    yaw: f32,
+    pitch: f32,
+    dimension: String,
+    on_ground: bool,
+}
+
+#[derive(Serialize)]
+struct PlaySoundRequest {
+    player_uuid: String,
+    sound: String,
+    category: String,
+    volume: f32,
+    pitch: f32,
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+#[derive(Serialize)]
+struct ParticleRequest {
+    particle: String,
+    x: f64,
+    y: f64,
+    z: f64,
+    offset_x: f32,
+    offset_y: f32,
+    offset_z: f32,
+    speed: f32,
+    count: i32,
+    players: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct PluginMessage {
+    target_plugin: String,
+    channel: String,
+    payload: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PluginMessageEnvelope {
+    source_plugin: String,
+    channel: String,
+    #[serde(default)]
+    payload: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct PlayerInventoryRequest {
+    player_uuid: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PlayerInventory {
+    #[serde(default)]
+    slots: Vec<InventorySlot>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InventorySlot {
+    index: i32,
+    #[serde(default)]
+    item: Option<ItemStack>,
+}
+
+/// Same shape as a block query; kept as its own name for the inventory wrapper.
+type BlockEntityInventoryRequest = BlockQueryRequest;
+
+#[derive(Debug, Default, Deserialize)]
+struct BlockEntityInventory {
+    size: u32,
+    #[serde(default)]
+    slots: Vec<InventorySlot>,
+}
+
+#[derive(Serialize)]
+struct FogRequest {
+    player_uuid: String,
+    fog_type: String,
+    density: f32,
+    color: [u8; 3],
+}
+
+#[derive(Serialize)]
+struct WorldTimeRequest {
+    dimension: String,
+}
+
+#[derive(Serialize)]
+struct SetWorldTimeRequest {
+    dimension: String,
+    time: u64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct WorldTimeResponse {
+    time: u64,
+    day: u64,
+}
+
+impl WorldTimeResponse {
+    fn is_midday(&self) -> bool {
+        self.time % TICKS_PER_DAY == MIDDAY_TICKS
+    }
+
+    fn is_night(&self) -> bool {
+        (NIGHT_START_TICKS..NIGHT_END_TICKS).contains(&(self.time % TICKS_PER_DAY))
+    }
+}
+
+#[derive(Serialize)]
+struct WeatherRequest {
+    dimension: String,
+}
+
+#[derive(Serialize)]
+struct WorldBorderRequest {
+    dimension: String,
+}
+
+#[derive(Serialize)]
+struct SetWorldBorderRequest<'a> {
+    dimension: String,
+    #[serde(flatten)]
+    border: &'a WorldBorder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct WorldBorder {
+    center_x: f64,
+    center_z: f64,
+    /// Side length in blocks.
+    size: f64,
+    damage_per_block: f32,
+    /// Distance outside the border a player can stand before taking damage.
+    safe_zone: f32,
+}
+
+impl WorldBorder {
+    fn validate(&self) -> Result<(), InvalidBorderError> {
+        if !(self.size.is_finite() && self.size > 0.0) {
+            return Err(InvalidBorderError::Size(self.size));
+        }
+        if !(self.center_x.is_finite() && self.center_z.is_finite()) {
+            return Err(InvalidBorderError::Center);
+        }
+        if self.damage_per_block < 0.0 || self.safe_zone < 0.0 {
+            return Err(InvalidBorderError::Negative);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct WeatherResponse {
+    thundering: bool,
+    /// `0` when the host cannot tell how long the current weather lasts.
+    #[serde(default)]
+    duration_remaining_ticks: u64,
+}
+
+impl WeatherResponse {
+    /// Seconds until the weather changes, or `None` if the host did not say.
+    fn remaining_secs(&self) -> Option<u64> {
+        (self.duration_remaining_ticks > 0).then_some(self.duration_remaining_ticks / TICKS_PER_SECOND)
+    }
+}
+
+#[derive(Serialize)]
+struct BlockQueryRequest {
+    x: i32,
+    y: i32,
+    z: i32,
+    dimension: String,
+}
+
+#[derive(Debug)]
+enum SetBlockError {
+    OutOfBounds,
+    ChunkNotLoaded,
+    Rejected(i64),
+}
+
+impl std::fmt::Display for SetBlockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SetBlockError::OutOfBounds => write!(f, "position is outside the world bounds"),
+            SetBlockError::ChunkNotLoaded => write!(f, "chunk is not loaded"),
+            SetBlockError::Rejected(code) => write!(f, "host rejected set_block with code {code}"),
+        }
+    }
+}
+
+impl std::error::Error for SetBlockError {}
+
+#[derive(Debug)]
+struct NotFoundError(String);
+
+impl std::fmt::Display for NotFoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} not found", self.0)
+    }
+}
+
+impl std::error::Error for NotFoundError {}
+
+#[derive(Debug)]
+enum InvalidBorderError {
+    Size(f64),
+    Center,
+    Negative,
+}
+
+impl std::fmt::Display for InvalidBorderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidBorderError::Size(size) => write!(f, "world border size must be positive, got {size}"),
+            InvalidBorderError::Center => write!(f, "world border center must be finite"),
+            InvalidBorderError::Negative => write!(f, "world border damage and safe zone must not be negative"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidBorderError {}
+
+#[derive(Debug)]
+struct NotAContainerError(Position);
+
+impl std::fmt::Display for NotAContainerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Position { x, y, z, dimension } = &self.0;
+        write!(f, "block at {x}, {y}, {z} in {dimension} is not a container")
+    }
+}
+
+impl std::error::Error for NotAContainerError {}
+
+/// Queues `msg` for the player. Messages sent within `NOTIFY_BATCH_MS` of each other arrive as one.
+fn notify(uuid: &str, msg: &FormattedText) {
+    let Ok(now) = now_ms() else {
+        send_message(uuid, msg.to_legacy());
+        return;
+    };
+    NOTIFY_THROTTLE.with(|t| t.borrow_mut().enqueue(uuid, msg.to_legacy(), now));
+    flush_notifications_at(now);
+}
+
+fn flush_notifications() {
+    if let Ok(now) = now_ms() {
+        flush_notifications_at(now);
+    }
+}
+
+fn flush_notifications_at(now: u64) {
+    for (uuid, message) in NOTIFY_THROTTLE.with(|t| t.borrow_mut().flush_due(now)) {
+        if !NOTIFY_LIMITER.with(|l| l.borrow_mut().check_and_consume(&uuid, 1, now)) {
+            // Past the chat limit only the latest line is shown, above the hotbar.
+            let latest = message.rsplit('\n').next().unwrap_or_default();
+            if let Err(e) = title::send_action_bar(&uuid, latest) {
+                LOGGER.debug(&format!("dropped message to {uuid}: rate limited and {e}"), None);
+            }
+            continue;
+        }
+        send_message(&uuid, message);
+    }
+}
+
+fn send_message(uuid: &str, message: String) {
+    let req = SendMessageRequest { player_uuid: uuid.into(), message };
+    if let Ok(data) = codec::encode(&req) {
+        unsafe { host::host_send_message(&data) }.ok();
+    }
+}
+
+/// Gray `label` followed by a white `value`, the plugin's usual stat styling.
+fn stat(label: &str, value: impl std::fmt::Display) -> FormattedText {
+    FormattedText::text("").append(FormattedText::text(label).color(Color::Gray)).append(FormattedText::text(&value.to_string()).color(Color::White))
+}
+
+fn stats_message(stats: &Stats) -> FormattedText {
+    FormattedText::text("")
+        .append(stat("Broken: ", stats.broken))
+        .append(stat(" Placed: ", stats.placed))
+        .append(stat(" Denied: ", stats.denied))
+        .append(stat(" Kills: ", stats.kills))
+        .append(stat(" Dropped: ", stats.items_dropped))
+        .append(stat(" Picked up: ", stats.items_picked))
+}
+
+fn highlight(value: impl std::fmt::Display, rest: &str) -> FormattedText {
+    FormattedText::text("").append(FormattedText::text(&value.to_string()).color(Color::Yellow)).append(FormattedText::text(rest).color(Color::Gray))
+}
+
+fn protected_notice(detail: &str) -> FormattedText {
+    FormattedText::text("").append(FormattedText::text("Protected! ").color(Color::Red).bold(true)).append(FormattedText::text(detail).color(Color::Gray))
+}
+
+/// Entities within `radius` blocks of the center of `pos`; a radius of `0.0` only matches entities at that exact point.
+fn get_entity_list(pos: &Position, radius: f32, filter: Option<&str>) -> Result<Vec<NearbyEntity>, Error> {
+    let req = EntityListRequest {
+        x: f64::from(pos.x) + 0.5,
+        y: f64::from(pos.y),
+        z: f64::from(pos.z) + 0.5,
+        radius,
+        dimension: pos.dimension.clone(),
+        filter: filter.map(Into::into),
+    };
+    let res = unsafe { host::host_get_entity_list(&codec::encode(&req)?)? };
+    if res.is_empty() {
+        return Ok(Vec::new());
+    }
+    parse_host_response(&res)
+}
+
+fn count_entities_of_type(pos: &Position, radius: f32, entity_type: &str) -> Result<usize, Error> {
+    Ok(get_entity_list(pos, radius, Some(entity_type))?.len())
+}
+
+fn get_block(pos: &Position) -> Result<Block, Error> {
+    let req = BlockQueryRequest { x: pos.x, y: pos.y, z: pos.z, dimension: pos.dimension.clone() };
+    let data = codec::encode(&req)?;
+    let res = unsafe { host::host_get_block(&data)? };
+    parse_host_response(&res)
+}
+
+/// Returns the tile-entity data at the position, or `Value::Null` if there is none.
+fn get_block_nbt(pos: &Position) -> Result<serde_json::Value, Error> {
+    let req = BlockQueryRequest { x: pos.x, y: pos.y, z: pos.z, dimension: pos.dimension.clone() };
+    let res = unsafe { host::host_get_block_nbt(&codec::encode(&req)?)? };
+    if res.is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    parse_host_response(&res)
+}
+
+/// Reads the contents of the chest, hopper, ... at `pos` without needing a player to open it.
+fn get_block_entity_inventory(pos: &Position) -> Result<BlockEntityInventory, Error> {
+    let req = BlockEntityInventoryRequest { x: pos.x, y: pos.y, z: pos.z, dimension: pos.dimension.clone() };
+    let res = unsafe { host::host_get_block_entity_inventory(&codec::encode(&req)?)? };
+    if res.is_empty() || res.as_slice() == b"null" {
+        return Err(NotAContainerError(pos.clone()).into());
+    }
+    parse_host_response(&res)
+}
+
+fn sign_text(nbt: &serde_json::Value) -> Option<&str> {
+    nbt.pointer("/FrontText/Text").or_else(|| nbt.get("Text")).and_then(|t| t.as_str()).filter(|t| !t.trim().is_empty())
+}
+
+fn set_block(pos: &Position, block_type: &str, properties: HashMap<String, String>) -> Result<(), Error> {
+    let req = Block { block_type: block_type.into(), position: pos.clone(), properties };
+    let data = codec::encode(&req)?;
+    match unsafe { host::host_set_block(&data)? } {
+        1 => Ok(()),
+        -1 => Err(SetBlockError::OutOfBounds.into()),
+        -2 => Err(SetBlockError::ChunkNotLoaded.into()),
+        code => Err(SetBlockError::Rejected(code).into()),
+    }
+}
+
+fn teleport(req: &TeleportRequest) -> Result<TeleportResult, Error> {
+    let data = codec::encode(req)?;
+    match unsafe { host::host_teleport_player(&data)? } {
+        1 => Ok(TeleportResult { chunk_loaded: true }),
+        2 => Ok(TeleportResult { chunk_loaded: false }),
+        code => Err(host_rejected("teleport", code)),
+    }
+}
+
+fn kick_player(player_uuid: &str, reason: &str) -> Result<(), Error> {
+    let req = KickRequest { player_uuid: player_uuid.into(), reason: reason.into() };
+    match unsafe { host::host_kick_player(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("kick", code)),
+    }
+}
+
+fn ban_player(player_uuid: &str, reason: &str, expires_at: Option<u64>) -> Result<(), Error> {
+    let req = BanRequest { player_uuid: player_uuid.into(), reason: reason.into(), expires_at };
+    match unsafe { host::host_ban_player(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("ban", code)),
+    }
+}
+
+fn broadcast(msg: &FormattedText) -> Result<i64, Error> {
+    let req = BroadcastRequest { message: msg.to_legacy(), permission: None };
+    let data = codec::encode(&req)?;
+    match unsafe { host::host_broadcast_message(&data)? } {
+        code if code > 0 => Ok(code),
+        code => Err(host_rejected("broadcast", code)),
+    }
+}
+
+fn give_item(player_uuid: &str, item: ItemStack) -> Result<i64, Error> {
+    let req = GiveItemRequest { player_uuid: player_uuid.into(), item, enchantments: Vec::new() };
+    let data = codec::encode(&req)?;
+    match unsafe { host::host_give_item(&data)? } {
+        code if code > 0 => Ok(code),
+        code => Err(host_rejected("give_item", code)),
+    }
+}
+
+fn give_exp(player_uuid: &str, amount: i32) -> Result<(), Error> {
+    let req = GiveExpRequest { player_uuid: player_uuid.into(), amount };
+    match unsafe { host::host_give_exp(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("give_exp", code)),
+    }
+}
+
+fn run_command(cmd: &str) -> Result<String, Error> {
+    let req = RunCommandRequest { command: cmd.into(), as_player: None };
+    let data = codec::encode(&req)?;
+    let res: RunCommandResponse = parse_host_response(&unsafe { host::host_run_command(&data)? })?;
+    if res.success {
+        Ok(res.output)
+    } else if res.output.is_empty() {
+        Err(Error::msg(format!("command failed: {cmd}")))
+    } else {
+        Err(Error::msg(res.output))
+    }
+}
+
+fn schedule<F: Fn() + 'static>(delay_ticks: u64, repeat: bool, callback: F) -> Result<u32, Error> {
+    let callback_id = NEXT_TASK_ID.with(|n| {
+        let id = n.get();
+        n.set(id.wrapping_add(1));
+        id
+    });
+    let req = ScheduleRequest { callback_id, delay_ticks, repeat };
+    let data = codec::encode(&req)?;
+    match unsafe { host::host_schedule_task(&data)? } {
+        1 => {
+            let task = ScheduledTask { repeat, callback: Box::new(callback) };
+            TASKS.with(|t| t.borrow_mut().insert(callback_id, task));
+            Ok(callback_id)
+        }
+        code => Err(host_rejected("schedule_task", code)),
+    }
+}
+
+fn get_player_location(player_uuid: &str) -> Result<PlayerLocation, Error> {
+    let req = PlayerLocationRequest { player_uuid: player_uuid.into() };
+    let data = codec::encode(&req)?;
+    let res = unsafe { host::host_get_player_location(&data)? };
+    if res.is_empty() {
+        return Err(Error::new(NotFoundError(format!("player {player_uuid}"))));
+    }
+    parse_host_response(&res)
+}
+
+fn get_online_players() -> Result<Vec<Player>, Error> {
+    let res = unsafe { host::host_get_online_players()? };
+    parse_host_response(&res)
+}
+
+fn list_plugins() -> Result<Vec<PluginInfo>, Error> {
+    let res = unsafe { host::host_list_plugins()? };
+    parse_host_response(&res)
+}
+
+/// One line naming every loaded plugin and its version, with disabled ones marked.
+fn plugin_summary(plugins: &[PluginInfo]) -> String {
+    if plugins.is_empty() {
+        return "no plugins loaded".into();
+    }
+    let names: Vec<String> = plugins.iter().map(|p| format!("{} {}{}", p.name, p.version, if p.enabled { "" } else { " (disabled)" })).collect();
+    format!("{} plugins loaded: {}", plugins.len(), names.join(", "))
+}
+
+fn get_server_info() -> Result<ServerInfo, Error> {
+    let res = unsafe { host::host_get_server_info()? };
+    parse_host_response(&res)
+}
+
+fn get_player_inventory(player_uuid: &str) -> Result<PlayerInventory, Error> {
+    let req = PlayerInventoryRequest { player_uuid: player_uuid.into() };
+    let res = unsafe { host::host_get_player_inventory(&codec::encode(&req)?)? };
+    parse_host_response(&res)
+}
+
+fn find_items<'a>(inventory: &'a PlayerInventory, item_type: &str) -> Vec<&'a InventorySlot> {
+    inventory.slots.iter().filter(|slot| slot.item.as_ref().is_some_and(|i| i.item_type == item_type)).collect()
+}
+
+fn play_sound(mut req: PlaySoundRequest) -> Result<(), Error> {
+    req.pitch = clamp_pitch(req.pitch);
+    match unsafe { host::host_play_sound(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("play_sound", code)),
+    }
+}
+
+fn play_sound_at(player_uuid: &str, sound: &str, pos: &Position) -> Result<(), Error> {
+    play_sound(PlaySoundRequest {
+        player_uuid: player_uuid.into(),
+        sound: sound.into(),
+        category: "master".into(),
+        volume: 1.0,
+        pitch: 1.0,
+        x: f64::from(pos.x) + 0.5,
+        y: f64::from(pos.y) + 0.5,
+        z: f64::from(pos.z) + 0.5,
+    })
+}
+
+/// Clamps `pitch` into the `0.5..=2.0` range the client plays; NaN plays at normal pitch.
+fn clamp_pitch(pitch: f32) -> f32 {
+    if pitch.is_nan() { 1.0 } else { pitch.clamp(0.5, 2.0) }
+}
+
+/// Clamps `density` into `0.0..=1.0` rather than rejecting it, logging a warning when it had to.
+fn clamp_fog_density(density: f32) -> f32 {
+    let clamped = if density.is_nan() { 0.0 } else { density.clamp(0.0, 1.0) };
+    if clamped != density {
+        LOGGER.warn(&format!("fog density {density} is outside 0.0..=1.0, using {clamped}"), None);
+    }
+    clamped
+}
+
+fn set_fog(player_uuid: &str, fog_type: &str, density: f32, color: [u8; 3]) -> Result<(), Error> {
+    let req = FogRequest { player_uuid: player_uuid.into(), fog_type: fog_type.into(), density: clamp_fog_density(density), color };
+    match unsafe { host::host_set_fog(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("set_fog", code)),
+    }
+}
+
+fn spawn_particle(particle: &str, pos: &Position, count: i32) -> Result<(), Error> {
+    let req = ParticleRequest {
+        particle: particle.into(),
+        x: f64::from(pos.x) + 0.5,
+        y: f64::from(pos.y) + 0.5,
+        z: f64::from(pos.z) + 0.5,
+        offset_x: 0.25,
+        offset_y: 0.25,
+        offset_z: 0.25,
+        speed: 0.1,
+        count,
+        players: None,
+    };
+    match unsafe { host::host_particle_effect(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("particle_effect", code)),
+    }
+}
+
+fn send_plugin_message(target_plugin: &str, channel: &str, payload: Vec<u8>) -> Result<(), Error> {
+    let msg = PluginMessage { target_plugin: target_plugin.into(), channel: channel.into(), payload };
+    match unsafe { host::host_plugin_message(&codec::encode(&msg)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected(format!("message to {target_plugin}"), code)),
+    }
+}
+
+fn get_world_time(dimension: &str) -> Result<WorldTimeResponse, Error> {
+    let req = WorldTimeRequest { dimension: dimension.into() };
+    let res = unsafe { host::host_get_world_time(&codec::encode(&req)?)? };
+    parse_host_response(&res)
+}
+
+fn get_weather(dimension: &str) -> Result<WeatherResponse, Error> {
+    let req = WeatherRequest { dimension: dimension.into() };
+    let res = unsafe { host::host_get_weather(&codec::encode(&req)?)? };
+    parse_host_response(&res)
+}
+
+fn set_world_time(dimension: &str, time: u64) -> Result<(), Error> {
+    let req = SetWorldTimeRequest { dimension: dimension.into(), time };
+    match unsafe { host::host_set_world_time(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("set_world_time", code)),
+    }
+}
+
+fn get_world_border(dimension: &str) -> Result<WorldBorder, Error> {
+    let req = WorldBorderRequest { dimension: dimension.into() };
+    let res = unsafe { host::host_get_world_border(&codec::encode(&req)?)? };
+    parse_host_response(&res)
+}
+
+/// Validates `border` before handing it to the host, so a zero-sized border never reaches the server.
+fn set_world_border(dimension: &str, border: &WorldBorder) -> Result<(), Error> {
+    border.validate()?;
+    let req = SetWorldBorderRequest { dimension: dimension.into(), border };
+    match unsafe { host::host_set_world_border(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("set_world_border", code)),
+    }
+}
+
+fn now_ms() -> Result<u64, Error> {
+    unsafe { host::host_current_time_ms() }
+}
+
+fn parse_host_response<T: DeserializeOwned>(data: &[u8]) -> Result<T, Error> {
+    let value: serde_json::Value = codec::decode(data)?;
+    if let Some(msg) = value.get("error").and_then(|e| e.as_str()) {
+        return Err(Error::msg(msg.to_string()));
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+fn get_stats(uuid: &str) -> Stats {
+    STATS.with(|s| s.borrow().get(uuid).cloned().unwrap_or_default())
+}
+
+fn update_stats<F: FnOnce(&mut Stats)>(uuid: &str, f: F) {
+    STATS.with(|s| {
+        let mut map = s.borrow_mut();
+        let stats = map.entry(uuid.into()).or_default();
+        f(stats);
+    });
+}
+
+fn persist_stats(uuid: &str) {
+    let store = storage::KvStore::<Stats>::new(STATS_NAMESPACE);
+    if let Err(e) = store.set(uuid, &get_stats(uuid)) {
+        LOGGER.warn(&format!("failed to persist stats for {uuid}: {e}"), None);
+    }
+}
+
+fn restore_stats(uuid: &str) {
+    if STATS.with(|s| s.borrow().contains_key(uuid)) {
+        return;
+    }
+    match storage::KvStore::<Stats>::new(STATS_NAMESPACE).get(uuid) {
+        Ok(Some(stats)) => STATS.with(|s| {
+            s.borrow_mut().insert(uuid.into(), stats);
+        }),
+        Ok(None) => {}
+        Err(e) => LOGGER.warn(&format!("failed to load stats for {uuid}: {e}"), None),
+    }
+}
+
+/// Gives every online player a `STATS` entry, restored from storage or zeroed.
+fn track_online_players() -> Result<(), Error> {
+    for player in get_online_players()? {
+        restore_stats(&player.uuid);
+        update_stats(&player.uuid, |_| {});
+    }
+    Ok(())
+}
+
+fn record_rate(uuid: &str, now: u64, delta: &Stats) {
+    RATES.with(|r| {
+        r.borrow_mut().entry(uuid.into()).or_insert_with(|| timeseries::TimeSeries::new(RATE_BUCKET_MS, RATE_WINDOWS)).push(now, delta);
+    });
+}
+
+#[plugin_fn]
+pub fn plugin_init() -> FnResult<()> {
+    STARTED_AT_MS.with(|s| s.set(now_ms().ok()));
+    match config::load_config::<PluginConfig>() {
+        Ok(mut cfg) => {
+            #[cfg(feature = "regions")]
+            for r in std::mem::take(&mut cfg.regions) {
+                region::add_region(r);
+            }
+            dimensions::set_registry(std::mem::take(&mut cfg.dimensions));
+            if let Err(e) = protection::set_patterns(&cfg.protected_blocks) {
+                LOGGER.warn(&format!("invalid protected block pattern, keeping defaults: {e}"), None);
+            }
+            CONFIG.with(|c| *c.borrow_mut() = cfg);
+        }
+        Err(e) => LOGGER.warn(&format!("failed to load config, using defaults: {e}"), None),
+    }
+    if let Err(e) = restore_dynamic_protected() {
+        LOGGER.warn(&format!("failed to restore runtime protected blocks: {e}"), None);
+    }
+    match get_server_info() {
+        Ok(info) => {
+            LOGGER.info(&format!("running on {} {} for up to {} players (plugin API v{})", info.server_software, info.mc_version, info.max_players, info.plugin_api_version), None);
+            if !info.online_mode {
+                LOGGER.warn("server is in offline mode; stats are keyed by unverified player UUIDs", None);
+            }
+        }
+        Err(e) => LOGGER.warn(&format!("failed to query server info: {e}"), None),
+    }
+    LOGGER.info("block protection initialized", None);
+    Ok(())
+}
+
+#[plugin_fn]
+pub fn on_enable() -> FnResult<()> {
+    if let Err(e) = schedule(STATS_SUMMARY_TICKS, true, log_stats_summary) {
+        LOGGER.warn(&format!("failed to schedule stats summary: {e}"), None);
+    }
+    if let Err(e) = schedule(NOTIFY_FLUSH_TICKS, true, flush_notifications) {
+        LOGGER.warn(&format!("failed to schedule notification flush: {e}"), None);
+    }
+    if let Err(e) = track_online_players() {
+        LOGGER.warn(&format!("failed to list online players: {e}"), None);
+    }
+    match list_plugins() {
+        Ok(plugins) => LOGGER.info(&plugin_summary(&plugins), None),
+        Err(e) => LOGGER.debug(&format!("failed to list plugins: {e}"), None),
+    }
+    let board = scoreboard::Scoreboard { name: SCOREBOARD_BROKEN.into(), display_name: "§eBlocks Broken".into(), criteria: "dummy".into() };
+    if let Err(e) = scoreboard::create(&board) {
+        LOGGER.warn(&format!("failed to create scoreboard: {e}"), None);
+    }
+    LOGGER.info("block protection enabled", None);
+    Ok(())
+}
+
+#[plugin_fn]
+pub fn on_disable() -> FnResult<()> {
+    let tracked: Vec<String> = STATS.with(|s| s.borrow().keys().cloned().collect());
+    for uuid in &tracked {
+        persist_stats(uuid);
+    }
+    if let Err(e) = scoreboard::remove(SCOREBOARD_BROKEN) {
+        LOGGER.debug(&format!("failed to remove scoreboard: {e}"), None);
+    }
+    LOGGER.info("block protection disabled", None);
+    Ok(())
+}
+
+#[plugin_fn]
+pub fn on_scheduled_task(task_id: Vec<u8>) -> FnResult<()> {
+    let id: u32 = codec::decode(&task_id)?;
+    let Some(task) = TASKS.with(|t| t.borrow_mut().remove(&id)) else {
+        LOGGER.debug(&format!("no callback registered for task {id}"), None);
+        return Ok(());
+    };
+
+    (task.callback)();
+    if task.repeat {
+        TASKS.with(|t| t.borrow_mut().insert(id, task));
+    }
+    Ok(())
+}
+
+#[plugin_fn]
+pub fn plugin_manifest() -> FnResult<Vec<u8>> {
+    let manifest = PluginManifest {
+        name: "Block Protection".into(),
+        version: env!("CARGO_PKG_VERSION").into(),
+        author: "EinBexiii".into(),
+        description: "Protects valuable blocks from being mined and tracks player statistics".into(),
+        events: ROUTER.with(|r| r.events().into_iter().map(String::from).collect()),
+    };
+    Ok(codec::encode(&manifest)?)
+}
+
+#[plugin_fn]
+pub fn add_protected_block(data: Vec<u8>) -> FnResult<()> {
+    let block: String = codec::decode(&data)?;
+    let qualified = protection::qualify(&block);
+    if !protection::is_valid_namespaced_id(&qualified) {
+        return Err(Error::msg(format!("invalid block id: {block}")).into());
+    }
+    if protection::add_dynamic(&qualified) {
+        persist_dynamic_protected()?;
+        LOGGER.info(&format!("now protecting {qualified}"), None);
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct CheckpointQuery {
+    player_uuid: String,
+    #[serde(default)]
+    checkpoint_id: Option<String>,
+}
+
+/// Returns the checkpoints the player has reached, only `checkpoint_id` when it is set.
+#[plugin_fn]
+pub fn on_checkpoint_query(data: Vec<u8>) -> FnResult<Vec<u8>> {
+    let query: CheckpointQuery = codec::decode(&data)?;
+    let mut reached = checkpoint::CheckpointStore::default().all(&query.player_uuid)?;
+    if let Some(id) = &query.checkpoint_id {
+        reached.retain(|c| &c.id == id);
+    }
+    Ok(codec::encode(&reached)?)
+}
+
+#[plugin_fn]
+pub fn remove_protected_block(data: Vec<u8>) -> FnResult<()> {
+    let block: String = codec::decode(&data)?;
+    if protection::remove_dynamic(&block) {
+        persist_dynamic_protected()?;
+        LOGGER.info(&format!("no longer protecting {}", protection::qualify(&block)), None);
+    }
+    Ok(())
+}
+
+fn persist_dynamic_protected() -> Result<(), Error> {
+    storage::KvStore::<Vec<String>>::new(PROTECTION_NAMESPACE).set(DYNAMIC_PROTECTED_KEY, &protection::dynamic_blocks())
+}
+
+fn restore_dynamic_protected() -> Result<(), Error> {
+    let blocks = storage::KvStore::<Vec<String>>::new(PROTECTION_NAMESPACE).get(DYNAMIC_PROTECTED_KEY)?;
+    protection::set_dynamic(blocks.unwrap_or_default());
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DebugDump {
+    stats: HashMap<String, Stats>,
+    protected_blocks: Vec<String>,
+    dynamic_protected: Vec<String>,
+    #[cfg(feature = "regions")]
+    regions: Vec<region::Region>,
+    sleeping_players: Vec<String>,
+    scheduled_tasks: Vec<u32>,
+    event_count: u64,
+    error_count: u64,
+}
+
+/// Snapshot of the plugin's in-memory state as JSON, for admins inspecting a running server.
+#[plugin_fn]
+pub fn plugin_debug_dump() -> FnResult<Vec<u8>> {
+    Ok(serde_json::to_vec(&debug_dump())?)
+}
+
+fn debug_dump() -> DebugDump {
+    let mut scheduled_tasks: Vec<u32> = TASKS.with(|t| t.borrow().keys().copied().collect());
+    scheduled_tasks.sort_unstable();
+    DebugDump {
+        stats: STATS.with(|s| s.borrow().clone()),
+        protected_blocks: protection::patterns(),
+        dynamic_protected: protection::dynamic_blocks(),
+        #[cfg(feature = "regions")]
+        regions: region::regions(),
+        sleeping_players: SLEEPING.with(|s| s.borrow().clone()),
+        scheduled_tasks,
+        event_count: EVENT_COUNT.with(Cell::get),
+        error_count: ERROR_COUNT.with(Cell::get),
+    }
+}
+
+#[plugin_fn]
+pub fn plugin_serialize_state() -> FnResult<Vec<u8>> {
+    Ok(stats_snapshot()?)
+}
+
+#[plugin_fn]
+pub fn plugin_restore_state(data: Vec<u8>) -> FnResult<()> {
+    Ok(restore_stats_snapshot(&data)?)
+}
+
+/// Snapshots `STATS` as JSON so it survives a plugin reload.
+fn stats_snapshot() -> Result<Vec<u8>, serde_json::Error> {
+    STATS.with(|s| serde_json::to_vec(&*s.borrow()))
+}
+
+fn restore_stats_snapshot(data: &[u8]) -> Result<(), serde_json::Error> {
+    let restored: HashMap<String, Stats> = serde_json::from_slice(data)?;
+    LOGGER.info(&format!("restored stats for {} players", restored.len()), None);
+    STATS.with(|s| *s.borrow_mut() = restored);
+    Ok(())
+}
+
+#[plugin_fn]
+pub fn plugin_priority() -> FnResult<Vec<u8>> {
+    Ok(codec::encode(&EventPriority::Normal)?)
+}
+
+#[plugin_fn]
+pub fn plugin_register_commands() -> FnResult<Vec<u8>> {
+    let commands = vec![
+        CommandRegisterRequest {
+            command: "blockstats".into(),
+            description: "Show your or an online player's block statistics".into(),
+            permission: "blocklogger.stats".into(),
+            aliases: vec!["bs".into()],
+        },
+        CommandRegisterRequest {
+            command: "blockrate".into(),
+            description: "Show your blocks per minute".into(),
+            permission: "blocklogger.stats".into(),
+            aliases: vec!["br".into()],
+        },
+        CommandRegisterRequest {
+            command: "blockadmin".into(),
+            description: "Inspect and change the world through the plugin's host calls".into(),
+            permission: PERM_ADMIN.into(),
+            aliases: vec!["ba".into()],
+        },
+    ];
+    Ok(codec::encode(&commands)?)
+}
+
+#[plugin_fn]
+pub fn on_command(data: Vec<u8>) -> FnResult<Vec<u8>> {
+    let ev: CommandEvent = codec::decode(&data)?;
+    match ev.command.trim_start_matches('/') {
+        "blockstats" | "bs" => {
+            let uuid = match ev.args.first() {
+                Some(name) => match get_online_players()?.into_iter().find(|p| p.name.eq_ignore_ascii_case(name)) {
+                    Some(target) => target.uuid,
+                    None => {
+                        notify(&ev.player.uuid, &FormattedText::text(&format!("{name} is not online")).color(Color::Red));
+                        return Ok(Vec::new());
+                    }
+                },
+                None => ev.player.uuid.clone(),
+            };
+            let stats = get_stats(&uuid);
+            notify(&ev.player.uuid, &stats_message(&stats));
+            Ok(codec::encode(&stats)?)
+        }
+        "blockrate" | "br" => {
+            let now = now_ms()?;
+            let rate = RATES.with(|r| r.borrow().get(&ev.player.uuid).map(|ts| ts.rate_per_minute(now))).unwrap_or_default();
+            let msg = FormattedText::text("").append(stat("Per minute - Broken: ", rate.broken)).append(stat(" Placed: ", rate.placed));
+            notify(&ev.player.uuid, &msg);
+            Ok(codec::encode(&rate)?)
+        }
+        "blockadmin" | "ba" => {
+            let msg = match admin_command(&ev) {
+                Ok(reply) => FormattedText::text(&reply).color(Color::Gray),
+                Err(e) => FormattedText::text(&e.to_string()).color(Color::Red),
+            };
+            notify(&ev.player.uuid, &msg);
+            Ok(Vec::new())
+        }
+        other => Err(Error::msg(format!("unknown command: {other}")).into()),
+    }
+}
+
+/// Runs a `/blockadmin` subcommand and returns the line to show its sender.
+fn admin_command(ev: &CommandEvent) -> Result<String, Error> {
+    let args: Vec<&str> = ev.args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["block", x, y, z, dimension @ ..] => {
+            let block = get_block(&parse_position(x, y, z, dimension.first().copied())?)?;
+            let pos = &block.position;
+            Ok(format!("{} at {},{},{} in {}", block.block_type, pos.x, pos.y, pos.z, pos.dimension))
+        }
+        ["setblock", x, y, z, block_type, dimension @ ..] => {
+            let pos = parse_position(x, y, z, dimension.first().copied())?;
+            let block_type = protection::qualify(block_type);
+            set_block(&pos, &block_type, HashMap::new())?;
+            Ok(format!("set {},{},{} in {} to {block_type}", pos.x, pos.y, pos.z, pos.dimension))
+        }
+        ["run", command @ ..] if !command.is_empty() => run_command(&command.join(" ")),
+        ["where", uuid] => {
+            let loc = get_player_location(uuid)?;
+            let ground = if loc.on_ground { "on the ground" } else { "in the air" };
+            Ok(format!("{uuid} is at {:.1},{:.1},{:.1} in {} facing {:.0}/{:.0}, {ground}", loc.x, loc.y, loc.z, loc.dimension, loc.yaw, loc.pitch))
+        }
+        ["border", dimension, size @ ..] => {
+            let mut border = get_world_border(dimension)?;
+            if let Some(size) = size.first() {
+                border.size = size.parse().map_err(|_| Error::msg(format!("{size} is not a border size")))?;
+                set_world_border(dimension, &border)?;
+            }
+            Ok(format!("{dimension} border is {} blocks wide around {},{}", border.size, border.center_x, border.center_z))
+        }
+        ["time", dimension, time @ ..] => {
+            if let Some(time) = time.first() {
+                set_world_time(dimension, time.parse().map_err(|_| Error::msg(format!("{time} is not a tick count")))?)?;
+            }
+            let time = get_world_time(dimension)?;
+            let phase = if time.is_midday() { ", midday" } else if time.is_night() { ", night" } else { "" };
+            Ok(format!("{dimension} is on day {} at tick {}{phase}", time.day, time.time % TICKS_PER_DAY))
+        }
+        ["kick", uuid, reason @ ..] => {
+            let reason = if reason.is_empty() { KICK_REASON.to_string() } else { reason.join(" ") };
+            kick_player(uuid, &reason)?;
+            Ok(format!("kicked {uuid}: {reason}"))
+        }
+        ["gamemode", uuid, mode] => {
+            let mode = gamemode::GameMode::try_from(*mode)?;
+            gamemode::set_gamemode(uuid, mode)?;
+            Ok(format!("set {uuid} to {mode:?}"))
+        }
+        ["difficulty", level, dimension @ ..] => {
+            let level = difficulty::Difficulty::try_from(*level)?;
+            let dimension = dimension.first().copied().unwrap_or(DEFAULT_DIMENSION);
+            difficulty::set_difficulty(dimension, level)?;
+            Ok(format!("set {dimension} to {level:?}"))
+        }
+        ["fog", uuid, fog_type, density] => {
+            let density: f32 = density.parse().map_err(|_| Error::msg(format!("{density} is not a fog density")))?;
+            set_fog(uuid, fog_type, density, FOG_COLOR)?;
+            Ok(format!("set {fog_type} fog for {uuid} to density {}", clamp_fog_density(density)))
+        }
+        ["send", plugin, channel, payload @ ..] => {
+            send_plugin_message(plugin, channel, payload.join(" ").into_bytes())?;
+            Ok(format!("sent {channel} to {plugin}"))
+        }
+        ["grant", uuid, node] => {
+            permission::grant_permission(uuid, node)?;
+            Ok(format!("granted {node} to {uuid}"))
+        }
+        ["entities", x, y, z, radius, dimension @ ..] => {
+            let pos = parse_position(x, y, z, dimension.first().copied())?;
+            let radius: f32 = radius.parse().map_err(|_| Error::msg(format!("{radius} is not a radius")))?;
+            let entities = get_entity_list(&pos, radius, None)?;
+            let listed: Vec<String> = entities
+                .iter()
+                .map(|e| {
+                    let name = e.custom_name.as_deref().map(|n| format!(" \"{n}\"")).unwrap_or_default();
+                    format!("{} #{}{name} at {},{},{}", e.entity_type, e.id, e.position.x, e.position.y, e.position.z)
+                })
+                .collect();
+            Ok(format!("{} within {radius} blocks: {}", entities.len(), listed.join(", ")))
+        }
+        #[cfg(feature = "regions")]
+        ["region", "remove", name] => match region::remove_region(name) {
+            true => Ok(format!("removed region {name}")),
+            false => Err(Error::msg(format!("no region named {name}"))),
+        },
+        ["resetstats", uuid] => {
+            storage::KvStore::<Stats>::new(STATS_NAMESPACE).delete(uuid)?;
+            STATS.with(|s| s.borrow_mut().remove(*uuid));
+            Ok(format!("reset the stats of {uuid}"))
+        }
+        ["checkpoint", "clear", uuid] => {
+            checkpoint::CheckpointStore::default().clear_all(uuid)?;
+            Ok(format!("cleared the checkpoints of {uuid}"))
+        }
+        ["checkpoint", uuid, id] => {
+            let has = if checkpoint::CheckpointStore::default().has_reached(uuid, id) { "has" } else { "has not" };
+            Ok(format!("{uuid} {has} reached {id}"))
+        }
+        _ => Err(Error::msg(ADMIN_USAGE)),
+    }
+}
+
+fn parse_position(x: &str, y: &str, z: &str, dimension: Option<&str>) -> Result<Position, Error> {
+    let coord = |v: &str| v.parse::<i32>().map_err(|_| Error::msg(format!("{v} is not a block coordinate")));
+    Ok(Position { x: coord(x)?, y: coord(y)?, z: coord(z)?, dimension: dimension.unwrap_or(DEFAULT_DIMENSION).into() })
+}
+
+#[plugin_fn]
+pub fn on_plugin_message(data: Vec<u8>) -> FnResult<Vec<u8>> {
+    Ok(handle_plugin_message(codec::decode(&data)?)?)
+}
+
+fn handle_plugin_message(msg: PluginMessageEnvelope) -> Result<Vec<u8>, Error> {
+    match msg.channel.as_str() {
+        CHANNEL_STATS => {
+            let uuid = std::str::from_utf8(&msg.payload)?;
+            LOGGER.debug(&format!("{} requested stats for {uuid}", msg.source_plugin), None);
+            codec::encode(&get_stats(uuid))
+        }
+        CHANNEL_STATS_TEXT => {
+            let uuid = std::str::from_utf8(&msg.payload)?;
+            let text = stats_message(&get_stats(uuid)).hover_event("show_text", "Block Logger stats").click_event("copy_to_clipboard", uuid);
+            Ok(text.to_json().into_bytes())
+        }
+        other => {
+            LOGGER.debug(&format!("ignoring message from {} on unknown channel {other}", msg.source_plugin), None);
+            Ok(Vec::new())
+        }
+    }
+}
+
+#[cfg(feature = "metrics")]
+#[plugin_fn]
+pub fn plugin_metrics() -> FnResult<Vec<u8>> {
+    let tracked = STATS.with(|s| s.borrow().len());
+    let text = METRICS.with(|m| {
+        let mut metrics = m.borrow_mut();
+        metrics.set_gauge(METRIC_TRACKED_PLAYERS, tracked as f64);
+        metrics.export_prometheus()
+    });
+    Ok(text.into_bytes())
+}
+
+#[plugin_fn]
+pub fn plugin_health_check() -> FnResult<Vec<u8>> {
+    Ok(codec::encode(&health_report())?)
+}
+
+fn health_report() -> HealthReport {
+    let uptime_ms = match (STARTED_AT_MS.with(Cell::get), now_ms()) {
+        (Some(started), Ok(now)) => now.saturating_sub(started),
+        _ => 0,
+    };
+    HealthReport {
+        status: "ok".into(),
+        uptime_ms,
+        event_count: EVENT_COUNT.with(Cell::get),
+        error_count: ERROR_COUNT.with(Cell::get),
+        memory_usage_bytes: memory_usage_bytes(),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn memory_usage_bytes() -> Option<u64> {
+    const WASM_PAGE_SIZE: u64 = 65536;
+    Some(core::arch::wasm32::memory_size(0) as u64 * WASM_PAGE_SIZE)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn memory_usage_bytes() -> Option<u64> {
+    None
+}
+
+#[plugin_fn]
+pub fn plugin_encoding() -> FnResult<Vec<u8>> {
+    Ok(vec![codec::ENCODING])
+}
+
+#[plugin_fn]
+pub fn handle_event(envelope: Vec<u8>) -> FnResult<Vec<u8>> {
+    Ok(handle_envelope(&envelope))
+}
+
+/// Dispatches one envelope, turning handler failures into an error result for the host.
+fn handle_envelope(envelope: &[u8]) -> Vec<u8> {
+    EVENT_COUNT.with(|c| c.set(c.get() + 1));
+    ROUTER.with(|r| r.dispatch(envelope)).unwrap_or_else(|e| {
+        ERROR_COUNT.with(|c| c.set(c.get() + 1));
+        LOGGER.debug(&format!("event handler failed: {e}"), None);
+        e.to_output()
+    })
+}
+
+fn on_block_break(ev: BlockBreakEvent) -> Result<EventResult, PluginError> {
+    let mut chain = CancellationChain::new();
+    #[cfg(feature = "regions")]
+    chain.push(check_break_region(&ev));
+    if !chain.is_cancelled() {
+        chain.push(check_break_protected(&ev));
+    }
+
+    #[cfg(feature = "loot")]
+    if !chain.is_cancelled() && ev.block.block_type == SPAWNER_BLOCK {
+        let seed = now_ms().unwrap_or_default();
+        let drops = spawner_loot().roll(&mut loot::XorShiftRng::new(seed));
+        chain.push(EventResult::default().modify(MOD_CUSTOM_DROPS, serde_json::to_value(drops)?));
+    }
+
+    let result = chain.finalize();
+    #[cfg(feature = "metrics")]
+    METRICS.with(|m| m.borrow_mut().increment(if result.cancelled { METRIC_BREAKS_DENIED } else { METRIC_BREAKS }));
+    if !result.cancelled {
+        record_break(&ev);
+    }
+    Ok(result)
+}
+
+#[cfg(feature = "loot")]
+fn spawner_loot() -> loot::LootTable {
+    let golden_carrot = ItemStack { item_type: "minecraft:golden_carrot".into(), count: 1 };
+    loot::LootTable { entries: vec![loot::LootEntry { item: golden_carrot, weight: 1, conditions: Vec::new() }] }
+}
+
+#[cfg(feature = "regions")]
+fn check_break_region(ev: &BlockBreakEvent) -> EventResult {
+    let pos = &ev.block.position;
+    let Some(name) = region::denies(pos, region::RULE_NO_BREAK) else {
+        return EventResult::default();
+    };
+
+    update_stats(&ev.player.uuid, |s| s.denied += 1);
+    record_first_denial(&ev.player);
+    notify(&ev.player.uuid, &protected_notice(&format!("You cannot break blocks in {name}.")));
+    LOGGER.warn(&format!("{} tried to break {} in region {name} at {},{},{}", ev.player.name, ev.block.block_type, pos.x, pos.y, pos.z), None);
+    EventResult::cancel()
+}
+
+/// Records the first time a player is stopped from breaking a block, whether by a region or protection.
+fn record_first_denial(player: &Player) {
+    match checkpoint::CheckpointStore::default().reach(&player.uuid, CHECKPOINT_FIRST_DENY) {
+        Ok(true) => LOGGER.info(&format!("{} reached checkpoint {CHECKPOINT_FIRST_DENY}", player.name), None),
+        Ok(false) => {}
+        Err(e) => LOGGER.debug(&format!("failed to record checkpoint for {}: {e}", player.name), None),
+    }
+    if let Err(e) = advancement::grant_advancement(&player.uuid, ADVANCEMENT_FIRST_DENY, true) {
+        LOGGER.debug(&format!("failed to grant {ADVANCEMENT_FIRST_DENY} to {}: {e}", player.name), None);
+    }
+}
+
+fn check_break_protected(ev: &BlockBreakEvent) -> EventResult {
+    let pos = &ev.block.position;
+    if !protection::is_protected(&ev.block.block_type, &pos.dimension) {
+        return EventResult::default();
+    }
+    if gamemode::get_gamemode(&ev.player.uuid).is_ok_and(|m| m == gamemode::GameMode::Adventure) {
+        return EventResult::default();
+    }
+    match permission::has_permission(&ev.player.uuid, PERM_BYPASS) {
+        Ok(true) => {
+            LOGGER.info(&format!("{} bypassed protection on {} at {},{},{}", ev.player.name, ev.block.block_type, pos.x, pos.y, pos.z), None);
+            return EventResult::default();
+        }
+        Ok(false) => {}
+        Err(e) => LOGGER.debug(&format!("failed to check bypass for {}: {e}", ev.player.name), None),
+    }
+
+    update_stats(&ev.player.uuid, |s| s.denied += 1);
+    record_first_denial(&ev.player);
+    notify(
+        &ev.player.uuid,
+        &protected_notice(&format!("{} cannot be mined.", extract_block_name(&ev.block.block_type))),
+    );
+    LOGGER.warn(&format!("{} tried to break protected block {} at {},{},{}", ev.player.name, ev.block.block_type, pos.x, pos.y, pos.z), None);
+    if get_world_time(&pos.dimension).is_ok_and(|t| t.is_night()) {
+        LOGGER.warn(&format!("{} is mining protected blocks at night", ev.player.name), None);
+    }
+    if let Some(weather) = get_weather(&pos.dimension).ok().filter(|w| w.thundering) {
+        let left = weather.remaining_secs().map(|secs| format!(" ({secs}s left)")).unwrap_or_default();
+        LOGGER.warn(&format!("{} is mining protected blocks during a thunderstorm{left}", ev.player.name), None);
+    }
+    if difficulty::get_difficulty(&pos.dimension).is_ok_and(|d| d == difficulty::Difficulty::Peaceful) {
+        LOGGER.warn(&format!("{} is mining protected blocks on peaceful difficulty, possible cheat", ev.player.name), None);
+    }
+    if let Err(e) = spawn_particle(DENY_PARTICLE, pos, 12) {
+        LOGGER.debug(&format!("failed to spawn denial particles: {e}"), None);
+    }
+    match teleport(&push_back(&ev.player, pos)) {
+        Ok(res) if !res.chunk_loaded => LOGGER.debug(&format!("pushed {} back into an unloaded chunk", ev.player.name), None),
+        Ok(_) => {}
+        Err(e) => LOGGER.debug(&format!("failed to push back {}: {e}", ev.player.name), None),
+    }
+    if get_stats(&ev.player.uuid).denied == BAN_AFTER_DENIALS {
+        match ban_player(&ev.player.uuid, BAN_REASON, None) {
+            Ok(()) => LOGGER.warn(&format!("banned {} after {BAN_AFTER_DENIALS} denied breaks", ev.player.name), None),
+            Err(e) => LOGGER.error(&format!("failed to ban {}: {e}", ev.player.name), None),
+        }
+    }
+    EventResult::cancel()
+}
+
+fn record_break(ev: &BlockBreakEvent) {
+    let pos = &ev.block.position;
+
+    update_stats(&ev.player.uuid, |s| s.broken += 1);
+    let stats = get_stats(&ev.player.uuid);
+
+    let entry = scoreboard::ScoreEntry { objective: SCOREBOARD_BROKEN.into(), player: ev.player.name.clone(), score: i32::try_from(stats.broken).unwrap_or(i32::MAX) };
+    if let Err(e) = scoreboard::set_score(&entry) {
+        LOGGER.debug(&format!("failed to update scoreboard for {}: {e}", ev.player.name), None);
+    }
+
+    match now_ms() {
+        Ok(now) => {
+            record_rate(&ev.player.uuid, now, &Stats { broken: 1, ..Stats::default() });
+            let ready = NOTIFY_COOLDOWN.with(|c| {
+                let mut cooldown = c.borrow_mut();
+                let ready = cooldown.is_ready(&ev.player.uuid, now);
+                if ready {
+                    cooldown.mark(&ev.player.uuid, now);
+                }
+                ready
+            });
+            if ready {
+                notify(&ev.player.uuid, &highlight(stats.broken, " blocks broken"));
+            }
+        }
+        Err(e) => LOGGER.debug(&format!("failed to read host time: {e}"), None),
+    }
+
+    if let Err(e) = bossbar::update(&milestone_bar(&ev.player.uuid, stats.broken)) {
+        LOGGER.debug(&format!("failed to update milestone bar for {}: {e}", ev.player.name), None);
+    }
+
+    if stats.broken == MILESTONE_BLOCKS {
+        let announcement = FormattedText::text("")
+            .append(FormattedText::text(&ev.player.name).color(Color::Gold))
+            .append(FormattedText::text(" has mined ").color(Color::Gray))
+            .append(highlight(MILESTONE_BLOCKS, " blocks!"));
+        if let Err(e) = broadcast(&announcement) {
+            LOGGER.warn(&format!("failed to announce milestone for {}: {e}", ev.player.name), None);
+        }
+        let title = FormattedText::text(&format!("{MILESTONE_BLOCKS} blocks!")).color(Color::Gold).to_legacy();
+        let subtitle = FormattedText::text("Keep digging").color(Color::Gray).italic(true).to_legacy();
+        if let Err(e) = title::send_title(&ev.player.uuid, &title, &subtitle) {
+            LOGGER.debug(&format!("failed to show milestone title to {}: {e}", ev.player.name), None);
+        }
+        if let Err(e) = play_sound_at(&ev.player.uuid, MILESTONE_SOUND, pos) {
+            LOGGER.debug(&format!("failed to play milestone sound for {}: {e}", ev.player.name), None);
+        }
+        if let Err(e) = give_exp(&ev.player.uuid, MILESTONE_EXP) {
+            LOGGER.warn(&format!("failed to award milestone experience to {}: {e}", ev.player.name), None);
+        }
+    }
+
+    if stats.broken.is_multiple_of(100) {
+        let reward = ItemStack { item_type: "minecraft:diamond".into(), count: 1 };
+        match give_item(&ev.player.uuid, reward) {
+            Ok(_) => notify(&ev.player.uuid, &FormattedText::text("You earned a diamond!").color(Color::Aqua)),
+            Err(e) => LOGGER.warn(&format!("failed to reward {}: {e}", ev.player.name), None),
+        }
+    }
+
+    if ev.block.block_type.ends_with("_sign") {
+        match get_block_nbt(pos) {
+            Ok(nbt) => {
+                if let Some(text) = sign_text(&nbt) {
+                    LOGGER.info(&format!("{} broke a sign at {},{},{} reading {text:?}", ev.player.name, pos.x, pos.y, pos.z), None);
+                }
+            }
+            Err(e) => LOGGER.debug(&format!("failed to read sign at {},{},{}: {e}", pos.x, pos.y, pos.z), None),
+        }
+    }
+
+    LOGGER.debug(&format!("{} broke {} at {},{},{}", ev.player.name, ev.block.block_type, pos.x, pos.y, pos.z), None);
+}
+
+fn on_block_explode(ev: BlockExplodeEvent) -> Result<EventResult, PluginError> {
+    let pos = &ev.epicenter;
+
+    let mut chain = CancellationChain::new();
+    #[cfg(feature = "regions")]
+    if let Some(name) = region::denies(pos, region::RULE_NO_BREAK) {
+        LOGGER.info(&format!("cancelled {} explosion in region {name} at {},{},{}", ev.cause, pos.x, pos.y, pos.z), None);
+        chain.push(EventResult::cancel());
+    }
+
+    let mut survivors: Vec<&str> = ev.blocks.iter().filter(|b| protection::is_protected(&b.block_type, &b.position.dimension)).map(|b| b.block_type.as_str()).collect();
+    survivors.sort_unstable();
+    survivors.dedup();
+
+    LOGGER.debug(&format!("{} explosion (power {}) at {},{},{} hit {} blocks", ev.cause, ev.power, pos.x, pos.y, pos.z, ev.blocks.len()), None);
+    if !survivors.is_empty() {
+        chain.push(EventResult::default().modify(MOD_SURVIVOR_BLOCKS, survivors));
+    }
+    Ok(chain.finalize())
+}
+
+#[cfg(feature = "regions")]
+fn on_liquid_flow(ev: LiquidFlowEvent) -> Result<EventResult, PluginError> {
+    let to = &ev.to_position;
+    if extract_block_name(&ev.liquid_type).eq_ignore_ascii_case(extract_block_name(LAVA)) {
+        if let Some(name) = region::denies(to, region::RULE_NO_LAVA) {
+            LOGGER.debug(&format!("turned lava from {} into water in region {name} at {},{},{}", ev.from_block.block_type, to.x, to.y, to.z), None);
+            return Ok(EventResult::default().modify(MOD_LIQUID_TYPE, WATER));
+        }
+    }
+    if !is_protected_liquid(&ev.liquid_type) {
+        return Ok(EventResult::default());
+    }
+
+    // Only flows entering a region are stopped; liquid already inside keeps spreading.
+    let entering = region::denies(to, region::RULE_NO_BREAK).filter(|name| region::denies(&ev.from_block.position, region::RULE_NO_BREAK).as_ref() != Some(name));
+    if let Some(name) = entering {
+        LOGGER.debug(&format!("stopped {} flowing into region {name} at {},{},{}", ev.liquid_type, to.x, to.y, to.z), None);
+        return Ok(EventResult::cancel());
+    }
+    Ok(EventResult::default())
+}
+
+fn on_block_spread(ev: BlockSpreadEvent) -> Result<EventResult, PluginError> {
+    if !ev.spread_type.eq_ignore_ascii_case(SPREAD_FIRE) {
+        return Ok(EventResult::default());
+    }
+
+    let to = &ev.target_position;
+    let max_y = CONFIG.with(|c| c.borrow().max_fire_spread_y);
+    if to.y > max_y {
+        LOGGER.debug(&format!("stopped fire from {} spreading to {},{},{} above y={max_y}", ev.source.block_type, to.x, to.y, to.z), None);
+        return Ok(EventResult::cancel());
+    }
+    #[cfg(feature = "regions")]
+    if let Some(name) = region::denies(to, region::RULE_NO_BREAK) {
+        LOGGER.debug(&format!("stopped fire spreading into region {name} at {},{},{}", to.x, to.y, to.z), None);
+        return Ok(EventResult::cancel());
+    }
+    Ok(EventResult::default())
+}
+
+/// Pitch of a note block note in Hz; notes above `MAX_NOTE` are clamped to it.
+fn note_to_frequency(note: u8) -> f32 {
+    // Note 15 is A4, 440 Hz; each note is a semitone.
+    440.0 * 2f32.powf((f32::from(note.min(MAX_NOTE)) - 15.0) / 12.0)
+}
+
+fn on_note_block_play(ev: NoteBlockEvent) -> Result<EventResult, PluginError> {
+    let pos = &ev.position;
+    let mut res = EventResult::default();
+    if ev.note > MAX_NOTE {
+        LOGGER.debug(&format!("clamped note {} at {},{},{} to {MAX_NOTE}", ev.note, pos.x, pos.y, pos.z), None);
+        res = res.modify(MOD_NOTE, MAX_NOTE);
+    }
+    if !INSTRUMENTS.contains(&ev.instrument.as_str()) {
+        LOGGER.debug(&format!("replaced unknown instrument {:?} at {},{},{} with {DEFAULT_INSTRUMENT}", ev.instrument, pos.x, pos.y, pos.z), None);
+        res = res.modify(MOD_INSTRUMENT, DEFAULT_INSTRUMENT);
+    }
+    if res.modifications.is_some() {
+        return Ok(res);
+    }
+    let trigger = if ev.powered { "redstone" } else { "player" };
+    LOGGER.debug(&format!("{} note {} ({:.2} Hz) at {},{},{} ({trigger})", ev.instrument, ev.note, note_to_frequency(ev.note), pos.x, pos.y, pos.z), None);
+    Ok(EventResult::default())
+}
+
+#[cfg(feature = "regions")]
+fn on_piston_extend(ev: PistonExtendEvent) -> Result<EventResult, PluginError> {
+    on_piston(&ev.piston_position, &ev.piston_facing, &ev.affected_blocks)
+}
+
+#[cfg(feature = "regions")]
+fn on_piston_retract(ev: PistonRetractEvent) -> Result<EventResult, PluginError> {
+    on_piston(&ev.piston_position, &ev.piston_facing, &ev.affected_blocks)
+}
+
+#[cfg(feature = "regions")]
+/// Stops pistons that would move a block out of, into or within a `no_break` region.
+fn on_piston(piston: &Position, facing: &str, affected: &[(Block, Position)]) -> Result<EventResult, PluginError> {
+    for (block, to) in affected {
+        if let Some(name) = region::denies(&block.position, region::RULE_NO_BREAK).or_else(|| region::denies(to, region::RULE_NO_BREAK)) {
+            LOGGER.debug(&format!("stopped piston at {},{},{} facing {facing} moving {} in region {name}", piston.x, piston.y, piston.z, block.block_type), None);
+            return Ok(EventResult::cancel());
+        }
+    }
+    Ok(EventResult::default())
+}
+
+fn on_world_border_change(ev: WorldBorderEvent) -> Result<EventResult, PluginError> {
+    if let Err(e) = ev.new.validate() {
+        LOGGER.warn(&format!("cancelled world border change: {e}"), None);
+        return Ok(EventResult::cancel());
+    }
+    LOGGER.info(&format!("world border resized from {} to {} around {},{}", ev.old.size, ev.new.size, ev.new.center_x, ev.new.center_z), None);
+    Ok(EventResult::default())
+}
+
+#[cfg(feature = "regions")]
+fn is_protected_liquid(liquid_type: &str) -> bool {
+    let name = extract_block_name(liquid_type);
+    CONFIG.with(|c| c.borrow().protected_liquids.iter().any(|l| extract_block_name(l).eq_ignore_ascii_case(name)))
+}
+
+fn on_crop_grow(ev: CropGrowEvent) -> Result<EventResult, PluginError> {
+    let pos = &ev.position;
+    #[cfg(feature = "regions")]
+    if let Some(name) = region::denies(pos, region::RULE_NO_CROP_GROWTH) {
+        LOGGER.debug(&format!("froze {} in region {name} at {},{},{}", ev.block.block_type, pos.x, pos.y, pos.z), None);
+        return Ok(EventResult::cancel());
+    }
+    if ev.new_age > ev.max_age {
+        LOGGER.debug(&format!("capped {} at {},{},{} to age {}", ev.block.block_type, pos.x, pos.y, pos.z, ev.max_age), None);
+        return Ok(EventResult::default().modify(MOD_NEW_AGE, ev.max_age));
+    }
+    Ok(EventResult::default())
+}
+
+fn on_portal_create(ev: PortalCreateEvent) -> Result<EventResult, PluginError> {
+    let creator = ev.player.as_ref().map_or("nobody", |p| p.name.as_str());
+
+    #[cfg(feature = "regions")]
+    if let Some(name) = ev.blocks.iter().find_map(|b| region::denies(&b.position, region::RULE_NO_BREAK)) {
+        LOGGER.info(&format!("cancelled {} portal by {creator} in region {name}", ev.portal_type), None);
+        return Ok(EventResult::cancel());
+    }
+
+    LOGGER.debug(&format!("{creator} created a {} portal in {} ({} blocks)", ev.portal_type, ev.dimension, ev.blocks.len()), None);
+    Ok(EventResult::default())
+}
+
+fn on_block_place(ev: BlockPlaceEvent) -> Result<EventResult, PluginError> {
+    let pos = &ev.block.position;
+
+    update_stats(&ev.player.uuid, |s| s.placed += 1);
+    #[cfg(feature = "metrics")]
+    METRICS.with(|m| m.borrow_mut().increment(METRIC_PLACES));
+    let stats = get_stats(&ev.player.uuid);
+    if let Ok(now) = now_ms() {
+        record_rate(&ev.player.uuid, now, &Stats { placed: 1, ..Stats::default() });
+    }
+
+    if stats.placed.is_multiple_of(50) {
+        notify(&ev.player.uuid, &highlight(stats.placed, " blocks placed"));
+    }
+
+    LOGGER.debug(&format!("{} placed {} at {},{},{}", ev.player.name, ev.block.block_type, pos.x, pos.y, pos.z), None);
+    Ok(EventResult::default())
+}
+
+fn on_player_join(ev: PlayerJoinEvent) -> Result<EventResult, PluginError> {
+    restore_stats(&ev.player.uuid);
+    let stats = get_stats(&ev.player.uuid);
+
+    if stats.broken > 0 || stats.placed > 0 {
+        let msg = FormattedText::text("")
+            .append(FormattedText::text("Welcome back! ").color(Color::Gray))
+            .append(highlight(stats.broken, " broken, "))
+            .append(highlight(stats.placed, " placed"));
+        notify(&ev.player.uuid, &msg);
+    }
+
+    match get_player_inventory(&ev.player.uuid) {
+        Ok(inventory) => {
+            let spawners = find_items(&inventory, CONTRABAND_ITEM);
+            if !spawners.is_empty() {
+                let slots: Vec<String> = spawners.iter().map(|s| s.index.to_string()).collect();
+                LOGGER.warn(&format!("{} joined carrying {CONTRABAND_ITEM} in slots {}", ev.player.name, slots.join(", ")), None);
+            }
+        }
+        Err(e) => LOGGER.debug(&format!("failed to read inventory of {}: {e}", ev.player.name), None),
+    }
+    update_tablist(&ev.player.uuid, &stats);
+    if let Err(e) = bossbar::create(&milestone_bar(&ev.player.uuid, stats.broken)) {
+        LOGGER.debug(&format!("failed to show milestone bar to {}: {e}", ev.player.name), None);
+    }
+
+    LOGGER.info(&format!("{} joined", ev.player.name), None);
+    Ok(EventResult::default())
+}
+
+fn update_tablist(uuid: &str, stats: &Stats) {
+    let footer = FormattedText::text("").append(stat("Broken: ", stats.broken)).append(stat("  Placed: ", stats.placed)).to_legacy();
+    if let Err(e) = title::send_tablist(uuid, "", &footer) {
+        LOGGER.debug(&format!("failed to update tab list for {uuid}: {e}"), None);
+    }
+}
+
+/// The player's progress towards `MILESTONE_BLOCKS`; the bar stays full once the milestone is passed.
+fn milestone_bar(uuid: &str, broken: u64) -> bossbar::BossBar {
+    bossbar::BossBar {
+        id: format!("{MILESTONE_BAR_PREFIX}{uuid}"),
+        title: FormattedText::text(&format!("{broken} / {MILESTONE_BLOCKS} blocks")).color(Color::Gold).to_legacy(),
+        progress: broken as f32 / MILESTONE_BLOCKS as f32,
+        color: "yellow".into(),
+        style: "progress".into(),
+        players: vec![uuid.into()],
+    }
+}
+
+fn on_player_quit(ev: PlayerQuitEvent) -> Result<EventResult, PluginError> {
+    persist_stats(&ev.player.uuid);
+    if let Err(e) = bossbar::remove(&format!("{MILESTONE_BAR_PREFIX}{}", ev.player.uuid)) {
+        LOGGER.debug(&format!("failed to remove milestone bar of {}: {e}", ev.player.name), None);
+    }
+    SLEEPING.with(|s| s.borrow_mut().retain(|uuid| *uuid != ev.player.uuid));
+    LAST_POSITION.with(|p| p.borrow_mut().remove(&ev.player.uuid));
+    let stats = get_stats(&ev.player.uuid);
+    let reason = if ev.reason.is_empty() { "disconnected" } else { ev.reason.as_str() };
+
+    let (broken, placed, denied) = (stats.broken.to_string(), stats.placed.to_string(), stats.denied.to_string());
+    let fields = HashMap::from([("uuid", ev.player.uuid.as_str()), ("broken", broken.as_str()), ("placed", placed.as_str()), ("denied", denied.as_str())]);
+    LOGGER.info(&format!("{} quit ({reason})", ev.player.name), Some(fields));
+    Ok(EventResult::default())
+}
+
+/// Vanilla fall damage, one point per block beyond the first three, scaled by `multiplier`.
+fn fall_damage_for_distance(dist: f32, multiplier: f32) -> f32 {
+    (dist - SAFE_FALL_DISTANCE).max(0.0) * multiplier.max(0.0)
+}
+
+fn on_fall_damage(ev: FallDamageEvent) -> Result<EventResult, PluginError> {
+    let multiplier = CONFIG.with(|c| c.borrow().fall_damage_multiplier);
+    // Only landings are scaled; damage taken mid-air, e.g. while gliding into a wall, is left alone.
+    if multiplier == 1.0 || !ev.on_ground {
+        return Ok(EventResult::default());
+    }
+    let damage = fall_damage_for_distance(ev.fall_distance, multiplier);
+    LOGGER.debug(&format!("{} fell {:.1} blocks: {} damage scaled to {damage}", ev.player.name, ev.fall_distance, ev.damage), None);
+    // The host expects the damage as a string-encoded f32; "0" cancels fall damage entirely.
+    Ok(EventResult::default().modify(MOD_DAMAGE, damage.to_string()))
+}
+
+fn on_fog_change(ev: FogEvent) -> Result<EventResult, PluginError> {
+    let density = clamp_fog_density(ev.density);
+    if density != ev.density {
+        return Ok(EventResult::default().modify(MOD_DENSITY, density));
+    }
+    let [r, g, b] = ev.color;
+    LOGGER.debug(&format!("{} fog changed to {} ({density}, #{r:02x}{g:02x}{b:02x})", ev.player.name, ev.fog_type), None);
+    Ok(EventResult::default())
+}
+
+fn on_sleep(ev: SleepEvent) -> Result<EventResult, PluginError> {
+    let bed = &ev.bed_position;
+    if ev.result != SleepResult::Ok {
+        LOGGER.debug(&format!("{} could not sleep at {},{},{}: {:?}", ev.player.name, bed.x, bed.y, bed.z, ev.result), None);
+        return Ok(EventResult::default());
+    }
+
+    // Query the host first so a failed lookup leaves the sleeper list untouched.
+    let online = get_online_players()?.len().max(1);
+    let needed = (online * SLEEP_QUORUM_PERCENT).div_ceil(100);
+    let sleeping = SLEEPING.with(|s| {
+        let mut s = s.borrow_mut();
+        if !s.contains(&ev.player.uuid) {
+            s.push(ev.player.uuid.clone());
+        }
+        let sleeping = s.len();
+        if sleeping >= needed {
+            s.clear();
+        }
+        sleeping
+    });
+    LOGGER.debug(&format!("{} is sleeping ({sleeping}/{needed})", ev.player.name), None);
+
+    if sleeping >= needed {
+        if let Err(e) = broadcast(&highlight(format!("{sleeping}/{online}"), " players are asleep. Good night!")) {
+            LOGGER.warn(&format!("failed to announce night skip: {e}"), None);
+        }
+        match skip_night(&bed.dimension) {
+            Ok(Some(day)) => LOGGER.info(&format!("skipped the night in {}, now day {day}", bed.dimension), None),
+            Ok(None) => {}
+            Err(e) => LOGGER.warn(&format!("failed to skip the night in {}: {e}", bed.dimension), None),
+        }
+    }
+    Ok(EventResult::default())
+}
+
+/// Moves `dimension` to the next morning if it is night there, returning the new day.
+fn skip_night(dimension: &str) -> Result<Option<u64>, Error> {
+    let now = get_world_time(dimension)?;
+    if !now.is_night() {
+        return Ok(None);
+    }
+    let morning = now.time - now.time % TICKS_PER_DAY + TICKS_PER_DAY;
+    set_world_time(dimension, morning)?;
+    Ok(Some(now.day + 1))
+}
+
+fn on_player_exp(ev: PlayerExpEvent) -> Result<EventResult, PluginError> {
+    LOGGER.debug(&format!("{} experience {:+} (total {}, level {:+})", ev.player.name, ev.delta_exp, ev.new_total, ev.level_change), None);
+    if ev.new_total < 0 {
+        LOGGER.warn(&format!("blocked {} losing {} experience below zero", ev.player.name, -ev.delta_exp), None);
+        return Ok(EventResult::cancel());
+    }
+    if ev.delta_exp > MAX_EXP_GAIN {
+        LOGGER.warn(&format!("capped {}'s experience gain of {} to {MAX_EXP_GAIN}", ev.player.name, ev.delta_exp), None);
+        return Ok(EventResult::default().modify(MOD_DELTA_EXP, MAX_EXP_GAIN));
+    }
+    Ok(EventResult::default())
+}
+
+fn on_player_ban(ev: PlayerBanEvent) -> Result<EventResult, PluginError> {
+    let by = if ev.banned_by.is_empty() { "console" } else { ev.banned_by.as_str() };
+    persist_stats(&ev.player.uuid);
+    LOGGER.info(&format!("{} was banned by {by}: {}", ev.player.name, ev.reason), None);
+    Ok(EventResult::default())
+}
+
+fn on_player_death(ev: PlayerDeathEvent) -> Result<EventResult, PluginError> {
+    let kept = if ev.drop_items { "" } else { ", keeping their items" };
+    match &ev.killer {
+        Some(killer) => {
+            update_stats(&killer.uuid, |s| s.kills += 1);
+            LOGGER.info(&format!("{} was killed by {} ({}){kept}: {}", ev.victim.name, killer.name, ev.cause, ev.death_message), None);
+        }
+        None => LOGGER.info(&format!("{} died ({}){kept}: {}", ev.victim.name, ev.cause, ev.death_message), None),
+    }
+
+    if ev.cause != VOID_DEATH_CAUSE {
+        return Ok(EventResult::default());
+    }
+    let Some(pos) = LAST_POSITION.with(|p| p.borrow().get(&ev.victim.uuid).cloned()) else {
+        return Ok(EventResult::default());
+    };
+    LOGGER.debug(&format!("respawning {} at {},{},{} after falling into the void", ev.victim.name, pos.x, pos.y, pos.z), None);
+    Ok(EventResult::default()
+        .modify(MOD_RESPAWN_X, f64::from(pos.x) + 0.5)
+        .modify(MOD_RESPAWN_Y, f64::from(pos.y))
+        .modify(MOD_RESPAWN_Z, f64::from(pos.z) + 0.5))
+}
+
+fn on_player_chat(ev: PlayerChatEvent) -> Result<EventResult, PluginError> {
+    let Some(censored) = censor_protected_names(&ev.message) else {
+        return Ok(EventResult::default());
+    };
+
+    notify(&ev.player.uuid, &FormattedText::text("Protected block names are not allowed in chat.").color(Color::Red));
+    LOGGER.debug(&format!("censored chat message from {}", ev.player.name), None);
+
+    let res = EventResult::default().modify(MOD_MESSAGE, censored);
+    if ev.recipients.is_empty() {
+        return Ok(res);
+    }
+    // The sender gets the notice above instead of their own censored message.
+    let recipients: Vec<String> = ev.recipients.into_iter().filter(|uuid| *uuid != ev.player.uuid).collect();
+    Ok(res.modify(MOD_RECIPIENTS, recipients))
+}
+
+fn on_book_write(ev: BookWriteEvent) -> Result<EventResult, PluginError> {
+    let mut redacted = 0;
+    let pages: Vec<String> = ev
+        .pages
+        .iter()
+        .map(|page| {
+            page.lines()
+                .map(|line| match censor_protected_names(line) {
+                    Some(_) => {
+                        redacted += 1;
+                        REDACTED_LINE
+                    }
+                    None => line,
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect();
+    if redacted == 0 {
+        return Ok(EventResult::default());
+    }
+
+    let signed = if ev.author.is_empty() || ev.author == ev.player.name { String::new() } else { format!(" signed by {}", ev.author) };
+    LOGGER.info(&format!("redacted {redacted} lines from {}'s book {:?}{signed}", ev.player.name, ev.title), None);
+    Ok(EventResult::default().modify(MOD_PAGES, pages))
+}
+
+fn on_entity_spawn(ev: EntitySpawnEvent) -> Result<EventResult, PluginError> {
+    let pos = &ev.position;
+    let spawner = ev.spawner.as_deref().unwrap_or("natural");
+
+    match count_entities_of_type(pos, SPAWN_CAP_RADIUS, &ev.entity_type) {
+        Ok(nearby) if nearby >= SPAWN_CAP_PER_TYPE => {
+            LOGGER.debug(&format!("blocked {} spawn at {},{},{}: {nearby} already nearby", ev.entity_type, pos.x, pos.y, pos.z), None);
+            return Ok(EventResult::cancel());
+        }
+        Ok(_) => {}
+        Err(e) => LOGGER.debug(&format!("failed to count entities near {},{},{}: {e}", pos.x, pos.y, pos.z), None),
+    }
+
+    if let Some(replacement) = CONFIG.with(|c| c.borrow().spawn_replacements.get(&ev.entity_type).cloned()) {
+        LOGGER.debug(&format!("replaced {} #{} spawning at {},{},{} with {replacement}", ev.entity_type, ev.entity_id, pos.x, pos.y, pos.z), None);
+        return Ok(EventResult::default().modify(MOD_ENTITY_TYPE, replacement));
+    }
+
+    LOGGER.debug(&format!("{} #{} spawned at {},{},{} ({spawner})", ev.entity_type, ev.entity_id, pos.x, pos.y, pos.z), None);
+    Ok(EventResult::default())
+}
+
+fn on_entity_damage(ev: EntityDamageEvent) -> Result<EventResult, PluginError> {
+    let attacker = ev.attacker.as_ref().map_or("none", |a| a.entity_type.as_str());
+    let knockback = ev.knockback.map(|k| format!(", knockback {:.2},{:.2},{:.2}", k.x, k.y, k.z)).unwrap_or_default();
+    LOGGER.debug(&format!("{} #{} took {} {:?} damage (attacker: {attacker}{knockback})", ev.victim.entity_type, ev.victim.entity_id, ev.damage, ev.cause), None);
+    Ok(EventResult::default())
+}
+
+fn on_potion_effect(ev: PotionEffectEvent) -> Result<EventResult, PluginError> {
+    let effect = &ev.effect;
+    if ev.event_kind == PotionEffectKind::Remove || effect.amplifier <= MAX_EFFECT_AMPLIFIER {
+        LOGGER.debug(&format!("entity #{} effect {:?}: {} {} for {} ticks ({})", ev.entity_id, ev.event_kind, effect.id, effect.amplifier, effect.duration_ticks, ev.source), None);
+        return Ok(EventResult::default());
+    }
+
+    LOGGER.warn(&format!("capped {} amplifier {} on entity #{} from {}", effect.id, effect.amplifier, ev.entity_id, ev.source), None);
+    Ok(EventResult::default().modify(MOD_AMPLIFIER, MAX_EFFECT_AMPLIFIER))
+}
+
+fn on_sheep_dye(ev: SheepDyeEvent) -> Result<EventResult, PluginError> {
+    on_entity_modify(ev.into())
+}
+
+fn on_entity_modify(ev: EntityModifyEvent) -> Result<EventResult, PluginError> {
+    let mut changes: Vec<String> = ev.modifications.iter().map(|(k, v)| format!("{k}={v}")).collect();
+    changes.sort_unstable();
+    LOGGER.debug(&format!("{} modified {} #{}: {}", ev.player.name, ev.entity_type, ev.entity_id, changes.join(", ")), None);
+    Ok(EventResult::default())
+}
+
+fn on_fishing_event(ev: FishingEvent) -> Result<EventResult, PluginError> {
+    let pos = &ev.hook_position;
+    let caught = ev.caught.as_ref().map_or("nothing", |i| i.item_type.as_str());
+    LOGGER.debug(&format!("{} fishing {:?} at {},{},{}: {caught} ({} xp)", ev.player.name, ev.event_kind, pos.x, pos.y, pos.z, ev.experience), None);
+    match ev.caught {
+        Some(item) if item.count > MAX_STACK_SIZE => Ok(EventResult::default().modify(MOD_CAUGHT, serde_json::to_value(ItemStack { count: MAX_STACK_SIZE, ..item })?)),
+        _ => Ok(EventResult::default()),
+    }
+}
+
+fn on_vehicle_event(ev: VehicleEvent) -> Result<EventResult, PluginError> {
+    LOGGER.debug(&format!("{} vehicle {:?}: {} #{}", ev.player.name, ev.event_kind, ev.vehicle_type, ev.vehicle_id), None);
+    Ok(EventResult::default())
+}
+
+fn on_craft_item(ev: CraftItemEvent) -> Result<EventResult, PluginError> {
+    if ev.result.item_type == CONTRABAND_ITEM {
+        LOGGER.warn(&format!("{} tried to craft {CONTRABAND_ITEM} via {}", ev.player.name, ev.recipe_id), None);
+        return Ok(EventResult::cancel());
+    }
+
+    let used = ev.ingredients.iter().flatten().count();
+    LOGGER.debug(&format!("{} crafted {} {} from {used} ingredients", ev.player.name, ev.result.count, ev.result.item_type), None);
+    if ev.result.count > MAX_STACK_SIZE {
+        return Ok(EventResult::default().modify(MOD_RESULT, serde_json::to_value(ItemStack { count: MAX_STACK_SIZE, ..ev.result })?));
+    }
+    Ok(EventResult::default())
+}
+
+fn on_weather_change(ev: WeatherChangeEvent) -> Result<EventResult, PluginError> {
+    LOGGER.debug(&format!("weather in {} changing from {} to {} for {} ticks", ev.dimension, ev.from_weather, ev.to_weather, ev.duration_ticks), None);
+    if ev.to_weather == "thunder" && CONFIG.with(|c| c.borrow().downgrade_thunder) {
+        return Ok(EventResult::default().modify(MOD_TO_WEATHER, "rain").modify(MOD_DURATION_TICKS, ev.duration_ticks));
+    }
+    Ok(EventResult::default())
+}
+
+fn on_player_move(ev: PlayerMoveEvent) -> Result<EventResult, PluginError> {
+    if ev.on_ground {
+        LAST_POSITION.with(|p| p.borrow_mut().insert(ev.player.uuid.clone(), ev.to.clone()));
+    }
+    if !exceeds_movement_threshold(&ev.from, &ev.to) {
+        return Ok(EventResult::default());
+    }
+
+    let to = &ev.to;
+    let ground = if ev.on_ground { "" } else { " (airborne)" };
+    LOGGER.debug(&format!("{} moved to {},{},{}{ground}", ev.player.name, to.x, to.y, to.z), None);
+    Ok(EventResult::default())
+}
+
+fn on_dimension_transfer(ev: DimensionTransferEvent) -> Result<EventResult, PluginError> {
+    let entry = &ev.entry_position;
+    #[cfg(feature = "regions")]
+    if let Some(name) = region::denies(entry, region::RULE_NO_ENTRY) {
+        notify(&ev.player.uuid, &protected_notice(&format!("You cannot travel into {name}.")));
+        LOGGER.info(&format!("blocked {} entering region {name} from {}", ev.player.name, ev.from_dimension), None);
+        return Ok(EventResult::cancel());
+    }
+
+    let exit = &ev.exit_position;
+    if !(WORLD_MIN_Y..=WORLD_MAX_Y).contains(&entry.y) {
+        notify(&ev.player.uuid, &FormattedText::text("That portal leads outside the world.").color(Color::Red));
+        LOGGER.warn(&format!("sent {} back to {} instead of y={} in {}", ev.player.name, ev.from_dimension, entry.y, ev.to_dimension), None);
+        return Ok(EventResult::default().modify(MOD_TO_DIMENSION, ev.from_dimension.as_str()).modify(MOD_ENTRY_POSITION, serde_json::json!(exit)));
+    }
+
+    LOGGER.debug(&format!("{} travelled from {} at {},{},{} to {} at {},{},{}", ev.player.name, ev.from_dimension, exit.x, exit.y, exit.z, ev.to_dimension, entry.x, entry.y, entry.z), None);
+    Ok(EventResult::default())
+}
+
+fn on_player_interact(ev: PlayerInteractEvent) -> Result<EventResult, PluginError> {
+    match &ev.target {
+        InteractTarget::Block(block) => {
+            let pos = &block.position;
+            LOGGER.debug(&format!("{} interacted with {} at {},{},{} ({})", ev.player.name, block.block_type, pos.x, pos.y, pos.z, ev.hand), None);
+        }
+        InteractTarget::Entity(entity) => {
+            LOGGER.debug(&format!("{} interacted with {} #{} ({})", ev.player.name, entity.entity_type, entity.entity_id, ev.hand), None);
+        }
+    }
+    Ok(EventResult::default())
+}
+
+fn on_projectile_hit(ev: ProjectileHitEvent) -> Result<EventResult, PluginError> {
+    let shooter = ev.shooter.as_ref().map_or("nobody", |p| p.name.as_str());
+    match &ev.target {
+        ProjectileTarget::Block(block) => {
+            #[cfg(feature = "regions")]
+            if let Some(name) = region::denies(&block.position, region::RULE_NO_BREAK) {
+                LOGGER.debug(&format!("negated {} from {shooter} hitting {} in region {name}", ev.projectile_type, block.block_type), None);
+                return Ok(EventResult::cancel());
+            }
+            let pos = &block.position;
+            LOGGER.debug(&format!("{} from {shooter} hit {} at {},{},{}", ev.projectile_type, block.block_type, pos.x, pos.y, pos.z), None);
+        }
+        ProjectileTarget::Entity(entity) => {
+            LOGGER.debug(&format!("{} from {shooter} hit {} #{}", ev.projectile_type, entity.entity_type, entity.entity_id), None);
+        }
+        ProjectileTarget::None => {
+            let pos = &ev.position;
+            LOGGER.debug(&format!("{} from {shooter} landed at {},{},{}", ev.projectile_type, pos.x, pos.y, pos.z), None);
+        }
+    }
+    Ok(EventResult::default())
+}
+
+fn on_trident_throw(ev: TridentThrowEvent) -> Result<EventResult, PluginError> {
+    if ev.riptide_level > MAX_RIPTIDE_LEVEL {
+        LOGGER.warn(&format!("{} threw a trident with riptide {}, capping to {MAX_RIPTIDE_LEVEL}", ev.player.name, ev.riptide_level), None);
+        return Ok(EventResult::default().modify(MOD_RIPTIDE_LEVEL, MAX_RIPTIDE_LEVEL));
+    }
+    let channeling = if ev.channeling { ", channeling" } else { "" };
+    LOGGER.debug(&format!("{} threw {} (riptide {}{channeling})", ev.player.name, ev.trident_item.item_type, ev.riptide_level), None);
+    Ok(EventResult::default())
+}
+
+fn on_inventory_change(ev: InventoryChangeEvent) -> Result<EventResult, PluginError> {
+    let Some(item) = ev.new_item else {
+        return Ok(EventResult::default());
+    };
+
+    let replaced = ev.old_item.map(|old| format!(" replacing {} {}", old.count, old.item_type)).unwrap_or_default();
+    if item.count <= 0 {
+        LOGGER.warn(&format!("{} moved an invalid stack of {} {} into slot {}{replaced} ({})", ev.player.name, item.count, item.item_type, ev.slot, ev.cause), None);
+        return Ok(EventResult::cancel());
+    }
+
+    if item.count > MAX_STACK_SIZE {
+        LOGGER.warn(&format!("{} moved {} {} into slot {}{replaced} ({}), clamping to {MAX_STACK_SIZE}", ev.player.name, item.count, item.item_type, ev.slot, ev.cause), None);
+        let clamped = ItemStack { count: MAX_STACK_SIZE, ..item };
+        return Ok(EventResult::default().modify(MOD_NEW_ITEM, serde_json::to_value(clamped)?));
+    }
+
+    Ok(EventResult::default())
+}
+
+fn on_item_drop(ev: ItemDropEvent) -> Result<EventResult, PluginError> {
+    let count = u64::try_from(ev.item.count).unwrap_or_default();
+    update_stats(&ev.player.uuid, |s| s.items_dropped += count);
+    let pos = &ev.position;
+    LOGGER.debug(&format!("{} dropped {count} {} at {},{},{}", ev.player.name, ev.item.item_type, pos.x, pos.y, pos.z), None);
+    Ok(EventResult::default())
+}
+
+fn on_item_pickup(ev: ItemPickupEvent) -> Result<EventResult, PluginError> {
+    let count = u64::try_from(ev.item.count).unwrap_or_default();
+    update_stats(&ev.player.uuid, |s| s.items_picked += count);
+    LOGGER.debug(&format!("{} picked up {count} {} from entity #{}", ev.player.name, ev.item.item_type, ev.entity_id), None);
+    Ok(EventResult::default())
+}
+
+fn on_villager_trade(ev: VillagerTradeEvent) -> Result<EventResult, PluginError> {
+    let trade = &ev.trade;
+    // Cancel explicitly: an exhausted trade must never go through just because the host saw no veto.
+    if trade.is_exhausted() {
+        LOGGER.info(&format!("blocked exhausted {} trade for {} ({}/{} uses)", ev.profession, ev.player.name, trade.uses, trade.max_uses), None);
+        return Ok(EventResult::cancel());
+    }
+    if trade.output.item_type == CONTRABAND_ITEM {
+        LOGGER.warn(&format!("{} tried to buy {} from villager #{}", ev.player.name, CONTRABAND_ITEM, ev.villager_id), None);
+        return Ok(EventResult::cancel());
+    }
+    if trade.output.count > MAX_STACK_SIZE {
+        let clamped = ItemStack { count: MAX_STACK_SIZE, ..trade.output.clone() };
+        return Ok(EventResult::default().modify(MOD_OUTPUT, serde_json::to_value(clamped)?));
+    }
+
+    let input_b = trade.input_b.as_ref().map_or(String::new(), |b| format!(" + {} {}", b.count, b.item_type));
+    LOGGER.debug(&format!("{} traded {} {}{input_b} for {} {} with a {} ({} xp)", ev.player.name, trade.input_a.count, trade.input_a.item_type, trade.output.count, trade.output.item_type, ev.profession, trade.xp), None);
+    Ok(EventResult::default())
+}
+
+fn on_container_open(ev: ContainerOpenEvent) -> Result<EventResult, PluginError> {
+    #[cfg(feature = "regions")]
+    if let Some(name) = ev.position.as_ref().and_then(|pos| region::denies(pos, region::RULE_NO_CONTAINER)) {
+        notify(&ev.player.uuid, &protected_notice(&format!("You cannot open containers in {name}.")));
+        LOGGER.info(&format!("{} tried to open a {} in region {name}", ev.player.name, ev.container_type), None);
+        return Ok(EventResult::cancel());
+    }
+    match ev.position.as_ref().map(get_block_entity_inventory) {
+        Some(Ok(inv)) => {
+            let used = inv.slots.iter().filter(|slot| slot.item.is_some()).count();
+            LOGGER.debug(&format!("{} opened a {} ({used}/{} slots used)", ev.player.name, ev.container_type, inv.size), None);
+        }
+        Some(Err(e)) => LOGGER.debug(&format!("{} opened a {}; inventory unavailable: {e}", ev.player.name, ev.container_type), None),
+        None => LOGGER.debug(&format!("{} opened a {}", ev.player.name, ev.container_type), None),
+    }
+    Ok(EventResult::default())
+}
+
+fn on_container_close(ev: ContainerCloseEvent) -> Result<EventResult, PluginError> {
+    LOGGER.debug(&format!("{} closed a {}", ev.player.name, ev.container_type), None);
+    Ok(EventResult::default())
+}
+
+fn on_furnace_event(ev: FurnaceEvent) -> Result<EventResult, PluginError> {
+    let pos = &ev.block.position;
+    LOGGER.debug(&format!("furnace at {},{},{} {:?}: {} -> {} (fuel {})", pos.x, pos.y, pos.z, ev.event_kind, ev.input.item_type, ev.output.item_type, ev.fuel.item_type), None);
+
+    if ev.event_kind == FurnaceEventKind::Finished && ev.output.count > MAX_STACK_SIZE {
+        LOGGER.warn(&format!("furnace at {},{},{} produced {} {}, clamping to {MAX_STACK_SIZE}", pos.x, pos.y, pos.z, ev.output.count, ev.output.item_type), None);
+        let clamped = ItemStack { count: MAX_STACK_SIZE, ..ev.output };
+        return Ok(EventResult::default().modify(MOD_OUTPUT, serde_json::to_value(clamped)?));
+    }
+    Ok(EventResult::default())
+}
+
+fn on_grindstone_use(ev: GrindstoneEvent) -> Result<EventResult, PluginError> {
+    if ev.refunded_xp < 0 {
+        return Err(PluginError::InvalidPayload(format!("negative refunded_xp {}", ev.refunded_xp)));
+    }
+    let mut res = EventResult::default();
+    if ev.result.count > MAX_STACK_SIZE {
+        LOGGER.warn(&format!("grindstone gave {} {} {}, clamping to {MAX_STACK_SIZE}", ev.player.name, ev.result.count, ev.result.item_type), None);
+        res = res.modify(MOD_RESULT, serde_json::to_value(ItemStack { count: MAX_STACK_SIZE, ..ev.result })?);
+    }
+    if ev.refunded_xp == 0 {
+        return Ok(res);
+    }
+    let refunded = ev.refunded_xp * (100 - GRINDSTONE_XP_TAX_PERCENT) / 100;
+    let with = ev.input_b.map(|b| format!(" and {}", b.item_type)).unwrap_or_default();
+    LOGGER.debug(&format!("{} disenchanted {}{with} at a grindstone, refunding {refunded} of {} xp", ev.player.name, ev.input_a.item_type, ev.refunded_xp), None);
+    Ok(res.modify(MOD_REFUNDED_XP, refunded))
+}
+
+fn on_enchant_item(ev: EnchantItemEvent) -> Result<EventResult, PluginError> {
+    if ev.enchantments.iter().all(|e| e.level <= MAX_ENCHANT_LEVEL) {
+        LOGGER.debug(&format!("{} enchanted {} for {} levels", ev.player.name, ev.item.item_type, ev.cost), None);
+        return Ok(EventResult::default());
+    }
+
+    LOGGER.warn(&format!("{} tried to apply enchantments above level {MAX_ENCHANT_LEVEL} to {}", ev.player.name, ev.item.item_type), None);
+    let capped: Vec<Enchantment> = ev.enchantments.into_iter().map(|e| Enchantment { level: e.level.min(MAX_ENCHANT_LEVEL), ..e }).collect();
+    Ok(EventResult::default().modify(MOD_ENCHANTMENTS, serde_json::to_value(capped)?))
+}
+
+fn exceeds_movement_threshold(from: &Position, to: &Position) -> bool {
+    from.dimension != to.dimension || from.distance_sq(to) > MOVEMENT_THRESHOLD * MOVEMENT_THRESHOLD
+}
+
+fn censor_protected_names(message: &str) -> Option<String> {
+    let lowered = message.to_ascii_lowercase();
+    let mut censored = message.to_string();
+    let mut changed = false;
+
+    CONFIG.with(|c| {
+        for block in c.borrow().protected_blocks.iter().filter(|b| !b.contains(['*', '?', '[', '!', '@'])) {
+            let name = extract_block_name(block).to_ascii_lowercase();
+            if name.is_empty() {
+                continue;
+            }
+            for (start, _) in lowered.match_indices(&name) {
+                censored.replace_range(start..start + name.len(), &"*".repeat(name.len()));
+                changed = true;
+            }
+        }
+    });
+    changed.then_some(censored)
+}
+
+fn push_back(player: &Player, block: &Position) -> TeleportRequest {
+    let dx = player.position.x - (f64::from(block.x) + 0.5);
+    let dz = player.position.z - (f64::from(block.z) + 0.5);
+    let len = (dx * dx + dz * dz).sqrt();
+    let (nx, nz) = if len > f64::EPSILON { (dx / len, dz / len) } else { (0.0, 0.0) };
+
+    TeleportRequest {
+        player_uuid: player.uuid.clone(),
+        x: player.position.x + nx,
+        y: player.position.y,
+        z: player.position.z + nz,
+        yaw: player.yaw,
+        pitch: player.pitch,
+        dimension: block.dimension.clone(),
+    }
+}
+
+fn log_stats_summary() {
+    let (players, broken, placed) = STATS.with(|s| {
+        let map = s.borrow();
+        (map.len(), map.values().map(|st| st.broken).sum::<u64>(), map.values().map(|st| st.placed).sum::<u64>())
+    });
+    LOGGER.info(&format!("{players} players tracked: {broken} broken, {placed} placed"), None);
+}
+
+/// Some servers send block-state integers such as crop ages as strings.
+fn u8_from_number_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(u8),
+        String(String),
+    }
+
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(n) => Ok(n),
+        NumberOrString::String(s) => s.trim().parse().map_err(de::Error::custom),
+    }
+}
+
+/// Treats an explicit `null` like a missing field.
+fn null_as_default<'de, D: Deserializer<'de>, T: Default + Deserialize<'de>>(deserializer: D) -> Result<T, D::Error> {
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+fn default_dimension() -> String {
+    DEFAULT_DIMENSION.into()
+}
+
+fn extract_block_name(full: &str) -> &str {
+    full.rsplit(':').next().unwrap_or(full)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use host::mock::{self, Reply};
+    use proptest::prelude::*;
+    use router::PluginEvent as _;
+
+    fn mods(res: &EventResult) -> serde_json::Value {
+        res.modifications.clone().unwrap_or_default()
+    }
+
+    #[test]
+    fn chain_keeps_the_first_modification_per_key() {
+        let mut chain = CancellationChain::new();
+        chain.push(EventResult::default().modify(MOD_DAMAGE, 2)).push(EventResult::default().modify(MOD_DAMAGE, 9).modify(MOD_NOTE, 3));
+        assert!(!chain.is_cancelled());
+        assert_eq!(mods(&chain.finalize()), serde_json::json!({ MOD_DAMAGE: 2, MOD_NOTE: 3 }));
+    }
+
+    #[test]
+    fn any_cancellation_cancels_the_chain() {
+        let mut chain = CancellationChain::new();
+        chain.push(EventResult::default()).push(EventResult::cancel()).push(EventResult::default().modify(MOD_DAMAGE, 1));
+        let res = chain.finalize();
+        assert!(res.cancelled);
+        assert_eq!(mods(&res), serde_json::json!({ MOD_DAMAGE: 1 }));
+    }
+
+    fn sub_result((cancelled, entries): &(bool, Vec<(String, i64)>)) -> EventResult {
+        let res = EventResult { cancelled: *cancelled, modifications: None };
+        entries.iter().fold(res, |res, (key, value)| res.modify(key, *value))
+    }
+
+    fn sub_results() -> impl Strategy<Value = Vec<(bool, Vec<(String, i64)>)>> {
+        prop::collection::vec((any::<bool>(), prop::collection::vec(("[a-d]", 0..10i64), 0..4)), 0..8)
+    }
+
+    proptest! {
+        #[test]
+        fn chains_cancel_iff_a_sub_result_cancels(results in sub_results()) {
+            let mut chain = CancellationChain::new();
+            for result in &results {
+                chain.push(sub_result(result));
+            }
+            prop_assert_eq!(chain.finalize().cancelled, results.iter().any(|(cancelled, _)| *cancelled));
+        }
+
+        #[test]
+        fn chains_keep_the_first_value_per_key(results in sub_results()) {
+            let mut chain = CancellationChain::new();
+            let mut expected = serde_json::Map::new();
+            for result in &results {
+                let sub = sub_result(result);
+                for (key, value) in sub.modifications.iter().flat_map(|m| m.as_object()).flatten() {
+                    expected.entry(key.clone()).or_insert(value.clone());
+                }
+                chain.push(sub);
+            }
+            let merged = chain.finalize().modifications.and_then(|m| m.as_object().cloned()).unwrap_or_default();
+            prop_assert_eq!(merged, expected);
+        }
+    }
+
+    #[test]
+    fn host_error_responses_become_errors() {
+        let ok: Stats = parse_host_response(&codec::encode(&serde_json::json!({ "broken": 4 })).unwrap()).unwrap();
+        assert_eq!(ok.broken, 4);
+        let err = parse_host_response::<Stats>(&codec::encode(&serde_json::json!({ "error": "player offline" })).unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "player offline");
+    }
+
+    #[test]
+    fn sound_pitch_is_clamped() {
+        assert_eq!(clamp_pitch(1.2), 1.2);
+        assert_eq!(clamp_pitch(0.1), 0.5);
+        assert_eq!(clamp_pitch(8.0), 2.0);
+        assert_eq!(clamp_pitch(f32::NAN), 1.0);
+    }
+
+    #[test]
+    fn fog_density_is_clamped() {
+        assert_eq!(clamp_fog_density(0.4), 0.4);
+        assert_eq!(clamp_fog_density(-1.0), 0.0);
+        assert_eq!(clamp_fog_density(3.0), 1.0);
+        assert_eq!(clamp_fog_density(f32::NAN), 0.0);
+
+        mock::reply("host_set_fog", Reply::Code(1));
+        assert_eq!(admin(&["fog", "steve-uuid", "nether", "2.5"]).unwrap(), "set nether fog for steve-uuid to density 1");
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_set_fog")[0]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "player_uuid": "steve-uuid", "fog_type": "nether", "density": 1.0, "color": FOG_COLOR }));
+    }
+
+    fn player() -> serde_json::Value {
+        serde_json::json!({ "uuid": "steve-uuid", "name": "Steve" })
+    }
+
+    /// Dispatches `payload` as `event` and returns the decoded modifications.
+    fn dispatch(router: &router::TypedEventRouter, event: &str, payload: serde_json::Value) -> serde_json::Value {
+        let out = router.dispatch(&router::envelope(event, codec::ENCODING, &codec::encode(&payload).unwrap())).unwrap();
+        assert_eq!(out[0], 0, "{event} was cancelled");
+        if out.len() == 1 {
+            return serde_json::Value::Null;
+        }
+        codec::decode(&out[1..]).unwrap()
+    }
+
+    #[test]
+    fn enchant_handlers_can_force_add_silk_touch() {
+        fn add_silk_touch(mut ev: EnchantItemEvent) -> Result<EventResult, PluginError> {
+            if !ev.enchantments.iter().any(|e| e.id == "minecraft:silk_touch") {
+                ev.enchantments.push(Enchantment { id: "minecraft:silk_touch".into(), level: 1 });
+            }
+            Ok(EventResult::default().modify(MOD_ENCHANTMENTS, serde_json::to_value(ev.enchantments)?))
+        }
+        let mut router = router::TypedEventRouter::new();
+        router.register(EnchantItemEvent::EVENT_NAME, add_silk_touch);
+
+        for enchantments in [serde_json::json!([]), serde_json::json!([{ "id": "minecraft:efficiency", "level": 5 }])] {
+            let payload = serde_json::json!({ "player": player(), "item": { "item_type": "minecraft:diamond_pickaxe", "count": 1 }, "enchantments": enchantments, "cost": 3 });
+            let mods = dispatch(&router, "enchant_item", payload);
+            let applied = mods[MOD_ENCHANTMENTS].as_array().unwrap();
+            assert_eq!(applied.len(), enchantments.as_array().unwrap().len() + 1);
+            assert!(applied.contains(&serde_json::json!({ "id": "minecraft:silk_touch", "level": 1 })));
+        }
+    }
+
+    #[test]
+    fn crafting_grid_keeps_empty_slots() {
+        let plank = serde_json::json!({ "item_type": "minecraft:oak_planks", "count": 1 });
+        let stick = serde_json::json!({ "item_type": "minecraft:stick", "count": 1 });
+        let payload = serde_json::json!({
+            "player": player(),
+            "recipe_id": "minecraft:wooden_pickaxe",
+            "ingredients": [plank, plank, plank, null, stick, null, null, stick, null],
+            "result": { "item_type": "minecraft:wooden_pickaxe", "count": 1 },
+        });
+        let ev: CraftItemEvent = codec::decode(&codec::encode(&payload).unwrap()).unwrap();
+        assert_eq!(ev.ingredients.len(), 9);
+        let filled: Vec<usize> = ev.ingredients.iter().enumerate().filter(|(_, slot)| slot.is_some()).map(|(i, _)| i).collect();
+        assert_eq!(filled, [0, 1, 2, 4, 7]);
+        assert_eq!(ev.ingredients[4].as_ref().unwrap().item_type, "minecraft:stick");
+        assert!(on_craft_item(ev).unwrap().modifications.is_none());
+
+        let mut payload = payload;
+        payload["result"]["count"] = 99.into();
+        assert_eq!(mods(&on_craft_item(serde_json::from_value(payload).unwrap()).unwrap())[MOD_RESULT]["count"], MAX_STACK_SIZE);
+    }
+
+    #[test]
+    fn long_books_are_redacted_line_by_line() {
+        let pages: Vec<String> = (0..500).map(|i| format!("page {i}\nfound ancient_debris at {i},12,{i}\nthe end")).collect();
+        let ev: BookWriteEvent = serde_json::from_value(serde_json::json!({ "player": player(), "title": "Coords", "author": "Alex", "pages": pages })).unwrap();
+        assert_eq!(ev.author, "Alex");
+        let mods = mods(&on_book_write(ev).unwrap());
+        let pages = mods[MOD_PAGES].as_array().unwrap();
+        assert_eq!(pages.len(), 500);
+        assert!(pages.iter().enumerate().all(|(i, page)| page.as_str() == Some(format!("page {i}\n{REDACTED_LINE}\nthe end").as_str())));
+
+        let unsigned: BookWriteEvent = serde_json::from_value(serde_json::json!({ "player": player(), "pages": ["hello"] })).unwrap();
+        assert!(unsigned.author.is_empty());
+        assert!(on_book_write(unsigned).unwrap().modifications.is_none());
+    }
+
+    #[test]
+    fn clean_books_are_left_alone() {
+        let ev: BookWriteEvent = serde_json::from_value(serde_json::json!({ "player": player(), "pages": ["hello", "world"] })).unwrap();
+        assert!(on_book_write(ev).unwrap().modifications.is_none());
+    }
+
+    #[test]
+    fn zero_weather_duration_has_no_remaining_time() {
+        let weather: WeatherResponse = parse_host_response(&codec::encode(&serde_json::json!({ "raining": true, "thundering": true, "duration_remaining_ticks": 0 })).unwrap()).unwrap();
+        assert_eq!(weather.remaining_secs(), None);
+        let weather = WeatherResponse { duration_remaining_ticks: 1200, ..weather };
+        assert_eq!(weather.remaining_secs(), Some(60));
+    }
+
+    #[test]
+    fn block_names_drop_the_namespace() {
+        assert_eq!(extract_block_name("minecraft:diamond_ore"), "diamond_ore");
+        assert_eq!(extract_block_name("stone"), "stone");
+    }
+
+    #[test]
+    fn command_args_default_to_empty() {
+        let ev: CommandEvent = serde_json::from_value(serde_json::json!({ "player": player(), "command": "/blockstats" })).unwrap();
+        assert!(ev.args.is_empty());
+        let ev: CommandEvent = serde_json::from_value(serde_json::json!({ "player": player(), "command": "/bs", "args": ["Alex"] })).unwrap();
+        assert_eq!(ev.args, ["Alex"]);
+    }
+
+    fn admin(args: &[&str]) -> Result<String, Error> {
+        let ev: CommandEvent = serde_json::from_value(serde_json::json!({ "player": player(), "command": "/blockadmin", "args": args })).unwrap();
+        admin_command(&ev)
+    }
+
+    #[test]
+    fn get_block_round_trips_through_the_host() {
+        let pos = Position { x: 1, y: -60, z: 3, dimension: "nether".into() };
+        let block = serde_json::json!({ "block_type": "minecraft:netherrack", "position": pos, "properties": { "lit": "false" } });
+        mock::reply("host_get_block", Reply::Bytes(codec::encode(&block).unwrap()));
+        let block = get_block(&pos).unwrap();
+        assert_eq!((block.block_type.as_str(), &block.position), ("minecraft:netherrack", &pos));
+        assert_eq!(block.properties["lit"], "false");
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_get_block")[0]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "x": 1, "y": -60, "z": 3, "dimension": "nether" }));
+        assert_eq!(admin(&["block", "1", "-60", "3", "nether"]).unwrap(), "minecraft:netherrack at 1,-60,3 in nether");
+    }
+
+    #[test]
+    fn admin_rejects_bad_arguments() {
+        assert_eq!(admin(&["block", "1", "up", "3"]).unwrap_err().to_string(), "up is not a block coordinate");
+        assert_eq!(admin(&[]).unwrap_err().to_string(), ADMIN_USAGE);
+    }
+
+    #[test]
+    fn set_block_maps_host_codes_to_errors() {
+        let pos = Position { x: 0, y: 400, z: 0, dimension: DEFAULT_DIMENSION.into() };
+        let set = || set_block(&pos, "minecraft:stone", HashMap::new()).map_err(|e| e.downcast::<SetBlockError>().unwrap());
+        mock::reply("host_set_block", Reply::Code(1));
+        assert!(set().is_ok());
+        mock::reply("host_set_block", Reply::Code(-1));
+        assert!(matches!(set(), Err(SetBlockError::OutOfBounds)));
+        mock::reply("host_set_block", Reply::Code(-2));
+        assert!(matches!(set(), Err(SetBlockError::ChunkNotLoaded)));
+        mock::reply("host_set_block", Reply::Code(-7));
+        assert!(matches!(set(), Err(SetBlockError::Rejected(-7))));
+        assert_eq!(admin(&["setblock", "0", "400", "0", "Stone"]).unwrap_err().to_string(), "host rejected set_block with code -7");
+    }
+
+    #[test]
+    fn failed_commands_become_errors() {
+        let respond = |res: serde_json::Value| mock::reply("host_run_command", Reply::Bytes(codec::encode(&res).unwrap()));
+        respond(serde_json::json!({ "success": true, "output": "Set the time to 1000" }));
+        assert_eq!(admin(&["run", "time", "set", "1000"]).unwrap(), "Set the time to 1000");
+        respond(serde_json::json!({ "success": false, "output": "Unknown command" }));
+        assert_eq!(run_command("tiem").unwrap_err().to_string(), "Unknown command");
+        respond(serde_json::json!({ "success": false }));
+        assert_eq!(run_command("tiem").unwrap_err().to_string(), "command failed: tiem");
+        respond(serde_json::json!({ "error": "console is disabled" }));
+        assert!(run_command("say hi").is_err());
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_run_command")[0]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "command": "time set 1000", "as_player": null }));
+    }
+
+    #[test]
+    fn missing_players_are_not_found() {
+        mock::reply("host_get_player_location", Reply::Bytes(Vec::new()));
+        let err = get_player_location("ghost-uuid").unwrap_err();
+        assert_eq!(err.downcast_ref::<NotFoundError>().unwrap().to_string(), "player ghost-uuid not found");
+        let loc = serde_json::json!({ "x": 1.25, "y": 64.0, "z": -3.5, "yaw": 90.0, "pitch": 0.0, "dimension": "overworld", "on_ground": true });
+        mock::reply("host_get_player_location", Reply::Bytes(codec::encode(&loc).unwrap()));
+        assert_eq!(admin(&["where", "steve-uuid"]).unwrap(), "steve-uuid is at 1.2,64.0,-3.5 in overworld facing 90/0, on the ground");
+    }
+
+    #[test]
+    fn plugin_lists_decode() {
+        mock::reply("host_list_plugins", Reply::Bytes(codec::encode(&serde_json::json!([])).unwrap()));
+        let plugins = list_plugins().unwrap();
+        assert!(plugins.is_empty());
+        assert_eq!(plugin_summary(&plugins), "no plugins loaded");
+
+        let listed = serde_json::json!([
+            { "name": "block-logger", "version": "1.0.0", "enabled": true },
+            { "name": "whitelist", "version": "0.3.1", "enabled": true },
+            { "name": "economy", "version": "2.0.0", "enabled": false },
+        ]);
+        mock::reply("host_list_plugins", Reply::Bytes(codec::encode(&listed).unwrap()));
+        let plugins = list_plugins().unwrap();
+        assert_eq!(plugins.len(), 3);
+        assert!(!plugins[2].enabled);
+        assert_eq!(plugin_summary(&plugins), "3 plugins loaded: block-logger 1.0.0, whitelist 0.3.1, economy 2.0.0 (disabled)");
+    }
+
+    #[test]
+    fn zero_sized_borders_never_reach_the_host() {
+        let border = serde_json::json!({ "center_x": 0.0, "center_z": 0.0, "size": 1000.0, "damage_per_block": 0.2, "safe_zone": 5.0 });
+        mock::reply("host_get_world_border", Reply::Bytes(codec::encode(&border).unwrap()));
+        mock::reply("host_set_world_border", Reply::Code(1));
+        let mut border = get_world_border("overworld").unwrap();
+        border.size = 0.0;
+        let err = set_world_border("overworld", &border).unwrap_err();
+        assert!(matches!(err.downcast_ref::<InvalidBorderError>(), Some(InvalidBorderError::Size(0.0))));
+        assert!(mock::calls("host_set_world_border").is_empty());
+
+        assert_eq!(admin(&["border", "overworld", "500"]).unwrap(), "overworld border is 500 blocks wide around 0,0");
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_set_world_border")[0]).unwrap();
+        assert_eq!(sent["dimension"], "overworld");
+        assert_eq!(sent["size"], 500.0);
+    }
+
+    #[test]
+    fn midday_is_tick_6000_of_any_day() {
+        let at = |time| WorldTimeResponse { time, day: time / TICKS_PER_DAY };
+        assert!(at(MIDDAY_TICKS).is_midday());
+        assert!(at(3 * TICKS_PER_DAY + MIDDAY_TICKS).is_midday());
+        assert!(!at(MIDDAY_TICKS + 1).is_midday());
+        assert!(!at(0).is_midday());
+
+        mock::reply("host_set_world_time", Reply::Code(1));
+        mock::reply("host_get_world_time", Reply::Bytes(codec::encode(&serde_json::json!({ "time": 78000, "day": 3 })).unwrap()));
+        assert_eq!(admin(&["time", "overworld", "78000"]).unwrap(), "overworld is on day 3 at tick 6000, midday");
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_set_world_time")[0]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "dimension": "overworld", "time": 78000 }));
+    }
+
+    #[test]
+    fn kicks_report_host_rejections() {
+        mock::reply("host_kick_player", Reply::Code(1));
+        assert_eq!(admin(&["kick", "steve-uuid"]).unwrap(), format!("kicked steve-uuid: {KICK_REASON}"));
+        assert_eq!(admin(&["kick", "steve-uuid", "too", "loud"]).unwrap(), "kicked steve-uuid: too loud");
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_kick_player")[1]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "player_uuid": "steve-uuid", "reason": "too loud" }));
+        mock::reply("host_kick_player", Reply::Code(0));
+        assert_eq!(kick_player("steve-uuid", "bye").unwrap_err().to_string(), host_rejected("kick", 0).to_string());
+    }
+
+    #[test]
+    fn plugin_messages_round_trip() {
+        mock::reply("host_plugin_message", Reply::Code(1));
+        update_stats("steve-uuid", |s| s.broken = 7);
+        assert_eq!(admin(&["send", "block-logger", CHANNEL_STATS, "steve-uuid"]).unwrap(), format!("sent {CHANNEL_STATS} to block-logger"));
+
+        // The host hands the message to its target with the sender in place of the target.
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_plugin_message")[0]).unwrap();
+        assert_eq!(sent["target_plugin"], "block-logger");
+        let envelope = PluginMessageEnvelope { source_plugin: "block-logger".into(), channel: serde_json::from_value(sent["channel"].clone()).unwrap(), payload: serde_json::from_value(sent["payload"].clone()).unwrap() };
+        let received: PluginMessageEnvelope = codec::decode(&codec::encode(&envelope).unwrap()).unwrap();
+        assert_eq!((received.channel.as_str(), received.payload.as_slice()), (CHANNEL_STATS, b"steve-uuid".as_slice()));
+        let stats: Stats = codec::decode(&handle_plugin_message(received).unwrap()).unwrap();
+        assert_eq!(stats.broken, 7);
+
+        let text_request = PluginMessageEnvelope { source_plugin: "chat-bridge".into(), channel: CHANNEL_STATS_TEXT.into(), payload: b"steve-uuid".to_vec() };
+        let text: serde_json::Value = serde_json::from_slice(&handle_plugin_message(text_request).unwrap()).unwrap();
+        assert_eq!(text["extra"][0]["extra"][1], serde_json::json!({ "text": "7", "color": "white" }));
+        assert_eq!(text["clickEvent"]["value"], "steve-uuid");
+
+        mock::reply("host_plugin_message", Reply::Code(0));
+        assert!(send_plugin_message("missing", CHANNEL_STATS, Vec::new()).unwrap_err().to_string().contains("message to missing"));
+    }
+
+    #[test]
+    fn rate_limited_messages_move_to_the_action_bar() {
+        mock::reply("host_send_message", Reply::Code(1));
+        mock::reply("host_send_title", Reply::Code(1));
+        NOTIFY_LIMITER.with(|l| while l.borrow_mut().check_and_consume("steve-uuid", 1, 0) {});
+        NOTIFY_THROTTLE.with(|t| {
+            let mut t = t.borrow_mut();
+            t.enqueue("steve-uuid", "first".into(), 0);
+            t.enqueue("steve-uuid", "second".into(), 0);
+        });
+        flush_notifications_at(0);
+        assert!(mock::calls("host_send_message").is_empty());
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_send_title")[0]).unwrap();
+        assert_eq!((&sent["action_bar"], &sent["title"], &sent["player_uuid"]), (&serde_json::json!("second"), &serde_json::json!(""), &serde_json::json!("steve-uuid")));
+    }
+
+    #[test]
+    fn void_deaths_respawn_at_the_last_position() {
+        let router = event_router();
+        let moved = serde_json::json!({ "player": player(), "from": { "x": 10, "y": 70, "z": -4 }, "to": { "x": 10, "y": 70, "z": -5 }, "on_ground": true });
+        dispatch(&router, "player_move", moved);
+        let jumped = serde_json::json!({ "player": player(), "from": { "x": 10, "y": 70, "z": -5 }, "to": { "x": 10, "y": 20, "z": -9 } });
+        dispatch(&router, "player_move", jumped);
+
+        let ev: PlayerDeathEvent = serde_json::from_value(serde_json::json!({ "victim": player(), "cause": "void", "death_message": "Steve fell out of the world", "drop_items": true })).unwrap();
+        assert_eq!((ev.death_message.as_str(), ev.drop_items), ("Steve fell out of the world", true));
+        let mods = mods(&on_player_death(ev).unwrap());
+        assert_eq!((mods[MOD_RESPAWN_X].as_f64(), mods[MOD_RESPAWN_Y].as_f64(), mods[MOD_RESPAWN_Z].as_f64()), (Some(10.5), Some(70.0), Some(-4.5)));
+
+        let ev: PlayerDeathEvent = serde_json::from_value(serde_json::json!({ "victim": player(), "cause": "lava" })).unwrap();
+        assert!(!ev.drop_items);
+        assert!(on_player_death(ev).unwrap().modifications.is_none());
+    }
+
+    #[test]
+    fn censored_chat_skips_the_sender() {
+        let ev: PlayerChatEvent = serde_json::from_value(serde_json::json!({ "player": player(), "message": "diamond_ore at spawn", "recipients": ["steve-uuid", "alex-uuid"] })).unwrap();
+        let censored = mods(&on_player_chat(ev).unwrap());
+        assert_eq!(censored[MOD_RECIPIENTS], serde_json::json!(["alex-uuid"]));
+        assert!(censored[MOD_MESSAGE].is_string());
+
+        let ev: PlayerChatEvent = serde_json::from_value(serde_json::json!({ "player": player(), "message": "diamond_ore at spawn" })).unwrap();
+        assert!(mods(&on_player_chat(ev).unwrap()).get(MOD_RECIPIENTS).is_none());
+        let ev: PlayerChatEvent = serde_json::from_value(serde_json::json!({ "player": player(), "message": "hello", "recipients": ["alex-uuid"] })).unwrap();
+        assert!(on_player_chat(ev).unwrap().modifications.is_none());
+    }
+
+    fn transfer(entry_y: i32) -> serde_json::Value {
+        serde_json::json!({
+            "player": player(),
+            "from_dimension": "overworld",
+            "to_dimension": "nether",
+            "entry_position": { "x": 8, "y": entry_y, "z": 8, "dimension": "nether" },
+            "exit_position": { "x": 64, "y": 70, "z": 64, "dimension": "overworld" },
+        })
+    }
+
+    #[test]
+    fn transfers_outside_the_world_are_redirected_back() {
+        let router = event_router();
+        assert!(dispatch(&router, "dimension_transfer", transfer(64)).is_null());
+        let mods = dispatch(&router, "dimension_transfer", transfer(400));
+        assert_eq!(mods[MOD_TO_DIMENSION], "overworld");
+        assert_eq!(mods[MOD_ENTRY_POSITION], serde_json::json!({ "x": 64, "y": 70, "z": 64, "dimension": "overworld" }));
+    }
+
+    #[cfg(feature = "regions")]
+    #[test]
+    fn transfers_into_no_entry_regions_are_cancelled() {
+        let min = Position { x: 0, y: 0, z: 0, dimension: "nether".into() };
+        let max = Position { x: 16, y: 128, z: 16, dimension: "nether".into() };
+        region::add_region(region::Region { name: "fortress".into(), min, max, rules: vec![region::RULE_NO_ENTRY.into()] });
+        let out = event_router().dispatch(&router::envelope("dimension_transfer", codec::ENCODING, &codec::encode(&transfer(64)).unwrap())).unwrap();
+        assert_eq!(out, [1]);
+    }
+
+    #[test]
+    fn grindstone_fields_default_when_absent() {
+        let ev: GrindstoneEvent = serde_json::from_value(serde_json::json!({
+            "player": player(),
+            "input_a": { "item_type": "minecraft:diamond_sword", "count": 1 },
+            "result": { "item_type": "minecraft:diamond_sword", "count": 1 },
+        }))
+        .unwrap();
+        assert!(ev.input_b.is_none());
+        assert_eq!(ev.refunded_xp, 0);
+        assert!(on_grindstone_use(ev).unwrap().modifications.is_none());
+
+        let ev: GrindstoneEvent = serde_json::from_value(serde_json::json!({
+            "player": player(),
+            "input_a": { "item_type": "minecraft:book", "count": 1 },
+            "input_b": { "item_type": "minecraft:book", "count": 1 },
+            "result": { "item_type": "minecraft:book", "count": 99 },
+            "refunded_xp": 10,
+        }))
+        .unwrap();
+        let mods = mods(&on_grindstone_use(ev).unwrap());
+        assert_eq!(mods[MOD_RESULT]["count"], MAX_STACK_SIZE);
+        assert_eq!(mods[MOD_REFUNDED_XP], 10 * (100 - GRINDSTONE_XP_TAX_PERCENT) / 100);
+    }
+
+    #[test]
+    fn zero_radius_only_lists_the_exact_point() {
+        let pos = Position { x: 4, y: 64, z: -2, dimension: DEFAULT_DIMENSION.into() };
+        mock::reply("host_get_entity_list", Reply::Bytes(Vec::new()));
+        assert!(get_entity_list(&pos, 0.0, None).unwrap().is_empty());
+
+        let found = serde_json::json!([{ "id": 7, "entity_type": "minecraft:item", "position": pos, "custom_name": "Loot" }]);
+        mock::reply("host_get_entity_list", Reply::Bytes(codec::encode(&found).unwrap()));
+        assert_eq!(count_entities_of_type(&pos, 0.0, "minecraft:item").unwrap(), 1);
+        assert_eq!(admin(&["entities", "4", "64", "-2", "0"]).unwrap(), "1 within 0 blocks: minecraft:item #7 \"Loot\" at 4,64,-2");
+
+        let sent: Vec<serde_json::Value> = mock::calls("host_get_entity_list").iter().map(|c| codec::decode(c).unwrap()).collect();
+        assert_eq!(sent[1], serde_json::json!({ "x": 4.5, "y": 64.0, "z": -1.5, "radius": 0.0, "dimension": "overworld", "filter": "minecraft:item" }));
+        assert!(sent[2]["filter"].is_null());
+    }
+
+    #[test]
+    fn configured_spawns_are_replaced() {
+        CONFIG.with(|c| c.borrow_mut().spawn_replacements.insert("minecraft:phantom".into(), "minecraft:bat".into()));
+        let spawn = |entity_type: &str| -> EntitySpawnEvent { serde_json::from_value(serde_json::json!({ "entity_id": 9, "entity_type": entity_type, "position": { "x": 0, "y": 80, "z": 0 } })).unwrap() };
+        assert_eq!(mods(&on_entity_spawn(spawn("minecraft:phantom")).unwrap())[MOD_ENTITY_TYPE], "minecraft:bat");
+        assert!(on_entity_spawn(spawn("minecraft:zombie")).unwrap().modifications.is_none());
+    }
+
+    #[test]
+    fn small_moves_stay_under_the_threshold() {
+        let at = |x, y, z, dimension: &str| Position { x, y, z, dimension: dimension.into() };
+        let origin = at(0, 64, 0, "overworld");
+        assert!(!exceeds_movement_threshold(&origin, &origin));
+        assert!(!exceeds_movement_threshold(&origin, &at(1, 64, 0, "overworld")));
+        assert!(exceeds_movement_threshold(&origin, &at(1, 64, 1, "overworld")));
+        assert!(exceeds_movement_threshold(&origin, &at(0, 64, 0, "nether")));
+
+        let ev: PlayerMoveEvent = serde_json::from_value(serde_json::json!({ "player": player(), "from": origin, "to": origin })).unwrap();
+        assert!(!ev.on_ground);
+    }
+
+    #[test]
+    fn inventory_changes_carry_the_replaced_item() {
+        let ev: InventoryChangeEvent = serde_json::from_value(serde_json::json!({
+            "player": player(),
+            "slot": 3,
+            "old_item": { "item_type": "minecraft:dirt", "count": 12 },
+            "new_item": { "item_type": "minecraft:diamond", "count": 65 },
+        }))
+        .unwrap();
+        assert_eq!(ev.old_item.as_ref().map(|i| i.count), Some(12));
+        assert_eq!(mods(&on_inventory_change(ev).unwrap())[MOD_NEW_ITEM]["count"], MAX_STACK_SIZE);
+
+        let ev: InventoryChangeEvent = serde_json::from_value(serde_json::json!({ "player": player(), "slot": 3 })).unwrap();
+        assert!(ev.old_item.is_none() && ev.new_item.is_none());
+    }
+
+    #[test]
+    fn thunder_is_downgraded_unless_cancelled() {
+        let payload = serde_json::json!({ "dimension": "overworld", "from_weather": "clear", "to_weather": "thunder", "duration_ticks": 6000 });
+        let mut router = router::TypedEventRouter::new();
+        router.register_event::<WeatherChangeEvent>();
+        assert!(dispatch(&router, "weather_change", payload.clone()).is_null());
+
+        CONFIG.with(|c| c.borrow_mut().downgrade_thunder = true);
+        let redirected = dispatch(&router, "weather_change", payload.clone());
+        assert_eq!(redirected[MOD_TO_WEATHER], "rain");
+        assert_eq!(redirected[MOD_DURATION_TICKS], 6000);
+
+        fn cancel_and_redirect(_: WeatherChangeEvent) -> Result<EventResult, PluginError> {
+            Ok(EventResult::cancel().modify(MOD_TO_WEATHER, "clear"))
+        }
+        let mut router = router::TypedEventRouter::new();
+        router.register(WeatherChangeEvent::EVENT_NAME, cancel_and_redirect);
+        let out = router.dispatch(&router::envelope("weather_change", codec::ENCODING, &codec::encode(&payload).unwrap())).unwrap();
+        assert_eq!(out, [1]);
+    }
+
+    #[test]
+    fn every_damage_cause_deserializes() {
+        let causes = [
+            ("attack", DamageCause::Attack),
+            ("projectile", DamageCause::Projectile),
+            ("fall", DamageCause::Fall),
+            ("fire", DamageCause::Fire),
+            ("lava", DamageCause::Lava),
+            ("drowning", DamageCause::Drowning),
+            ("suffocation", DamageCause::Suffocation),
+            ("starvation", DamageCause::Starvation),
+            ("poison", DamageCause::Poison),
+            ("magic", DamageCause::Magic),
+            ("explosion", DamageCause::Explosion),
+            ("void", DamageCause::Void),
+            ("lightning", DamageCause::Lightning),
+            ("wither", DamageCause::Wither),
+            ("thorns", DamageCause::Thorns),
+            ("contact", DamageCause::Contact),
+        ];
+        for (name, cause) in causes {
+            assert_eq!(serde_json::from_value::<DamageCause>(serde_json::json!(name)).unwrap(), cause);
+        }
+        assert!(serde_json::from_value::<DamageCause>(serde_json::json!("cactus")).is_err());
+
+        let victim = serde_json::json!({ "entity_id": 4, "entity_type": "minecraft:zombie" });
+        let ev: EntityDamageEvent = serde_json::from_value(serde_json::json!({ "victim": victim, "damage": 2.5, "cause": "attack", "knockback": { "x": 0.4, "y": 0.1, "z": -0.4 } })).unwrap();
+        assert_eq!(ev.knockback.map(|k| k.z), Some(-0.4));
+        assert!(on_entity_damage(ev).unwrap().modifications.is_none());
+        let ev: EntityDamageEvent = serde_json::from_value(serde_json::json!({ "victim": victim, "damage": 1.0, "cause": "void" })).unwrap();
+        assert!(ev.knockback.is_none());
+    }
+
+    #[test]
+    fn oversized_catches_are_clamped() {
+        let fishing = |kind: &str, caught: serde_json::Value| -> FishingEvent {
+            serde_json::from_value(serde_json::json!({ "player": player(), "hook_position": { "x": 3, "y": 62, "z": -8 }, "caught": caught, "event_kind": kind })).unwrap()
+        };
+        let kinds: Vec<FishingEventKind> = ["cast", "reel", "bite"].into_iter().map(|k| fishing(k, serde_json::Value::Null).event_kind).collect();
+        assert_eq!(kinds, [FishingEventKind::Cast, FishingEventKind::Reel, FishingEventKind::Bite]);
+
+        let ev = fishing("reel", serde_json::Value::Null);
+        assert!(ev.caught.is_none());
+        assert!(on_fishing_event(ev).unwrap().modifications.is_none());
+        let cod = |count: i32| serde_json::json!({ "item_type": "minecraft:cod", "count": count });
+        assert!(on_fishing_event(fishing("reel", cod(1))).unwrap().modifications.is_none());
+        assert_eq!(mods(&on_fishing_event(fishing("reel", cod(200))).unwrap())[MOD_CAUGHT], cod(MAX_STACK_SIZE));
+    }
+
+    #[cfg(feature = "regions")]
+    #[test]
+    fn lava_only_stops_when_entering_a_region() {
+        CONFIG.with(|c| c.borrow_mut().protected_liquids = vec![LAVA.into()]);
+        let at = |x: i32| Position { x, y: 64, z: 0, dimension: DEFAULT_DIMENSION.into() };
+        region::add_region(region::Region { name: "keep".into(), min: at(0), max: at(10), rules: vec![region::RULE_NO_BREAK.into()] });
+        region::add_region(region::Region { name: "pool".into(), min: at(20), max: at(30), rules: vec![region::RULE_NO_LAVA.into()] });
+        let flow = |liquid: &str, from: i32, to: i32| -> LiquidFlowEvent {
+            serde_json::from_value(serde_json::json!({ "from_block": { "block_type": liquid, "position": at(from) }, "to_position": at(to), "liquid_type": liquid })).unwrap()
+        };
+
+        let ev = flow(LAVA, -1, 0);
+        assert_eq!(ev.from_block.position.x, -1);
+        assert!(is_protected_liquid(&ev.liquid_type));
+        assert!(on_liquid_flow(ev).unwrap().cancelled);
+        assert!(!on_liquid_flow(flow(LAVA, 4, 5)).unwrap().cancelled);
+        assert!(!on_liquid_flow(flow(WATER, -1, 0)).unwrap().cancelled);
+        assert_eq!(mods(&on_liquid_flow(flow(LAVA, 19, 20)).unwrap())[MOD_LIQUID_TYPE], WATER);
+        assert!(on_liquid_flow(flow(WATER, 19, 20)).unwrap().modifications.is_none());
+    }
+
+    #[test]
+    fn experience_never_drops_below_zero() {
+        let exp = |delta_exp: i32, new_total: i32| -> PlayerExpEvent {
+            serde_json::from_value(serde_json::json!({ "player": player(), "delta_exp": delta_exp, "new_total": new_total, "level_change": -1 })).unwrap()
+        };
+        let removal = exp(-30, 12);
+        assert_eq!((removal.delta_exp, removal.level_change), (-30, -1));
+        assert!(!on_player_exp(removal).unwrap().cancelled);
+        assert!(on_player_exp(exp(-30, -5)).unwrap().cancelled);
+        assert_eq!(mods(&on_player_exp(exp(5000, 5000)).unwrap())[MOD_DELTA_EXP], MAX_EXP_GAIN);
+        assert!(on_player_exp(exp(MAX_EXP_GAIN, 1200)).unwrap().modifications.is_none());
+    }
+
+    #[test]
+    fn exhausted_trades_are_always_cancelled() {
+        let trade = |uses: u32, input_b: serde_json::Value| {
+            serde_json::json!({
+                "player": player(),
+                "villager_id": 31,
+                "profession": "librarian",
+                "trade": {
+                    "input_a": { "item_type": "minecraft:emerald", "count": 12 },
+                    "input_b": input_b,
+                    "output": { "item_type": "minecraft:enchanted_book", "count": 1 },
+                    "uses": uses,
+                    "max_uses": 12,
+                    "xp": 5,
+                },
+            })
+        };
+        let ev: VillagerTradeEvent = serde_json::from_value(trade(3, serde_json::Value::Null)).unwrap();
+        assert!(ev.trade.input_b.is_none());
+        assert_eq!(ev.trade.xp, 5);
+        assert!(!on_villager_trade(ev).unwrap().cancelled);
+
+        let mut router = router::TypedEventRouter::new();
+        router.register_event::<VillagerTradeEvent>();
+        let book = serde_json::json!({ "item_type": "minecraft:book", "count": 1 });
+        let out = router.dispatch(&router::envelope("villager_trade", codec::ENCODING, &codec::encode(&trade(12, book)).unwrap())).unwrap();
+        assert_eq!(out, [1]);
+    }
+
+    #[test]
+    fn fall_damage_scales_past_the_safe_distance() {
+        assert_eq!(fall_damage_for_distance(1.0, 2.0), 0.0);
+        assert_eq!(fall_damage_for_distance(3.5, 2.0), 1.0);
+        assert_eq!(fall_damage_for_distance(100.0, 0.5), 48.5);
+        assert_eq!(fall_damage_for_distance(100.0, -1.0), 0.0);
+
+        let fall = |on_ground: bool| -> FallDamageEvent {
+            serde_json::from_value(serde_json::json!({ "player": player(), "fall_distance": 20.0, "damage": 17.0, "on_ground": on_ground })).unwrap()
+        };
+        CONFIG.with(|c| c.borrow_mut().fall_damage_multiplier = 0.0);
+        assert_eq!(mods(&on_fall_damage(fall(true)).unwrap())[MOD_DAMAGE], "0");
+        assert!(on_fall_damage(fall(false)).unwrap().modifications.is_none());
+    }
+
+    #[cfg(feature = "regions")]
+    #[test]
+    fn full_piston_chains_are_checked_block_by_block() {
+        let at = |x: i32| Position { x, y: 70, z: 0, dimension: DEFAULT_DIMENSION.into() };
+        let chain = |from: i32| -> Vec<serde_json::Value> {
+            (from..from + 12).map(|x| serde_json::json!([{ "block_type": "minecraft:stone", "position": at(x) }, at(x + 1)])).collect()
+        };
+        let extend = |from: i32| -> PistonExtendEvent {
+            serde_json::from_value(serde_json::json!({ "piston_position": at(from - 1), "piston_facing": "east", "affected_blocks": chain(from) })).unwrap()
+        };
+        let ev = extend(100);
+        assert_eq!((ev.affected_blocks.len(), ev.piston_facing.as_str()), (12, "east"));
+        assert_eq!(ev.affected_blocks[11].1.x, 112);
+        assert!(!on_piston_extend(ev).unwrap().cancelled);
+
+        region::add_region(region::Region { name: "wall".into(), min: at(212), max: at(220), rules: vec![region::RULE_NO_BREAK.into()] });
+        assert!(on_piston_extend(extend(200)).unwrap().cancelled);
+        let retract: PistonRetractEvent = serde_json::from_value(serde_json::json!({ "piston_position": at(199), "piston_facing": "west", "affected_blocks": chain(200) })).unwrap();
+        assert!(on_piston_retract(retract).unwrap().cancelled);
+    }
+
+    #[test]
+    fn notes_map_to_their_frequency() {
+        assert!((note_to_frequency(0) - 185.0).abs() < 0.01);
+        assert!((note_to_frequency(24) - 739.99).abs() < 0.01);
+        assert_eq!(note_to_frequency(25), note_to_frequency(24));
+
+        let note = |instrument: &str, note: u8| -> NoteBlockEvent {
+            serde_json::from_value(serde_json::json!({ "position": { "x": 0, "y": 64, "z": 0 }, "instrument": instrument, "note": note, "powered": true })).unwrap()
+        };
+        assert!(on_note_block_play(note("bell", 24)).unwrap().modifications.is_none());
+        assert_eq!(mods(&on_note_block_play(note("bell", 25)).unwrap()), serde_json::json!({ MOD_NOTE: MAX_NOTE }));
+        assert_eq!(mods(&on_note_block_play(note("", 3)).unwrap()), serde_json::json!({ MOD_INSTRUMENT: DEFAULT_INSTRUMENT }));
+        assert_eq!(mods(&on_note_block_play(note("kazoo", 30)).unwrap()), serde_json::json!({ MOD_NOTE: MAX_NOTE, MOD_INSTRUMENT: DEFAULT_INSTRUMENT }));
+    }
+
+    #[test]
+    fn quorum_skips_to_the_next_morning() {
+        let sleep = |result: &str| -> SleepEvent {
+            serde_json::from_value(serde_json::json!({ "player": player(), "bed_position": { "x": 8, "y": 70, "z": -3, "dimension": "overworld" }, "result": result })).unwrap()
+        };
+        let tired = sleep("not_night");
+        assert_eq!((tired.result, tired.bed_position.x), (SleepResult::NotNight, 8));
+        assert!(!on_sleep(tired).unwrap().cancelled);
+        assert!(mock::calls("host_get_online_players").is_empty());
+
+        mock::reply("host_get_online_players", Reply::Bytes(codec::encode(&serde_json::json!([player()])).unwrap()));
+        mock::reply("host_broadcast_message", Reply::Code(1));
+        mock::reply("host_set_world_time", Reply::Code(1));
+        mock::reply("host_get_world_time", Reply::Bytes(codec::encode(&serde_json::json!({ "time": 2 * TICKS_PER_DAY + 18000, "day": 2 })).unwrap()));
+        on_sleep(sleep("ok")).unwrap();
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_set_world_time")[0]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "dimension": "overworld", "time": 3 * TICKS_PER_DAY }));
+
+        mock::reply("host_get_world_time", Reply::Bytes(codec::encode(&serde_json::json!({ "time": MIDDAY_TICKS, "day": 0 })).unwrap()));
+        assert_eq!(skip_night("overworld").unwrap(), None);
+        assert_eq!(mock::calls("host_set_world_time").len(), 1);
+    }
+
+    #[cfg(feature = "loot")]
+    #[test]
+    fn spawners_drop_loot_only_when_the_break_is_allowed() {
+        let spawner = || -> BlockBreakEvent {
+            serde_json::from_value(serde_json::json!({ "player": player(), "block": { "block_type": SPAWNER_BLOCK, "position": { "x": 0, "y": 20, "z": 0 } } })).unwrap()
+        };
+        mock::reply("host_has_permission", Reply::Code(0));
+        let denied = on_block_break(spawner()).unwrap();
+        assert!(denied.cancelled && denied.modifications.is_none());
+
+        mock::reply("host_has_permission", Reply::Code(1));
+        let allowed = on_block_break(spawner()).unwrap();
+        assert!(!allowed.cancelled);
+        assert_eq!(mods(&allowed)[MOD_CUSTOM_DROPS], serde_json::json!([{ "item_type": "minecraft:golden_carrot", "count": 1 }]));
+    }
+
+    #[test]
+    fn allowlisted_dimensions_are_the_only_ones_handled() {
+        dimensions::set_registry(toml::from_str("mode = \"allowlist\"\nlist = [\"overworld\"]").unwrap());
+        mock::reply("host_has_permission", Reply::Code(0));
+        let router = event_router();
+        let break_in = |dimension: &str| {
+            let payload = serde_json::json!({ "player": player(), "block": { "block_type": "minecraft:diamond_ore", "position": { "x": 0, "y": 12, "z": 0, "dimension": dimension } } });
+            router.dispatch(&router::envelope("block_break", codec::ENCODING, &codec::encode(&payload).unwrap())).unwrap()
+        };
+        assert_eq!(break_in("nether"), [0]);
+        assert_eq!(get_stats("steve-uuid").denied, 0);
+        assert_eq!(break_in("overworld"), [1]);
+        assert_eq!(get_stats("steve-uuid").denied, 1);
+
+        // Events without a dimension are always handled.
+        let join = serde_json::json!({ "player": player() });
+        assert!(router.dispatch(&router::envelope("player_join", codec::ENCODING, &codec::encode(&join).unwrap())).is_ok());
+    }
+
+    fn logged_messages() -> Vec<String> {
+        mock::calls("host_log").iter().map(|data| codec::decode::<serde_json::Value>(data).unwrap()["message"].as_str().unwrap().to_owned()).collect()
+    }
+
+    #[test]
+    fn quitting_logs_the_session_with_any_reason() {
+        let router = event_router();
+        dispatch(&router, "player_quit", serde_json::json!({ "player": player(), "reason": "kicked: ✨ not a vanilla reason ✨" }));
+        dispatch(&router, "player_quit", serde_json::json!({ "player": player() }));
+        let quits: Vec<String> = logged_messages().into_iter().filter(|m| m.contains("Steve quit")).collect();
+        assert_eq!(quits, ["[block-logger] Steve quit (kicked: ✨ not a vanilla reason ✨)", "[block-logger] Steve quit (disconnected)"]);
+    }
+
+    #[test]
+    fn stats_survive_a_serialize_and_restore() {
+        update_stats("steve-uuid", |s| s.broken = 12);
+        update_stats("alex-uuid", |s| {
+            s.placed = 3;
+            s.items_picked = 40;
+        });
+        let before = STATS.with(|s| s.borrow().clone());
+        let snapshot = stats_snapshot().unwrap();
+        STATS.with(|s| s.borrow_mut().clear());
+        restore_stats_snapshot(&snapshot).unwrap();
+        assert_eq!(STATS.with(|s| s.borrow().clone()), before);
+        assert!(restore_stats_snapshot(b"not json").is_err());
+        assert_eq!(get_stats("alex-uuid").items_picked, 40);
+    }
+
+    #[test]
+    fn interact_targets_are_tagged_by_type() {
+        let block: InteractTarget = serde_json::from_value(serde_json::json!({ "type": "block", "block_type": "minecraft:lever", "position": { "x": 1, "y": 64, "z": 2 } })).unwrap();
+        assert!(matches!(block, InteractTarget::Block(b) if b.block_type == "minecraft:lever" && b.position.y == 64));
+        let entity: InteractTarget = serde_json::from_value(serde_json::json!({ "type": "entity", "entity_id": 7, "entity_type": "minecraft:villager" })).unwrap();
+        assert!(matches!(entity, InteractTarget::Entity(e) if e.entity_id == 7 && e.entity_type == "minecraft:villager"));
+        let err = serde_json::from_value::<InteractTarget>(serde_json::json!({ "type": "air" })).unwrap_err();
+        assert!(err.to_string().contains("unknown variant `air`"));
+    }
+
+    #[test]
+    fn cancelled_interactions_report_the_cancel_flag() {
+        fn deny_villagers(ev: PlayerInteractEvent) -> Result<EventResult, PluginError> {
+            Ok(match ev.target {
+                InteractTarget::Entity(e) if e.entity_type == "minecraft:villager" => EventResult::cancel(),
+                _ => EventResult::default(),
+            })
+        }
+        let mut router = router::TypedEventRouter::new();
+        router.register(PlayerInteractEvent::EVENT_NAME, deny_villagers);
+        let interact = |target: serde_json::Value| {
+            let payload = serde_json::json!({ "player": player(), "target": target, "hand": "main_hand" });
+            router.dispatch(&router::envelope("player_interact", codec::ENCODING, &codec::encode(&payload).unwrap())).unwrap()[0]
+        };
+        assert_eq!(interact(serde_json::json!({ "type": "entity", "entity_id": 7, "entity_type": "minecraft:villager" })), 1);
+        assert_eq!(interact(serde_json::json!({ "type": "block", "block_type": "minecraft:lever", "position": { "x": 1, "y": 64, "z": 2 } })), 0);
+    }
+
+    #[test]
+    fn online_players_get_zeroed_stats() {
+        let online = serde_json::json!([
+            { "uuid": "steve-uuid", "name": "Steve", "position": { "x": 0.5, "y": 64.0, "z": 0.5 } },
+            { "uuid": "alex-uuid", "name": "Alex" },
+        ]);
+        mock::reply("host_get_online_players", Reply::Bytes(codec::encode(&online).unwrap()));
+        let players = get_online_players().unwrap();
+        assert_eq!(players.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), ["Steve", "Alex"]);
+        assert_eq!(players[0].position.y, 64.0);
+
+        update_stats("steve-uuid", |s| s.broken = 5);
+        track_online_players().unwrap();
+        assert_eq!(get_stats("steve-uuid").broken, 5);
+        assert!(STATS.with(|s| s.borrow().get("alex-uuid") == Some(&Stats::default())));
+    }
+
+    #[test]
+    fn every_furnace_event_kind_is_handled() {
+        let router = event_router();
+        for (kind, expected) in [("started", FurnaceEventKind::Started), ("finished", FurnaceEventKind::Finished), ("cancelled", FurnaceEventKind::Cancelled)] {
+            assert_eq!(serde_json::from_value::<FurnaceEventKind>(serde_json::json!(kind)).unwrap(), expected);
+            let payload = serde_json::json!({
+                "block": { "block_type": "minecraft:furnace", "position": { "x": 0, "y": 64, "z": 0 } },
+                "fuel": { "item_type": "minecraft:coal", "count": 1 },
+                "input": { "item_type": "minecraft:raw_iron", "count": 1 },
+                "output": { "item_type": "minecraft:iron_ingot", "count": 1 },
+                "event_kind": kind,
+            });
+            assert_eq!(dispatch(&router, "furnace_event", payload), serde_json::Value::Null);
+        }
+        assert!(serde_json::from_value::<FurnaceEventKind>(serde_json::json!("paused")).is_err());
+    }
+
+    #[test]
+    fn empty_inventories_have_no_items_to_find() {
+        let empty = serde_json::json!({ "slots": (0..36).map(|index| serde_json::json!({ "index": index, "item": null })).collect::<Vec<_>>() });
+        mock::reply("host_get_player_inventory", Reply::Bytes(codec::encode(&empty).unwrap()));
+        let inventory = get_player_inventory("steve-uuid").unwrap();
+        assert_eq!(inventory.slots.len(), 36);
+        assert!(inventory.slots.iter().all(|slot| slot.item.is_none()));
+        assert!(find_items(&inventory, CONTRABAND_ITEM).is_empty());
+
+        let full = serde_json::json!({ "slots": [{ "index": 4, "item": { "item_type": CONTRABAND_ITEM, "count": 2 } }, { "index": 5 }] });
+        let inventory: PlayerInventory = serde_json::from_value(full).unwrap();
+        assert_eq!(find_items(&inventory, CONTRABAND_ITEM).iter().map(|slot| slot.index).collect::<Vec<_>>(), [4]);
+    }
+
+    #[test]
+    fn particles_are_broadcast_to_every_player() {
+        mock::reply("host_particle_effect", Reply::Code(1));
+        spawn_particle("minecraft:block", &Position { x: 3, y: 64, z: -2, dimension: "overworld".into() }, 12).unwrap();
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_particle_effect")[0]).unwrap();
+        assert_eq!((sent["players"].clone(), sent["count"].clone()), (serde_json::Value::Null, serde_json::json!(12)));
+        assert_eq!((sent["x"].as_f64(), sent["y"].as_f64(), sent["z"].as_f64()), (Some(3.5), Some(64.5), Some(-1.5)));
+
+        mock::reply("host_particle_effect", Reply::Code(0));
+        let err = spawn_particle("minecraft:block", &Position { x: 0, y: 0, z: 0, dimension: "overworld".into() }, 1).unwrap_err();
+        assert_eq!(err.to_string(), "host rejected particle_effect with code 0");
+    }
+
+    #[test]
+    fn natural_portals_have_no_player() {
+        let payload = serde_json::json!({
+            "player": null,
+            "blocks": [{ "block_type": "minecraft:nether_portal", "position": { "x": 8, "y": 70, "z": 8 } }],
+            "portal_type": "nether",
+        });
+        let ev: PortalCreateEvent = serde_json::from_value(payload.clone()).unwrap();
+        assert!(ev.player.is_none());
+        assert_eq!((ev.portal_type.as_str(), ev.dimension.as_str(), ev.blocks.len()), ("nether", "overworld", 1));
+        assert_eq!(dispatch(&event_router(), "portal_create", payload), serde_json::Value::Null);
+    }
+
+    #[test]
+    fn crop_ages_accept_numbers_and_strings() {
+        let crop = |new_age: serde_json::Value, max_age: serde_json::Value| {
+            let pos = serde_json::json!({ "x": 4, "y": 63, "z": 9 });
+            serde_json::from_value::<CropGrowEvent>(serde_json::json!({ "block": { "block_type": "minecraft:wheat", "position": pos }, "new_age": new_age, "max_age": max_age, "position": pos }))
+        };
+        let numeric = crop(serde_json::json!(3), serde_json::json!(7)).unwrap();
+        assert_eq!((numeric.new_age, numeric.max_age), (3, 7));
+        let stringly = crop(serde_json::json!("3"), serde_json::json!(" 7 ")).unwrap();
+        assert_eq!((stringly.new_age, stringly.max_age), (3, 7));
+        assert!(crop(serde_json::json!(3), serde_json::json!("seven")).is_err());
+        assert!(crop(serde_json::json!(3), serde_json::json!(300)).is_err());
+    }
+
+    #[test]
+    fn sign_nbt_keeps_utf8_text_and_missing_tiles_are_null() {
+        let pos = Position { x: 2, y: 70, z: -5, dimension: "overworld".into() };
+        let sign = serde_json::json!({ "id": "minecraft:sign", "front_text": { "messages": ["Grüße", "日本語", "🙂", ""] } });
+        mock::reply("host_get_block_nbt", Reply::Bytes(codec::encode(&sign).unwrap()));
+        let nbt = get_block_nbt(&pos).unwrap();
+        assert_eq!(nbt["front_text"]["messages"][1], "日本語");
+        assert_eq!(nbt, sign);
+
+        mock::reply("host_get_block_nbt", Reply::Bytes(Vec::new()));
+        assert_eq!(get_block_nbt(&pos).unwrap(), serde_json::Value::Null);
+        mock::reply("host_get_block_nbt", Reply::Bytes(codec::encode(&serde_json::Value::Null).unwrap()));
+        assert_eq!(get_block_nbt(&pos).unwrap(), serde_json::Value::Null);
+    }
+
+    #[test]
+    fn unknown_entity_types_are_modified_like_any_other() {
+        let payload = serde_json::json!({ "player": player(), "entity_id": 99, "entity_type": "othermod:glow_squid_king", "modifications": { "variant": "royal" } });
+        let ev: EntityModifyEvent = serde_json::from_value(payload.clone()).unwrap();
+        assert_eq!((ev.entity_type.as_str(), ev.modifications["variant"].as_str()), ("othermod:glow_squid_king", "royal"));
+        let router = event_router();
+        assert_eq!(dispatch(&router, "entity_modify", payload), serde_json::Value::Null);
+
+        let dyed: EntityModifyEvent = serde_json::from_value::<SheepDyeEvent>(serde_json::json!({ "player": player(), "entity_id": 3, "old_color": "white", "new_color": "lime" })).unwrap().into();
+        assert_eq!((dyed.entity_type.as_str(), dyed.modifications[MOD_NEW_COLOR].as_str()), ("minecraft:sheep", "lime"));
+    }
+
+    #[test]
+    fn every_vehicle_event_kind_is_handled() {
+        let router = event_router();
+        let kinds = [("mount", VehicleEventKind::Mount), ("dismount", VehicleEventKind::Dismount), ("damage", VehicleEventKind::Damage), ("destroy", VehicleEventKind::Destroy)];
+        for (kind, expected) in kinds {
+            let payload = serde_json::json!({ "player": player(), "vehicle_type": "minecraft:minecart", "vehicle_id": 12, "event_kind": kind });
+            assert_eq!(serde_json::from_value::<VehicleEvent>(payload.clone()).unwrap().event_kind, expected);
+            assert_eq!(dispatch(&router, "vehicle_event", payload), serde_json::Value::Null);
+        }
+        assert!(serde_json::from_value::<VehicleEventKind>(serde_json::json!("Mount")).is_err());
+    }
+
+    #[test]
+    fn a_fresh_plugin_reports_healthy() {
+        let report = serde_json::to_value(health_report()).unwrap();
+        assert_eq!(report, serde_json::json!({ "status": "ok", "uptime_ms": 0, "event_count": 0, "error_count": 0, "memory_usage_bytes": null }));
+    }
+
+    #[test]
+    fn potion_effects_default_to_not_ambient() {
+        let payload = serde_json::json!({ "entity_id": 5, "effect": { "id": "minecraft:speed", "duration_ticks": 600 }, "event_kind": "apply" });
+        let ev: PotionEffectEvent = serde_json::from_value(payload.clone()).unwrap();
+        assert!(!ev.effect.ambient);
+        assert_eq!((ev.effect.amplifier, ev.source.as_str(), ev.event_kind), (0, "", PotionEffectKind::Apply));
+        assert_eq!(dispatch(&event_router(), "potion_effect", payload), serde_json::Value::Null);
+    }
+
+    #[test]
+    fn runtime_protected_blocks_survive_a_restart() {
+        mock::reply("host_store_data", Reply::Code(1));
+        assert!(protection::add_dynamic("Beacon") && protection::add_dynamic("minecraft:lodestone"));
+        persist_dynamic_protected().unwrap();
+        let stored: serde_json::Value = codec::decode(mock::calls("host_store_data").last().unwrap()).unwrap();
+        let value: Vec<u8> = serde_json::from_value(stored["value"].clone()).unwrap();
+
+        protection::set_dynamic(Vec::new());
+        assert!(!protection::is_protected("minecraft:beacon", "overworld"));
+        mock::reply("host_load_data", Reply::Bytes(value));
+        restore_dynamic_protected().unwrap();
+        assert_eq!(protection::dynamic_blocks(), ["minecraft:beacon", "minecraft:lodestone"]);
+        assert!(protection::is_protected("minecraft:beacon", "overworld"));
+    }
+
+    #[test]
+    fn ender_chests_open_without_a_position() {
+        let payload = serde_json::json!({ "player": player(), "container_type": "minecraft:ender_chest", "position": null });
+        let ev: ContainerOpenEvent = serde_json::from_value(payload.clone()).unwrap();
+        assert!(ev.position.is_none());
+        assert_eq!(ev.dimension(), None);
+        assert_eq!(dispatch(&event_router(), "container_open", payload), serde_json::Value::Null);
+        assert!(mock::calls("host_get_block_entity_inventory").is_empty());
+    }
+
+    #[test]
+    fn double_chests_keep_all_54_slots() {
+        let pos = Position { x: 10, y: 64, z: 10, dimension: "overworld".into() };
+        let slots: Vec<serde_json::Value> = (0..54).map(|index| serde_json::json!({ "index": index, "item": { "item_type": "minecraft:cobblestone", "count": 64 } })).collect();
+        mock::reply("host_get_block_entity_inventory", Reply::Bytes(codec::encode(&serde_json::json!({ "size": 54, "slots": slots })).unwrap()));
+        let inv = get_block_entity_inventory(&pos).unwrap();
+        assert_eq!((inv.size, inv.slots.len()), (54, 54));
+        assert_eq!(inv.slots[53].index, 53);
+
+        mock::reply("host_get_block_entity_inventory", Reply::Bytes(Vec::new()));
+        let err = get_block_entity_inventory(&pos).unwrap_err();
+        assert!(matches!(err.downcast_ref::<NotAContainerError>(), Some(NotAContainerError(at)) if *at == pos));
+        assert_eq!(err.to_string(), "block at 10, 64, 10 in overworld is not a container");
+    }
+
+    #[test]
+    fn fire_spreads_within_bounds_are_left_alone() {
+        let spread = |spread_type: &str, y: i32| {
+            let ev = serde_json::json!({
+                "source": { "block_type": "minecraft:fire", "position": { "x": 0, "y": y - 1, "z": 0 } },
+                "target_position": { "x": 0, "y": y, "z": 1 },
+                "spread_type": spread_type,
+            });
+            on_block_spread(serde_json::from_value(ev).unwrap()).unwrap()
+        };
+        for y in [-64, 0, MAX_FIRE_SPREAD_Y] {
+            let res = spread("fire", y);
+            assert!(!res.cancelled && res.modifications.is_none(), "fire at y={y}");
+        }
+        assert!(spread("FIRE", MAX_FIRE_SPREAD_Y + 1).cancelled);
+        assert!(!spread("mushroom", MAX_FIRE_SPREAD_Y + 1).cancelled);
+    }
+
+    #[test]
+    fn offline_mode_servers_are_detected() {
+        let info = serde_json::json!({ "mc_version": "1.21.4", "server_software": "Dragonfly", "max_players": 20, "online_mode": false, "plugin_api_version": 1 });
+        mock::reply("host_get_server_info", Reply::Bytes(codec::encode(&info).unwrap()));
+        let info = get_server_info().unwrap();
+        assert!(!info.online_mode);
+        assert_eq!((info.mc_version.as_str(), info.max_players), ("1.21.4", 20));
+        let stringly = serde_json::json!({ "mc_version": "1.21.4", "server_software": "Dragonfly", "max_players": 20, "online_mode": "false", "plugin_api_version": 1 });
+        assert!(serde_json::from_value::<ServerInfo>(stringly).is_err());
+    }
+
+    #[test]
+    fn projectile_targets_are_tagged_by_kind() {
+        let hit = |target: serde_json::Value| serde_json::json!({ "shooter": player(), "projectile_type": "minecraft:arrow", "target": target, "position": { "x": 5, "y": 64, "z": 5 } });
+        let block = serde_json::json!({ "kind": "block", "block_type": "minecraft:target", "position": { "x": 5, "y": 64, "z": 6 } });
+        let entity = serde_json::json!({ "kind": "entity", "entity_id": 42, "entity_type": "minecraft:zombie" });
+        let none = serde_json::json!({ "kind": "none" });
+
+        assert!(matches!(serde_json::from_value(block.clone()).unwrap(), ProjectileTarget::Block(b) if b.block_type == "minecraft:target"));
+        assert!(matches!(serde_json::from_value(entity.clone()).unwrap(), ProjectileTarget::Entity(e) if e.entity_id == 42));
+        assert!(matches!(serde_json::from_value(none.clone()).unwrap(), ProjectileTarget::None));
+        assert!(serde_json::from_value::<ProjectileTarget>(serde_json::json!({ "kind": "player" })).is_err());
+
+        let router = event_router();
+        for target in [block.clone(), entity, none] {
+            assert_eq!(dispatch(&router, "projectile_hit", hit(target)), serde_json::Value::Null);
+        }
+        #[cfg(feature = "regions")]
+        {
+            let at = |x, y, z| Position { x, y, z, dimension: "overworld".into() };
+            region::add_region(region::Region { name: "range".into(), min: at(0, 0, 0), max: at(10, 100, 10), rules: vec![region::RULE_NO_BREAK.into()] });
+            let out = router.dispatch(&router::envelope("projectile_hit", codec::ENCODING, &codec::encode(&hit(block)).unwrap())).unwrap();
+            assert_eq!(out[0], 1);
+        }
+    }
+
+    #[test]
+    fn a_fresh_dump_has_only_the_default_protection() {
+        let dump = serde_json::to_value(debug_dump()).unwrap();
+        assert_eq!(dump["stats"], serde_json::json!({}));
+        assert_eq!(dump["protected_blocks"], serde_json::json!(protection::DEFAULT_PATTERNS));
+        assert_eq!((dump["dynamic_protected"].clone(), dump["sleeping_players"].clone()), (serde_json::json!([]), serde_json::json!([])));
+        assert_eq!((dump["event_count"].clone(), dump["error_count"].clone()), (serde_json::json!(0), serde_json::json!(0)));
+        #[cfg(feature = "regions")]
+        assert_eq!(dump["regions"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn null_or_missing_channeling_means_no_channeling() {
+        let trident = serde_json::json!({ "item_type": "minecraft:trident", "count": 1 });
+        let explicit: TridentThrowEvent = serde_json::from_value(serde_json::json!({ "player": player(), "trident_item": trident, "riptide_level": null, "channeling": null })).unwrap();
+        let missing: TridentThrowEvent = serde_json::from_value(serde_json::json!({ "player": player(), "trident_item": trident })).unwrap();
+        for ev in [explicit, missing] {
+            assert_eq!((ev.channeling, ev.riptide_level), (false, 0));
+            assert!(mods(&on_trident_throw(ev).unwrap()).is_null());
+        }
+        let channeling: TridentThrowEvent = serde_json::from_value(serde_json::json!({ "player": player(), "trident_item": trident, "channeling": true })).unwrap();
+        assert!(channeling.channeling);
+    }
 }
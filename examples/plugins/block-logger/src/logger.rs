@@ -0,0 +1,102 @@
+use crate::codec;
+use crate::host;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LogRequest<'a> {
+    level: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<HashMap<&'a str, &'a str>>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PluginLogger {
+    pub(crate) min_level: LogLevel,
+    pub(crate) prefix: String,
+}
+
+impl PluginLogger {
+    pub(crate) fn new(min_level: LogLevel, prefix: impl Into<String>) -> Self {
+        Self { min_level, prefix: prefix.into() }
+    }
+
+    pub(crate) fn debug(&self, msg: &str, fields: Option<HashMap<&str, &str>>) {
+        self.log(LogLevel::Debug, msg, fields);
+    }
+
+    pub(crate) fn info(&self, msg: &str, fields: Option<HashMap<&str, &str>>) {
+        self.log(LogLevel::Info, msg, fields);
+    }
+
+    pub(crate) fn warn(&self, msg: &str, fields: Option<HashMap<&str, &str>>) {
+        self.log(LogLevel::Warn, msg, fields);
+    }
+
+    pub(crate) fn error(&self, msg: &str, fields: Option<HashMap<&str, &str>>) {
+        self.log(LogLevel::Error, msg, fields);
+    }
+
+    fn log(&self, level: LogLevel, msg: &str, fields: Option<HashMap<&str, &str>>) {
+        if level < self.min_level {
+            return;
+        }
+        let message = if self.prefix.is_empty() { msg.to_string() } else { format!("[{}] {msg}", self.prefix) };
+        let req = LogRequest { level: level.as_str(), message, fields };
+        if let Ok(data) = codec::encode(&req) {
+            unsafe { host::host_log(&data) }.ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::mock;
+
+    fn sent() -> Vec<serde_json::Value> {
+        mock::calls("host_log").iter().map(|data| codec::decode(data).unwrap()).collect()
+    }
+
+    #[test]
+    fn levels_below_the_minimum_are_dropped() {
+        assert!(LogLevel::Debug < LogLevel::Info && LogLevel::Warn < LogLevel::Error);
+        let logger = PluginLogger::new(LogLevel::Warn, "");
+        logger.debug("d", None);
+        logger.info("i", None);
+        logger.warn("w", None);
+        logger.error("e", None);
+        let levels: Vec<serde_json::Value> = sent().into_iter().map(|req| req["level"].clone()).collect();
+        assert_eq!(levels, ["warn", "error"]);
+    }
+
+    #[test]
+    fn fields_are_sent_alongside_the_prefixed_message() {
+        let logger = PluginLogger::new(LogLevel::Debug, "block-logger");
+        logger.info("broke a block", Some(HashMap::from([("player", "Steve"), ("block", "minecraft:stone")])));
+        logger.debug("no fields", None);
+        let sent = sent();
+        assert_eq!(sent[0], serde_json::json!({ "level": "info", "message": "[block-logger] broke a block", "fields": { "player": "Steve", "block": "minecraft:stone" } }));
+        assert_eq!(sent[1], serde_json::json!({ "level": "debug", "message": "[block-logger] no fields" }));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::ItemStack;
+use serde::{Deserialize, Serialize};
+
+/// Source of randomness for loot rolls. The plugin runs without an OS RNG,
+/// so `XorShiftRng` seeded from host time is the usual implementation.
+pub(crate) trait Rng {
+    fn next_u32(&mut self) -> u32;
+
+    /// Uniform value in `0..bound`; `bound` must be non-zero.
+    fn below(&mut self, bound: u32) -> u32 {
+        ((u64::from(self.next_u32()) * u64::from(bound)) >> 32) as u32
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct XorShiftRng(u64);
+
+impl XorShiftRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // Scramble nearby seeds (consecutive timestamps) apart; xorshift
+        // also never leaves the all-zero state.
+        Self((seed ^ 0x9E37_79B9_7F4A_7C15).wrapping_mul(0xBF58_476D_1CE4_E5B9).max(1))
+    }
+}
+
+impl Rng for XorShiftRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 32) as u32
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct LootEntry {
+    pub(crate) item: ItemStack,
+    pub(crate) weight: u32,
+    /// Entries only take part in a roll when every condition is active.
+    #[serde(default)]
+    pub(crate) conditions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct LootTable {
+    pub(crate) entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    /// Picks one unconditional entry, weighted by `weight`. Returns no items
+    /// if no entry has a positive weight.
+    pub(crate) fn roll(&self, rng: &mut impl Rng) -> Vec<ItemStack> {
+        self.roll_with(rng, &[])
+    }
+
+    pub(crate) fn roll_with(&self, rng: &mut impl Rng, active: &[&str]) -> Vec<ItemStack> {
+        let eligible: Vec<&LootEntry> =
+            self.entries.iter().filter(|e| e.weight > 0 && e.conditions.iter().all(|c| active.contains(&c.as_str()))).collect();
+        let total = eligible.iter().fold(0u32, |sum, e| sum.saturating_add(e.weight));
+        if total == 0 {
+            return Vec::new();
+        }
+
+        let mut pick = rng.below(total);
+        for entry in eligible {
+            if pick < entry.weight {
+                return vec![entry.item.clone()];
+            }
+            pick -= entry.weight;
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays fixed values so a roll lands on a known entry.
+    struct Fixed(Vec<u32>);
+
+    impl Rng for Fixed {
+        fn next_u32(&mut self) -> u32 {
+            self.0.remove(0)
+        }
+    }
+
+    fn entry(item: &str, weight: u32, conditions: &[&str]) -> LootEntry {
+        LootEntry { item: ItemStack { item_type: item.into(), count: 1 }, weight, conditions: conditions.iter().map(|c| c.to_string()).collect() }
+    }
+
+    fn rolled(items: Vec<ItemStack>) -> Vec<String> {
+        items.into_iter().map(|i| i.item_type).collect()
+    }
+
+    #[test]
+    fn roll_follows_weights() {
+        let table = LootTable { entries: vec![entry("minecraft:dirt", 3, &[]), entry("minecraft:diamond", 1, &[])] };
+        assert_eq!(rolled(table.roll(&mut Fixed(vec![0]))), ["minecraft:dirt"]);
+        assert_eq!(rolled(table.roll(&mut Fixed(vec![1 << 31]))), ["minecraft:dirt"]);
+        assert_eq!(rolled(table.roll(&mut Fixed(vec![u32::MAX]))), ["minecraft:diamond"]);
+    }
+
+    #[test]
+    fn conditional_entries_need_every_condition() {
+        let table = LootTable { entries: vec![entry("minecraft:nether_star", 1, &["night", "looting"])] };
+        assert!(table.roll(&mut XorShiftRng::new(1)).is_empty());
+        assert!(table.roll_with(&mut XorShiftRng::new(1), &["night"]).is_empty());
+        assert_eq!(rolled(table.roll_with(&mut XorShiftRng::new(1), &["looting", "night"])), ["minecraft:nether_star"]);
+    }
+
+    #[test]
+    fn zero_weights_roll_nothing() {
+        assert!(LootTable::default().roll(&mut XorShiftRng::new(1)).is_empty());
+        let table = LootTable { entries: vec![entry("minecraft:dirt", 0, &[])] };
+        assert!(table.roll(&mut XorShiftRng::new(1)).is_empty());
+    }
+
+    #[test]
+    fn xorshift_stays_below_the_bound() {
+        let mut rng = XorShiftRng::new(0);
+        assert!((0..1000).all(|_| rng.below(7) < 7));
+        assert_ne!(XorShiftRng::new(1).next_u32(), XorShiftRng::new(2).next_u32());
+    }
+}
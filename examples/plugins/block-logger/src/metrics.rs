@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Metrics {
+    counters: HashMap<String, u64>,
+    gauges: HashMap<String, f64>,
+}
+
+impl Metrics {
+    pub(crate) fn increment(&mut self, name: &str) {
+        *self.counters.entry(name.into()).or_default() += 1;
+    }
+
+    pub(crate) fn set_gauge(&mut self, name: &str, val: f64) {
+        self.gauges.insert(name.into(), val);
+    }
+
+    /// Renders every metric in the Prometheus text exposition format, sorted
+    /// by name so repeated scrapes diff cleanly.
+    pub(crate) fn export_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        let mut counters: Vec<_> = self.counters.iter().collect();
+        counters.sort_unstable_by_key(|(name, _)| *name);
+        for (name, value) in counters {
+            let _ = writeln!(out, "# TYPE {name} counter\n{name} {value}");
+        }
+
+        let mut gauges: Vec<_> = self.gauges.iter().collect();
+        gauges.sort_unstable_by_key(|(name, _)| *name);
+        for (name, value) in gauges {
+            let _ = writeln!(out, "# TYPE {name} gauge\n{name} {value}");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_is_sorted_by_kind_then_name() {
+        let mut m = Metrics::default();
+        m.increment("blocks_broken");
+        m.increment("blocks_broken");
+        m.increment("blocks_denied");
+        m.set_gauge("players_online", 3.0);
+        m.set_gauge("players_online", 2.5);
+        assert_eq!(
+            m.export_prometheus(),
+            "# TYPE blocks_broken counter\nblocks_broken 2\n\
+             # TYPE blocks_denied counter\nblocks_denied 1\n\
+             # TYPE players_online gauge\nplayers_online 2.5\n"
+        );
+    }
+
+    #[test]
+    fn empty_metrics_export_nothing() {
+        assert_eq!(Metrics::default().export_prometheus(), "");
+    }
+}
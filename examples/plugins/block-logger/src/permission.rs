@@ -0,0 +1,58 @@
+use crate::codec;
+use crate::error::host_rejected;
+use crate::host;
+use extism_pdk::*;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct PermissionRequest<'a> {
+    player_uuid: &'a str,
+    node: &'a str,
+}
+
+/// Returns whether the player holds `node`. The host answers `1` for yes,
+/// `0` for no and a negative code if the check itself failed.
+pub(crate) fn has_permission(uuid: &str, node: &str) -> Result<bool, Error> {
+    let req = PermissionRequest { player_uuid: uuid, node };
+    match unsafe { host::host_has_permission(&codec::encode(&req)?)? } {
+        1 => Ok(true),
+        0 => Ok(false),
+        code => Err(host_rejected(format!("permission check for {node}"), code)),
+    }
+}
+
+pub(crate) fn grant_permission(uuid: &str, node: &str) -> Result<(), Error> {
+    let req = PermissionRequest { player_uuid: uuid, node };
+    match unsafe { host::host_grant_permission(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected(format!("granting {node}"), code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::mock::{self, Reply};
+
+    #[test]
+    fn checks_map_host_answers() {
+        mock::reply("host_has_permission", Reply::Code(1));
+        assert!(has_permission("steve-uuid", "blocklogger.bypass").unwrap());
+        mock::reply("host_has_permission", Reply::Code(0));
+        assert!(!has_permission("steve-uuid", "blocklogger.bypass").unwrap());
+        mock::reply("host_has_permission", Reply::Code(-1));
+        assert!(has_permission("steve-uuid", "blocklogger.bypass").unwrap_err().to_string().contains("permission check for blocklogger.bypass"));
+        mock::reply("host_has_permission", Reply::Fail("host unavailable".into()));
+        assert!(has_permission("steve-uuid", "blocklogger.bypass").is_err());
+    }
+
+    #[test]
+    fn grants_map_host_answers() {
+        mock::reply("host_grant_permission", Reply::Code(1));
+        grant_permission("steve-uuid", "blocklogger.stats").unwrap();
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_grant_permission")[0]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "player_uuid": "steve-uuid", "node": "blocklogger.stats" }));
+        mock::reply("host_grant_permission", Reply::Code(0));
+        assert!(grant_permission("steve-uuid", "blocklogger.stats").unwrap_err().to_string().contains("granting blocklogger.stats"));
+    }
+}
@@ -0,0 +1,224 @@
+use crate::dimensions;
+use glob::{MatchOptions, Pattern, PatternError};
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+pub(crate) const DEFAULT_PATTERNS: &[&str] = &[
+    "minecraft:diamond_ore",
+    "minecraft:deepslate_diamond_ore",
+    "minecraft:ancient_debris",
+    "minecraft:spawner",
+];
+
+/// Fails compilation unless every entry of the `&[&str]` constant is a
+/// namespaced ID. Exported only so `tests/ui` can show the error.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! assert_namespaced_ids {
+    ($patterns:ident) => {
+        const _: () = {
+            let mut i = 0;
+            while i < $patterns.len() {
+                assert!($crate::bench::is_valid_namespaced_id($patterns[i]), concat!(stringify!($patterns), " entries must be namespace:name IDs"));
+                i += 1;
+            }
+        };
+    };
+}
+
+assert_namespaced_ids!(DEFAULT_PATTERNS);
+
+/// Whether `id` is a `namespace:name` resource location: exactly one colon,
+/// both halves non-empty and made of `[a-z0-9_.-]` (`/` is also allowed in the name).
+pub const fn is_valid_namespaced_id(id: &str) -> bool {
+    let bytes = id.as_bytes();
+    let mut colon = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b':' {
+            if colon.is_some() {
+                return false;
+            }
+            colon = Some(i);
+        } else if !(b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'.' || b == b'-' || (b == b'/' && colon.is_some())) {
+            return false;
+        }
+        i += 1;
+    }
+    matches!(colon, Some(c) if c > 0 && c + 1 < bytes.len())
+}
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions { case_sensitive: false, require_literal_separator: false, require_literal_leading_dot: false };
+
+thread_local! {
+    static PATTERNS: RefCell<Vec<String>> = RefCell::new(DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect());
+    /// `PATTERNS` compiled, in the same order.
+    static RULES: RefCell<Vec<Rule>> = RefCell::new(DEFAULT_PATTERNS.iter().map(|p| Rule::parse(p).expect("default patterns are valid globs")).collect());
+    static DYNAMIC_PROTECTED: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+pub(crate) struct Rule {
+    negated: bool,
+    pattern: Pattern,
+    dimension: Option<String>,
+}
+
+impl Rule {
+    fn parse(raw: &str) -> Result<Self, PatternError> {
+        let (negated, rest) = match raw.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, raw),
+        };
+        let (glob, dimension) = match rest.rsplit_once('@') {
+            Some((glob, dim)) => (glob, Some(dim.to_ascii_lowercase())),
+            None => (rest, None),
+        };
+        Ok(Self { negated, pattern: Pattern::new(glob)?, dimension })
+    }
+
+    fn applies(&self, qualified: &str, dimension: &str) -> bool {
+        self.dimension.as_deref().is_none_or(|d| d.eq_ignore_ascii_case(dimension)) && self.pattern.matches_with(qualified, MATCH_OPTIONS)
+    }
+}
+
+/// Compiles `patterns` and replaces the active rule set. A leading `!`
+/// negates a pattern, a trailing `@<dimension>` limits it to one dimension,
+/// and later patterns override earlier ones.
+pub(crate) fn set_patterns(patterns: &[String]) -> Result<(), PatternError> {
+    let rules = compile(patterns)?;
+    RULES.with(|r| *r.borrow_mut() = rules);
+    PATTERNS.with(|p| *p.borrow_mut() = patterns.to_vec());
+    Ok(())
+}
+
+/// Compiles every pattern, failing on the first invalid one.
+pub(crate) fn compile(patterns: &[String]) -> Result<Vec<Rule>, PatternError> {
+    patterns.iter().map(|raw| Rule::parse(raw)).collect()
+}
+
+/// The active patterns as written, in match order.
+pub(crate) fn patterns() -> Vec<String> {
+    PATTERNS.with(|p| p.borrow().clone())
+}
+
+/// Nothing is protected in dimensions the registry excludes. Elsewhere, blocks
+/// added at runtime are protected regardless of any negated pattern.
+pub(crate) fn is_protected(block_type: &str, dimension: &str) -> bool {
+    if !dimensions::protects(dimension) {
+        return false;
+    }
+    DYNAMIC_PROTECTED.with(|d| d.borrow().contains(&qualify(block_type))) || RULES.with(|r| is_protected_glob(block_type, dimension, &r.borrow()))
+}
+
+/// Lowercases `block_type` and adds the `minecraft:` namespace if it has none.
+pub(crate) fn qualify(block_type: &str) -> String {
+    let lowered = block_type.to_ascii_lowercase();
+    if lowered.contains(':') { lowered } else { format!("minecraft:{lowered}") }
+}
+
+pub(crate) fn add_dynamic(block_type: &str) -> bool {
+    DYNAMIC_PROTECTED.with(|d| d.borrow_mut().insert(qualify(block_type)))
+}
+
+pub(crate) fn remove_dynamic(block_type: &str) -> bool {
+    DYNAMIC_PROTECTED.with(|d| d.borrow_mut().remove(&qualify(block_type)))
+}
+
+pub(crate) fn set_dynamic(blocks: impl IntoIterator<Item = String>) {
+    let blocks = blocks.into_iter().map(|b| qualify(&b)).collect();
+    DYNAMIC_PROTECTED.with(|d| *d.borrow_mut() = blocks);
+}
+
+/// The runtime-added blocks, sorted.
+pub(crate) fn dynamic_blocks() -> Vec<String> {
+    let mut blocks: Vec<String> = DYNAMIC_PROTECTED.with(|d| d.borrow().iter().cloned().collect());
+    blocks.sort_unstable();
+    blocks
+}
+
+/// Whether `rules` protect `block_type` in `dimension`: the last rule that applies decides.
+pub(crate) fn is_protected_glob(block_type: &str, dimension: &str, rules: &[Rule]) -> bool {
+    let qualified = if block_type.contains(':') { block_type.to_string() } else { format!("minecraft:{block_type}") };
+    rules.iter().fold(false, |protected, rule| if rule.applies(&qualified, dimension) { !rule.negated } else { protected })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(raw: &[&str]) -> Vec<Rule> {
+        compile(&raw.iter().map(|p| p.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn wildcard_matches_every_ore() {
+        let ores = patterns(&["minecraft:*_ore"]);
+        assert!(is_protected_glob("minecraft:diamond_ore", "overworld", &ores));
+        assert!(is_protected_glob("iron_ore", "overworld", &ores));
+        assert!(!is_protected_glob("minecraft:stone", "overworld", &ores));
+    }
+
+    #[test]
+    fn negation_unprotects_earlier_matches() {
+        let rules = patterns(&["minecraft:*_ore", "!minecraft:coal_ore"]);
+        assert!(!is_protected_glob("minecraft:coal_ore", "overworld", &rules));
+        assert!(is_protected_glob("minecraft:gold_ore", "overworld", &rules));
+    }
+
+    #[test]
+    fn later_overlapping_patterns_win() {
+        let rules = patterns(&["!minecraft:*_ore", "minecraft:diamond_*"]);
+        assert!(is_protected_glob("minecraft:diamond_ore", "overworld", &rules));
+        let rules = patterns(&["minecraft:diamond_*", "!minecraft:*_ore"]);
+        assert!(!is_protected_glob("minecraft:diamond_ore", "overworld", &rules));
+    }
+
+    #[test]
+    fn dimension_suffix_limits_a_pattern() {
+        let rules = patterns(&["minecraft:ancient_debris@nether"]);
+        assert!(is_protected_glob("minecraft:ancient_debris", "Nether", &rules));
+        assert!(!is_protected_glob("minecraft:ancient_debris", "overworld", &rules));
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        let rules = patterns(&["MINECRAFT:SPAWNER"]);
+        assert!(is_protected_glob("minecraft:spawner", "overworld", &rules));
+    }
+
+    #[test]
+    fn invalid_patterns_keep_the_active_rules() {
+        let invalid = vec!["minecraft:*_ore".to_string(), "minecraft:[".to_string()];
+        assert!(compile(&invalid).is_err());
+        assert!(set_patterns(&invalid).is_err());
+        assert!(is_protected("minecraft:diamond_ore", "overworld"));
+        assert!(!is_protected("minecraft:iron_ore", "overworld"));
+
+        set_patterns(&invalid[..1]).unwrap();
+        assert!(is_protected("minecraft:iron_ore", "overworld"));
+        assert_eq!(super::patterns(), ["minecraft:*_ore"]);
+    }
+
+    #[test]
+    fn default_patterns_are_namespaced_ids() {
+        assert!(DEFAULT_PATTERNS.iter().all(|p| is_valid_namespaced_id(p)));
+        assert!(!is_valid_namespaced_id("diamond_ore"));
+        assert!(!is_valid_namespaced_id("minecraft:a:b"));
+        assert!(!is_valid_namespaced_id("Minecraft:stone"));
+        assert!(is_valid_namespaced_id("mymod:ores/tin"));
+    }
+
+    #[test]
+    fn spawners_are_protected_by_default() {
+        let defaults = patterns(DEFAULT_PATTERNS);
+        assert!(is_protected_glob("minecraft:spawner", "overworld", &defaults));
+        assert!(is_protected_glob("minecraft:diamond_ore", "overworld", &defaults));
+    }
+
+    #[test]
+    fn qualify_adds_the_default_namespace() {
+        assert_eq!(qualify("Stone"), "minecraft:stone");
+        assert_eq!(qualify("mymod:Tin"), "mymod:tin");
+    }
+}
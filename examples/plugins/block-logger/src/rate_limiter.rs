@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+/// Token bucket per key: each key starts with `capacity` tokens and regains
+/// `refill_rate` tokens per second, never exceeding `capacity`.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimiter {
+    capacity: u32,
+    refill_rate: u32,
+    tokens: HashMap<String, (u32, u64)>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(capacity: u32, refill_rate: u32) -> Self {
+        Self { capacity, refill_rate, tokens: HashMap::new() }
+    }
+
+    pub(crate) fn check_and_consume(&mut self, key: &str, cost: u32, now_ms: u64) -> bool {
+        let (tokens, last) = self.tokens.entry(key.into()).or_insert((self.capacity, now_ms));
+
+        let refill = now_ms.saturating_sub(*last) * u64::from(self.refill_rate) / 1000;
+        if refill > 0 {
+            *tokens = u64::from(*tokens).saturating_add(refill).min(u64::from(self.capacity)) as u32;
+            *last = now_ms;
+        }
+
+        if *tokens < cost {
+            return false;
+        }
+        *tokens -= cost;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst_then_refill() {
+        let mut limiter = RateLimiter::new(3, 1);
+        assert!((0..3).all(|_| limiter.check_and_consume("steve", 1, 0)));
+        assert!(!limiter.check_and_consume("steve", 1, 999));
+        assert!(limiter.check_and_consume("steve", 1, 1000));
+        assert!(!limiter.check_and_consume("steve", 1, 1000));
+    }
+
+    #[test]
+    fn refill_never_exceeds_capacity() {
+        let mut limiter = RateLimiter::new(2, 10);
+        assert!(limiter.check_and_consume("steve", 2, 0));
+        assert!(!limiter.check_and_consume("steve", 3, 60_000));
+        assert!(limiter.check_and_consume("steve", 2, 60_000));
+    }
+
+    #[test]
+    fn keys_have_separate_buckets() {
+        let mut limiter = RateLimiter::new(1, 1);
+        assert!(limiter.check_and_consume("steve", 1, 0));
+        assert!(limiter.check_and_consume("alex", 1, 0));
+        assert!(!limiter.check_and_consume("steve", 1, 0));
+    }
+}
@@ -0,0 +1,205 @@
+use crate::dimensions;
+use crate::Position;
+use rstar::{RTree, RTreeObject, AABB};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+pub(crate) const RULE_NO_BREAK: &str = "no_break";
+pub(crate) const RULE_NO_CROP_GROWTH: &str = "no_crop_growth";
+pub(crate) const RULE_NO_ENTRY: &str = "no_entry";
+pub(crate) const RULE_NO_CONTAINER: &str = "no_container";
+pub(crate) const RULE_NO_LAVA: &str = "no_lava";
+
+thread_local! {
+    static REGIONS: RefCell<SpatialIndex> = RefCell::new(SpatialIndex::new());
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Region {
+    pub(crate) name: String,
+    pub(crate) min: Position,
+    pub(crate) max: Position,
+    #[serde(default)]
+    pub(crate) rules: Vec<String>,
+}
+
+impl Region {
+    pub(crate) fn contains(&self, pos: &Position) -> bool {
+        let within = |v: i32, a: i32, b: i32| a.min(b) <= v && v <= a.max(b);
+        pos.dimension == self.min.dimension
+            && within(pos.x, self.min.x, self.max.x) && within(pos.y, self.min.y, self.max.y) && within(pos.z, self.min.z, self.max.z)
+    }
+
+    pub(crate) fn has_rule(&self, rule: &str) -> bool {
+        self.rules.iter().any(|r| r == rule)
+    }
+}
+
+/// Bounds are stored as `f64`: rstar multiplies extents when splitting nodes,
+/// which overflows integers for regions far apart, and block coordinates are exact in `f64`.
+impl RTreeObject for Region {
+    type Envelope = AABB<[f64; 3]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        AABB::from_corners(corner(&self.min), corner(&self.max))
+    }
+}
+
+fn corner(pos: &Position) -> [f64; 3] {
+    [pos.x.into(), pos.y.into(), pos.z.into()]
+}
+
+/// Regions keyed by dimension, each dimension backed by an R-tree over the
+/// region bounds. Names are unique across all dimensions.
+#[derive(Debug, Default)]
+pub(crate) struct SpatialIndex {
+    trees: HashMap<String, RTree<Region>>,
+}
+
+impl SpatialIndex {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `region`, replacing any existing region with the same name.
+    pub(crate) fn insert(&mut self, region: Region) {
+        self.remove(&region.name);
+        self.trees.entry(region.min.dimension.clone()).or_default().insert(region);
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) -> bool {
+        for tree in self.trees.values_mut() {
+            let found = tree.iter().find(|r| r.name == name).cloned();
+            if let Some(region) = found {
+                return tree.remove(&region).is_some();
+            }
+        }
+        false
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Region> {
+        self.trees.values().flat_map(|tree| tree.iter())
+    }
+
+    pub(crate) fn query(&self, pos: &Position) -> Vec<&Region> {
+        let Some(tree) = self.trees.get(&pos.dimension) else {
+            return Vec::new();
+        };
+        tree.locate_in_envelope_intersecting(AABB::from_point(corner(pos))).filter(|region| region.contains(pos)).collect()
+    }
+}
+
+pub(crate) fn add_region(region: Region) {
+    REGIONS.with(|r| r.borrow_mut().insert(region));
+}
+
+pub(crate) fn remove_region(name: &str) -> bool {
+    REGIONS.with(|r| r.borrow_mut().remove(name))
+}
+
+/// Every registered region, sorted by name.
+pub(crate) fn regions() -> Vec<Region> {
+    let mut all: Vec<Region> = REGIONS.with(|r| r.borrow().iter().cloned().collect());
+    all.sort_by(|a, b| a.name.cmp(&b.name));
+    all
+}
+
+pub(crate) fn denies(pos: &Position, rule: &str) -> Option<String> {
+    if !dimensions::protects(&pos.dimension) {
+        return None;
+    }
+    REGIONS.with(|r| r.borrow().query(pos).into_iter().find(|region| region.has_rule(rule)).map(|region| region.name.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: i32, y: i32, z: i32, dimension: &str) -> Position {
+        Position { x, y, z, dimension: dimension.into() }
+    }
+
+    fn region(name: &str, min: (i32, i32, i32), max: (i32, i32, i32), rules: &[&str]) -> Region {
+        Region {
+            name: name.into(),
+            min: pos(min.0, min.1, min.2, "overworld"),
+            max: pos(max.0, max.1, max.2, "overworld"),
+            rules: rules.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    fn names(found: Vec<&Region>) -> Vec<&str> {
+        let mut names: Vec<&str> = found.into_iter().map(|r| r.name.as_str()).collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn query_includes_the_bounds() {
+        let mut index = SpatialIndex::new();
+        index.insert(region("spawn", (-10, 0, -10), (10, 64, 10), &[RULE_NO_BREAK]));
+        index.insert(region("farm", (5, 0, 5), (20, 64, 20), &[RULE_NO_CROP_GROWTH]));
+        assert_eq!(names(index.query(&pos(10, 64, 10, "overworld"))), ["farm", "spawn"]);
+        assert_eq!(names(index.query(&pos(-10, 0, -10, "overworld"))), ["spawn"]);
+        assert!(index.query(&pos(11, 0, -10, "overworld")).is_empty());
+    }
+
+    #[test]
+    fn dimensions_are_indexed_separately() {
+        let mut index = SpatialIndex::new();
+        index.insert(region("spawn", (0, 0, 0), (10, 10, 10), &[]));
+        assert!(index.query(&pos(5, 5, 5, "nether")).is_empty());
+    }
+
+    #[test]
+    fn insert_replaces_by_name() {
+        let mut index = SpatialIndex::new();
+        index.insert(region("spawn", (0, 0, 0), (10, 10, 10), &[]));
+        index.insert(region("spawn", (100, 0, 100), (110, 10, 110), &[RULE_NO_ENTRY]));
+        assert_eq!(index.iter().count(), 1);
+        assert!(index.query(&pos(5, 5, 5, "overworld")).is_empty());
+        assert!(index.query(&pos(105, 5, 105, "overworld"))[0].has_rule(RULE_NO_ENTRY));
+        assert!(index.remove("spawn"));
+        assert!(!index.remove("spawn"));
+    }
+
+    #[test]
+    fn regions_far_apart_are_indexed() {
+        let mut index = SpatialIndex::new();
+        for i in -30..30 {
+            let x = i * 1_000_000;
+            index.insert(region(&format!("r{i}"), (x, i32::MIN, -x), (x + 500_000, i32::MAX, 500_000 - x), &[]));
+        }
+        assert_eq!(names(index.query(&pos(-30_000_000, 0, 30_000_000, "overworld"))), ["r-30"]);
+        assert_eq!(names(index.query(&pos(29_400_000, i32::MAX, -29_000_000, "overworld"))), ["r29"]);
+        assert!(index.query(&pos(29_500_001, 0, -29_000_000, "overworld")).is_empty());
+    }
+
+    #[test]
+    fn denies_names_the_first_region_with_the_rule() {
+        add_region(region("vault", (0, 0, 0), (4, 4, 4), &[RULE_NO_CONTAINER, RULE_NO_BREAK]));
+        assert_eq!(denies(&pos(1, 1, 1, "overworld"), RULE_NO_CONTAINER).as_deref(), Some("vault"));
+        assert_eq!(denies(&pos(1, 1, 1, "overworld"), RULE_NO_ENTRY), None);
+        assert_eq!(regions().len(), 1);
+    }
+
+    #[test]
+    fn contains_checks_every_axis_and_the_dimension() {
+        let spawn = region("spawn", (10, 64, 10), (-10, 0, -10), &[]);
+        assert!(spawn.contains(&pos(-10, 0, 10, "overworld")));
+        assert!(spawn.contains(&pos(0, 32, 0, "overworld")));
+        assert!(!spawn.contains(&pos(0, 65, 0, "overworld")));
+        assert!(!spawn.contains(&pos(11, 32, 0, "overworld")));
+        assert!(!spawn.contains(&pos(0, 32, 0, "nether")));
+    }
+
+    #[test]
+    fn removed_regions_stop_denying() {
+        add_region(region("vault", (0, 0, 0), (4, 4, 4), &[RULE_NO_BREAK]));
+        assert_eq!(denies(&pos(1, 1, 1, "overworld"), RULE_NO_BREAK).as_deref(), Some("vault"));
+        assert!(remove_region("vault"));
+        assert!(!remove_region("vault"));
+        assert_eq!(denies(&pos(1, 1, 1, "overworld"), RULE_NO_BREAK), None);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::codec;
+use crate::dimensions;
+use crate::error::PluginError;
+use crate::EventResult;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+pub(crate) type HandlerResult = Result<EventResult, PluginError>;
+
+/// An event payload the host can dispatch; implemented by `#[derive(PluginEvent)]`.
+pub(crate) trait PluginEvent: DeserializeOwned {
+    const EVENT_NAME: &'static str;
+
+    /// Runs the handler the derive names, `on_<EVENT_NAME>` unless overridden.
+    fn handle(self) -> HandlerResult;
+
+    /// The dimension the event happens in, from the field named by
+    /// `#[event(dimension = ...)]`; `None` for events not tied to one.
+    fn dimension(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// A field that can name an event's dimension.
+pub(crate) trait Dimension {
+    fn dimension(&self) -> Option<&str>;
+}
+
+impl Dimension for String {
+    fn dimension(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl<T: Dimension> Dimension for Option<T> {
+    fn dimension(&self) -> Option<&str> {
+        self.as_ref().and_then(Dimension::dimension)
+    }
+}
+
+type BoxedHandler = Box<dyn Fn(&[u8]) -> HandlerResult>;
+
+/// Decodes each event payload into its typed struct before calling the handler
+/// registered for that event name.
+#[derive(Default)]
+pub(crate) struct TypedEventRouter {
+    handlers: HashMap<&'static str, BoxedHandler>,
+}
+
+impl TypedEventRouter {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Panics if a handler is already registered for `name`.
+    pub(crate) fn register<E, F>(&mut self, name: &'static str, handler: F) -> &mut Self
+    where
+        E: PluginEvent,
+        F: Fn(E) -> HandlerResult + 'static,
+    {
+        let previous = self.handlers.insert(
+            name,
+            Box::new(move |data| {
+                let ev = codec::decode(data).map_err(|source| PluginError::Decode { event: name.into(), source })?;
+                handler(ev).map_err(|source| PluginError::Handler { event: name.into(), source: Box::new(source) })
+            }),
+        );
+        assert!(previous.is_none(), "duplicate handler registered for event {name}");
+        self
+    }
+
+    /// Registers `E`'s own handler under its event name. Events in a dimension
+    /// the registry excludes are not handled.
+    pub(crate) fn register_event<E: PluginEvent + 'static>(&mut self) -> &mut Self {
+        self.register(E::EVENT_NAME, |ev: E| match ev.dimension() {
+            Some(dimension) if !dimensions::protects(dimension) => Ok(EventResult::default()),
+            _ => ev.handle(),
+        })
+    }
+
+    /// Registered event names, sorted.
+    pub(crate) fn events(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self.handlers.keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Routes a `handle_event` envelope and encodes the handler's result.
+    /// Unknown events and payloads in another encoding produce an empty result.
+    pub(crate) fn dispatch(&self, envelope: &[u8]) -> Result<Vec<u8>, PluginError> {
+        let (event_type, encoding, payload) = split_envelope(envelope).ok_or(PluginError::MalformedEnvelope)?;
+        if encoding != codec::ENCODING {
+            crate::LOGGER.warn(&format!("ignoring {event_type}: unsupported payload encoding {encoding}"), None);
+            return Ok(vec![0]);
+        }
+
+        let res = match self.handlers.get(event_type) {
+            Some(handler) => handler(payload)?,
+            None => EventResult::default(),
+        };
+
+        let mut out = vec![u8::from(res.cancelled)];
+        if let Some(mods) = res.modifications.as_ref().filter(|_| !res.cancelled) {
+            out.extend(codec::encode(mods).map_err(PluginError::Encode)?);
+        }
+        Ok(out)
+    }
+}
+
+pub(crate) fn split_envelope(envelope: &[u8]) -> Option<(&str, u8, &[u8])> {
+    let len = u32::from_le_bytes(envelope.get(..4)?.try_into().ok()?) as usize;
+    let end = len.checked_add(4)?;
+    let event_type = std::str::from_utf8(envelope.get(4..end)?).ok()?;
+    let encoding = *envelope.get(end)?;
+    Some((event_type, encoding, &envelope[end + 1..]))
+}
+
+/// Builds the `handle_event` envelope the host sends for `event`.
+pub(crate) fn envelope(event: &str, encoding: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = (event.len() as u32).to_le_bytes().to_vec();
+    out.extend(event.as_bytes());
+    out.push(encoding);
+    out.extend(payload);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dragonfly_wasm_macros::PluginEvent;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PluginEvent)]
+    #[event(name = "ping")]
+    struct Ping {
+        cancel: bool,
+    }
+
+    fn on_ping(ev: Ping) -> HandlerResult {
+        if ev.cancel {
+            return Ok(EventResult::cancel().modify("ignored", true));
+        }
+        Ok(EventResult::default().modify("pong", 1))
+    }
+
+    fn router() -> TypedEventRouter {
+        let mut router = TypedEventRouter::new();
+        router.register_event::<Ping>();
+        router
+    }
+
+    #[test]
+    fn envelope_round_trips() {
+        let payload = codec::encode(&serde_json::json!({ "cancel": false })).unwrap();
+        let env = envelope("ping", codec::ENCODING, &payload);
+        assert_eq!(split_envelope(&env), Some(("ping", codec::ENCODING, payload.as_slice())));
+    }
+
+    #[test]
+    fn truncated_envelopes_are_rejected() {
+        let env = envelope("ping", codec::ENCODING, b"");
+        for len in 0..env.len() {
+            assert_eq!(split_envelope(&env[..len]), None);
+        }
+        assert_eq!(split_envelope(&[0xFF, 0xFF, 0xFF, 0xFF, 0]), None);
+        assert_eq!(split_envelope(&[2, 0, 0, 0, 0xC3, 0x28, 0]), None);
+        assert!(matches!(router().dispatch(&[1, 0]), Err(PluginError::MalformedEnvelope)));
+    }
+
+    #[test]
+    fn dispatch_encodes_modifications() {
+        let payload = codec::encode(&serde_json::json!({ "cancel": false })).unwrap();
+        let out = router().dispatch(&envelope("ping", codec::ENCODING, &payload)).unwrap();
+        assert_eq!(out[0], 0);
+        let mods: serde_json::Value = codec::decode(&out[1..]).unwrap();
+        assert_eq!(mods, serde_json::json!({ "pong": 1 }));
+    }
+
+    #[test]
+    fn cancelled_results_drop_modifications() {
+        let payload = codec::encode(&serde_json::json!({ "cancel": true })).unwrap();
+        assert_eq!(router().dispatch(&envelope("ping", codec::ENCODING, &payload)).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn unknown_events_and_foreign_encodings_are_ignored() {
+        let payload = codec::encode(&serde_json::json!({ "cancel": true })).unwrap();
+        assert_eq!(router().dispatch(&envelope("pong", codec::ENCODING, &payload)).unwrap(), vec![0]);
+        assert_eq!(router().dispatch(&envelope("ping", codec::ENCODING ^ 1, &payload)).unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn undecodable_payloads_name_the_event() {
+        let payload = codec::encode(&serde_json::json!({ "cancel": "yes" })).unwrap();
+        let err = router().dispatch(&envelope("ping", codec::ENCODING, &payload)).unwrap_err();
+        assert!(matches!(&err, PluginError::Decode { event, .. } if event == "ping"));
+        assert_eq!(err.error_code(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate handler registered for event ping")]
+    fn duplicate_registration_panics() {
+        router().register("ping", on_ping);
+    }
+
+    #[test]
+    fn events_are_sorted() {
+        let mut router = router();
+        router.register("a_first", on_ping);
+        assert_eq!(router.events(), vec!["a_first", "ping"]);
+    }
+}
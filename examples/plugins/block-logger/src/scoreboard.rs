@@ -0,0 +1,43 @@
+use crate::codec;
+use crate::error::host_rejected;
+use crate::host;
+use extism_pdk::*;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Scoreboard {
+    pub(crate) name: String,
+    pub(crate) display_name: String,
+    pub(crate) criteria: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ScoreEntry {
+    pub(crate) objective: String,
+    pub(crate) player: String,
+    pub(crate) score: i32,
+}
+
+#[derive(Serialize)]
+struct RemoveRequest<'a> {
+    name: &'a str,
+}
+
+pub(crate) fn create(board: &Scoreboard) -> Result<(), Error> {
+    check("create", unsafe { host::host_scoreboard_create(&codec::encode(board)?)? })
+}
+
+pub(crate) fn set_score(entry: &ScoreEntry) -> Result<(), Error> {
+    check("set_score", unsafe { host::host_scoreboard_set_score(&codec::encode(entry)?)? })
+}
+
+pub(crate) fn remove(name: &str) -> Result<(), Error> {
+    check("remove", unsafe { host::host_scoreboard_remove(&codec::encode(&RemoveRequest { name })?)? })
+}
+
+fn check(op: &str, code: i64) -> Result<(), Error> {
+    match code {
+        1 => Ok(()),
+        code => Err(host_rejected(format!("scoreboard {op}"), code)),
+    }
+}
@@ -0,0 +1,97 @@
+use crate::codec;
+use crate::error::host_rejected;
+use crate::host;
+use extism_pdk::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+#[derive(Serialize)]
+struct StoreRequest {
+    namespace: String,
+    key: String,
+    value: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct LoadRequest {
+    namespace: String,
+    key: String,
+}
+
+/// Typed view over one host storage namespace. Values are stored with the
+/// plugin's payload encoding; storing an empty value deletes the key.
+pub(crate) struct KvStore<T> {
+    namespace: String,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> KvStore<T> {
+    pub(crate) fn new(namespace: impl Into<String>) -> Self {
+        Self { namespace: namespace.into(), _marker: PhantomData }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Result<Option<T>, Error> {
+        let req = LoadRequest { namespace: self.namespace.clone(), key: key.into() };
+        let res = unsafe { host::host_load_data(&codec::encode(&req)?)? };
+        if res.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(codec::decode(&res)?))
+    }
+
+    pub(crate) fn set(&self, key: &str, value: &T) -> Result<(), Error> {
+        self.store(key, codec::encode(value)?)
+    }
+
+    pub(crate) fn delete(&self, key: &str) -> Result<(), Error> {
+        self.store(key, Vec::new())
+    }
+
+    fn store(&self, key: &str, value: Vec<u8>) -> Result<(), Error> {
+        let req = StoreRequest { namespace: self.namespace.clone(), key: key.into(), value };
+        match unsafe { host::host_store_data(&codec::encode(&req)?)? } {
+            1 => Ok(()),
+            code => Err(host_rejected(format!("store of {}/{key}", self.namespace), code)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::mock::{self, Reply};
+
+    fn stored() -> Vec<serde_json::Value> {
+        mock::calls("host_store_data").iter().map(|c| codec::decode(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn values_are_stored_and_loaded_in_the_payload_encoding() {
+        let store = KvStore::<Vec<u32>>::new("test");
+        mock::reply("host_store_data", Reply::Code(1));
+        store.set("key", &vec![1, 2, 3]).unwrap();
+        let sent = &stored()[0];
+        assert_eq!((&sent["namespace"], &sent["key"]), (&serde_json::json!("test"), &serde_json::json!("key")));
+        let value: Vec<u8> = serde_json::from_value(sent["value"].clone()).unwrap();
+        assert_eq!(value, codec::encode(&vec![1, 2, 3]).unwrap());
+
+        mock::reply("host_load_data", Reply::Bytes(value));
+        assert_eq!(store.get("key").unwrap(), Some(vec![1, 2, 3]));
+        let loaded: serde_json::Value = codec::decode(&mock::calls("host_load_data")[0]).unwrap();
+        assert_eq!(loaded, serde_json::json!({ "namespace": "test", "key": "key" }));
+    }
+
+    #[test]
+    fn deleting_stores_an_empty_value() {
+        let store = KvStore::<String>::new("test");
+        mock::reply("host_store_data", Reply::Code(1));
+        store.delete("key").unwrap();
+        assert_eq!(stored()[0]["value"], serde_json::json!([]));
+
+        mock::reply("host_load_data", Reply::Bytes(Vec::new()));
+        assert_eq!(store.get("key").unwrap(), None);
+        mock::reply("host_store_data", Reply::Code(0));
+        assert!(store.delete("key").unwrap_err().to_string().contains("test/key"));
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+/// The sixteen chat colours, each sent as a `§` code, or by name in JSON components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Color {
+    Black,
+    DarkBlue,
+    DarkGreen,
+    DarkAqua,
+    DarkRed,
+    DarkPurple,
+    Gold,
+    Gray,
+    DarkGray,
+    Blue,
+    Green,
+    Aqua,
+    Red,
+    LightPurple,
+    Yellow,
+    White,
+}
+
+impl Color {
+    fn code(self) -> char {
+        match self {
+            Color::Black => '0',
+            Color::DarkBlue => '1',
+            Color::DarkGreen => '2',
+            Color::DarkAqua => '3',
+            Color::DarkRed => '4',
+            Color::DarkPurple => '5',
+            Color::Gold => '6',
+            Color::Gray => '7',
+            Color::DarkGray => '8',
+            Color::Blue => '9',
+            Color::Green => 'a',
+            Color::Aqua => 'b',
+            Color::Red => 'c',
+            Color::LightPurple => 'd',
+            Color::Yellow => 'e',
+            Color::White => 'f',
+        }
+    }
+}
+
+/// Builder for styled chat text. Bedrock clients do not understand JSON text
+/// components, so messages are rendered with `§` formatting codes before they
+/// are sent. Appended components inherit the style of their parent, so
+/// mixed-style messages start from an unstyled `FormattedText::text("")`.
+/// `to_json` renders the same text as a JSON text component, including the
+/// click and hover events that `§` codes cannot express.
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct FormattedText {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<Color>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bold: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    italic: Option<bool>,
+    #[serde(rename = "clickEvent", skip_serializing_if = "Option::is_none")]
+    click_event: Option<TextEvent>,
+    #[serde(rename = "hoverEvent", skip_serializing_if = "Option::is_none")]
+    hover_event: Option<TextEvent>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    extra: Vec<FormattedText>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TextEvent {
+    action: String,
+    value: String,
+}
+
+impl FormattedText {
+    pub(crate) fn text(s: &str) -> Self {
+        Self { text: s.into(), ..Self::default() }
+    }
+
+    pub(crate) fn color(mut self, c: Color) -> Self {
+        self.color = Some(c);
+        self
+    }
+
+    pub(crate) fn bold(mut self, b: bool) -> Self {
+        self.bold = Some(b);
+        self
+    }
+
+    pub(crate) fn italic(mut self, b: bool) -> Self {
+        self.italic = Some(b);
+        self
+    }
+
+    /// e.g. `click_event("suggest_command", "/blockstats Steve")`.
+    pub(crate) fn click_event(mut self, action: &str, value: &str) -> Self {
+        self.click_event = Some(TextEvent { action: action.into(), value: value.into() });
+        self
+    }
+
+    /// e.g. `hover_event("show_text", "Broken since joining")`.
+    pub(crate) fn hover_event(mut self, action: &str, value: &str) -> Self {
+        self.hover_event = Some(TextEvent { action: action.into(), value: value.into() });
+        self
+    }
+
+    pub(crate) fn append(mut self, child: FormattedText) -> Self {
+        self.extra.push(child);
+        self
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("text components always serialize")
+    }
+
+    /// Renders the text with `§` codes, emitting codes only where the style changes.
+    pub(crate) fn to_legacy(&self) -> String {
+        let mut out = Legacy::default();
+        self.render(Style::default(), &mut out);
+        out.text
+    }
+
+    fn render(&self, parent: Style, out: &mut Legacy) {
+        let style = Style {
+            color: self.color.or(parent.color),
+            bold: self.bold.unwrap_or(parent.bold),
+            italic: self.italic.unwrap_or(parent.italic),
+        };
+        out.push(style, &self.text);
+        for child in &self.extra {
+            child.render(style, out);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    color: Option<Color>,
+    bold: bool,
+    italic: bool,
+}
+
+#[derive(Default)]
+struct Legacy {
+    text: String,
+    current: Style,
+}
+
+impl Legacy {
+    fn push(&mut self, style: Style, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if style != self.current {
+            // Bedrock keeps bold and italic across colour codes, so dropping any style needs a reset.
+            let dropped = (self.current.bold && !style.bold) || (self.current.italic && !style.italic) || (self.current.color.is_some() && style.color.is_none());
+            let mut from = self.current;
+            if dropped {
+                self.text.push_str("§r");
+                from = Style::default();
+            }
+            if let Some(c) = style.color.filter(|c| from.color != Some(*c)) {
+                self.text.push('§');
+                self.text.push(c.code());
+            }
+            if style.bold && !from.bold {
+                self.text.push_str("§l");
+            }
+            if style.italic && !from.italic {
+                self.text.push_str("§o");
+            }
+            self.current = style;
+        }
+        self.text.push_str(text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_has_no_codes() {
+        assert_eq!(FormattedText::text("hello").to_legacy(), "hello");
+    }
+
+    #[test]
+    fn colour_and_bold_become_codes() {
+        assert_eq!(FormattedText::text("Protected!").color(Color::Red).bold(true).to_legacy(), "§c§lProtected!");
+    }
+
+    #[test]
+    fn children_inherit_and_override_style() {
+        let msg = FormattedText::text("").append(FormattedText::text("Broken: ").color(Color::Gray)).append(FormattedText::text("12").color(Color::White));
+        assert_eq!(msg.to_legacy(), "§7Broken: §f12");
+        let nested = FormattedText::text("a").color(Color::Gold).append(FormattedText::text("b").italic(true));
+        assert_eq!(nested.to_legacy(), "§6a§ob");
+    }
+
+    #[test]
+    fn dropping_bold_resets_formatting() {
+        let msg = FormattedText::text("").append(FormattedText::text("Protected! ").color(Color::Red).bold(true)).append(FormattedText::text("no").color(Color::Gray));
+        assert_eq!(msg.to_legacy(), "§c§lProtected! §r§7no");
+    }
+
+    #[test]
+    fn every_colour_has_its_own_code() {
+        let colours = [
+            Color::Black, Color::DarkBlue, Color::DarkGreen, Color::DarkAqua, Color::DarkRed, Color::DarkPurple, Color::Gold, Color::Gray,
+            Color::DarkGray, Color::Blue, Color::Green, Color::Aqua, Color::Red, Color::LightPurple, Color::Yellow, Color::White,
+        ];
+        let codes: String = colours.iter().map(|c| c.code()).collect();
+        assert_eq!(codes, "0123456789abcdef");
+        assert_eq!(serde_json::from_str::<Color>("\"light_purple\"").unwrap(), Color::LightPurple);
+    }
+
+    #[test]
+    fn json_components_round_trip() {
+        let msg = FormattedText::text("")
+            .append(FormattedText::text("Steve").color(Color::DarkGreen).bold(true).click_event("suggest_command", "/blockstats Steve"))
+            .append(FormattedText::text(" broke \"12\" blocks").hover_event("show_text", "since joining"));
+        let json: serde_json::Value = serde_json::from_str(&msg.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "text": "",
+                "extra": [
+                    { "text": "Steve", "color": "dark_green", "bold": true, "clickEvent": { "action": "suggest_command", "value": "/blockstats Steve" } },
+                    { "text": " broke \"12\" blocks", "hoverEvent": { "action": "show_text", "value": "since joining" } },
+                ],
+            })
+        );
+        assert_eq!(serde_json::from_value::<Color>(json["extra"][0]["color"].clone()).unwrap(), Color::DarkGreen);
+        assert_eq!(msg.to_legacy(), "§2§lSteve§r broke \"12\" blocks");
+    }
+
+    #[test]
+    fn unstyled_text_after_colour_resets() {
+        let msg = FormattedText::text("x").color(Color::Aqua).append(FormattedText::text("y").append(FormattedText::text("z")));
+        assert_eq!(msg.to_legacy(), "§bxyz");
+        let msg = FormattedText::text("").append(FormattedText::text("x").color(Color::Aqua)).append(FormattedText::text("y"));
+        assert_eq!(msg.to_legacy(), "§bx§ry");
+    }
+}
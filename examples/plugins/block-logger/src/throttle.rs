@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+/// Collects messages per player and releases them as one batch at most every
+/// `flush_interval_ms`. A player's first message in an interval is due immediately.
+#[derive(Debug, Clone)]
+pub(crate) struct NotificationThrottler {
+    pending: HashMap<String, Vec<String>>,
+    flush_interval_ms: u64,
+    last_flush: HashMap<String, u64>,
+}
+
+impl NotificationThrottler {
+    pub(crate) fn new(flush_interval_ms: u64) -> Self {
+        Self { pending: HashMap::new(), flush_interval_ms, last_flush: HashMap::new() }
+    }
+
+    /// Queues `msg`, rendered with `§` codes, for the player; a message identical to one still pending is dropped.
+    pub(crate) fn enqueue(&mut self, player_uuid: &str, msg: String, now_ms: u64) {
+        let queue = self.pending.entry(player_uuid.into()).or_default();
+        if !queue.contains(&msg) {
+            queue.push(msg);
+        }
+        self.last_flush.retain(|_, last| now_ms.saturating_sub(*last) < self.flush_interval_ms);
+    }
+
+    /// Removes and returns every batch whose interval has passed, one `(player_uuid, message)` per player.
+    /// Batches of several messages are joined one message per line.
+    pub(crate) fn flush_due(&mut self, now_ms: u64) -> Vec<(String, String)> {
+        let due: Vec<String> = self
+            .pending
+            .keys()
+            .filter(|uuid| self.last_flush.get(*uuid).is_none_or(|last| now_ms.saturating_sub(*last) >= self.flush_interval_ms))
+            .cloned()
+            .collect();
+
+        due.into_iter()
+            .filter_map(|uuid| {
+                let msgs = self.pending.remove(&uuid)?;
+                self.last_flush.insert(uuid.clone(), now_ms);
+                Some((uuid, msgs.join("\n")))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_message_is_due_immediately() {
+        let mut t = NotificationThrottler::new(500);
+        t.enqueue("steve", "hi".into(), 1000);
+        assert_eq!(t.flush_due(1000), vec![("steve".to_string(), "hi".to_string())]);
+        assert!(t.flush_due(1000).is_empty());
+    }
+
+    #[test]
+    fn messages_within_an_interval_are_batched() {
+        let mut t = NotificationThrottler::new(500);
+        t.enqueue("steve", "a".into(), 0);
+        t.flush_due(0);
+        t.enqueue("steve", "b".into(), 100);
+        t.enqueue("steve", "c".into(), 200);
+        t.enqueue("steve", "b".into(), 300);
+        assert!(t.flush_due(400).is_empty());
+        assert_eq!(t.flush_due(500), vec![("steve".to_string(), "b\nc".to_string())]);
+    }
+
+    #[test]
+    fn players_are_throttled_separately() {
+        let mut t = NotificationThrottler::new(500);
+        t.enqueue("steve", "a".into(), 0);
+        t.flush_due(0);
+        t.enqueue("steve", "b".into(), 10);
+        t.enqueue("alex", "c".into(), 10);
+        assert_eq!(t.flush_due(10), vec![("alex".to_string(), "c".to_string())]);
+    }
+}
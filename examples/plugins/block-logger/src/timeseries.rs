@@ -0,0 +1,102 @@
+use crate::Stats;
+use std::collections::VecDeque;
+
+const MINUTE_MS: u64 = 60_000;
+
+#[derive(Debug, Clone)]
+pub(crate) struct TimeSeries {
+    buckets: VecDeque<(u64, Stats)>,
+    bucket_width_ms: u64,
+    window_count: usize,
+}
+
+impl TimeSeries {
+    pub(crate) fn new(bucket_width_ms: u64, window_count: usize) -> Self {
+        Self { buckets: VecDeque::with_capacity(window_count), bucket_width_ms: bucket_width_ms.max(1), window_count: window_count.max(1) }
+    }
+
+    pub(crate) fn push(&mut self, now_ms: u64, delta: &Stats) {
+        let start = now_ms - now_ms % self.bucket_width_ms;
+        match self.buckets.back_mut() {
+            Some((bucket_start, stats)) if *bucket_start == start => add(stats, delta),
+            _ => self.buckets.push_back((start, delta.clone())),
+        }
+        self.trim(now_ms);
+    }
+
+    /// Averages the buckets still inside the window, scaled to one minute.
+    pub(crate) fn rate_per_minute(&self, now_ms: u64) -> Stats {
+        let cutoff = self.cutoff(now_ms);
+        let mut total = Stats::default();
+        for (_, stats) in self.buckets.iter().filter(|(start, _)| *start >= cutoff) {
+            add(&mut total, stats);
+        }
+
+        let span = self.bucket_width_ms * self.window_count as u64;
+        let scale = |v: u64| v * MINUTE_MS / span;
+        Stats {
+            broken: scale(total.broken),
+            placed: scale(total.placed),
+            denied: scale(total.denied),
+            kills: scale(total.kills),
+            items_dropped: scale(total.items_dropped),
+            items_picked: scale(total.items_picked),
+        }
+    }
+
+    pub(crate) fn trim(&mut self, now_ms: u64) {
+        let cutoff = self.cutoff(now_ms);
+        while self.buckets.front().is_some_and(|(start, _)| *start < cutoff) {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn cutoff(&self, now_ms: u64) -> u64 {
+        let current = now_ms - now_ms % self.bucket_width_ms;
+        current.saturating_sub(self.bucket_width_ms * (self.window_count as u64 - 1))
+    }
+}
+
+fn add(into: &mut Stats, delta: &Stats) {
+    into.broken += delta.broken;
+    into.placed += delta.placed;
+    into.denied += delta.denied;
+    into.kills += delta.kills;
+    into.items_dropped += delta.items_dropped;
+    into.items_picked += delta.items_picked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broken(n: u64) -> Stats {
+        Stats { broken: n, ..Stats::default() }
+    }
+
+    #[test]
+    fn rate_scales_the_window_to_a_minute() {
+        let mut ts = TimeSeries::new(10_000, 3);
+        ts.push(0, &broken(2));
+        ts.push(5_000, &broken(1));
+        ts.push(25_000, &broken(3));
+        assert_eq!(ts.rate_per_minute(25_000).broken, 12);
+    }
+
+    #[test]
+    fn old_buckets_fall_out_of_the_window() {
+        let mut ts = TimeSeries::new(10_000, 3);
+        ts.push(0, &broken(6));
+        ts.push(30_000, &broken(1));
+        assert_eq!(ts.rate_per_minute(30_000), broken(2));
+        ts.trim(60_000);
+        assert_eq!(ts.rate_per_minute(60_000), Stats::default());
+    }
+
+    #[test]
+    fn zero_width_buckets_do_not_divide_by_zero() {
+        let mut ts = TimeSeries::new(0, 0);
+        ts.push(7, &broken(1));
+        assert_eq!(ts.rate_per_minute(7).broken, 60_000);
+    }
+}
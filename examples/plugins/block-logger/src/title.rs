@@ -0,0 +1,98 @@
+use crate::codec;
+use crate::error::host_rejected;
+use crate::host;
+use extism_pdk::*;
+use serde::{Deserialize, Serialize};
+
+/// Vanilla title timings, in ticks.
+pub(crate) const DEFAULT_FADE_IN: i32 = 10;
+pub(crate) const DEFAULT_STAY: i32 = 70;
+pub(crate) const DEFAULT_FADE_OUT: i32 = 20;
+
+/// Empty `title`, `subtitle` or `action_bar` fields leave that line untouched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TitleRequest {
+    pub(crate) player_uuid: String,
+    #[serde(default)]
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) subtitle: String,
+    #[serde(default)]
+    pub(crate) action_bar: String,
+    pub(crate) fade_in: i32,
+    pub(crate) stay: i32,
+    pub(crate) fade_out: i32,
+}
+
+impl TitleRequest {
+    fn new(player_uuid: &str) -> Self {
+        Self {
+            player_uuid: player_uuid.into(),
+            title: String::new(),
+            subtitle: String::new(),
+            action_bar: String::new(),
+            fade_in: DEFAULT_FADE_IN,
+            stay: DEFAULT_STAY,
+            fade_out: DEFAULT_FADE_OUT,
+        }
+    }
+}
+
+/// Player list header and footer. Unlike titles, empty strings are sent as-is and clear that line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TablistRequest {
+    pub(crate) player_uuid: String,
+    pub(crate) header: String,
+    pub(crate) footer: String,
+}
+
+pub(crate) fn send(req: &TitleRequest) -> Result<(), Error> {
+    match unsafe { host::host_send_title(&codec::encode(req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("title", code)),
+    }
+}
+
+pub(crate) fn send_title(player_uuid: &str, title: &str, subtitle: &str) -> Result<(), Error> {
+    send(&TitleRequest { title: title.into(), subtitle: subtitle.into(), ..TitleRequest::new(player_uuid) })
+}
+
+pub(crate) fn send_action_bar(player_uuid: &str, msg: &str) -> Result<(), Error> {
+    send(&TitleRequest { action_bar: msg.into(), ..TitleRequest::new(player_uuid) })
+}
+
+pub(crate) fn send_tablist(player_uuid: &str, header: &str, footer: &str) -> Result<(), Error> {
+    let req = TablistRequest { player_uuid: player_uuid.into(), header: header.into(), footer: footer.into() };
+    match unsafe { host::host_send_tablist(&codec::encode(&req)?)? } {
+        1 => Ok(()),
+        code => Err(host_rejected("tablist", code)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::mock::{self, Reply};
+
+    #[test]
+    fn new_requests_use_vanilla_timings() {
+        let req = TitleRequest::new("steve");
+        assert_eq!((req.fade_in, req.stay, req.fade_out), (DEFAULT_FADE_IN, DEFAULT_STAY, DEFAULT_FADE_OUT));
+        assert!(req.title.is_empty() && req.subtitle.is_empty() && req.action_bar.is_empty());
+    }
+
+    #[test]
+    fn missing_lines_decode_as_empty() {
+        let req: TitleRequest = serde_json::from_str(r#"{"player_uuid":"steve","title":"Hi","fade_in":0,"stay":20,"fade_out":0}"#).unwrap();
+        assert_eq!(req.title, "Hi");
+        assert!(req.subtitle.is_empty() && req.action_bar.is_empty());
+    }
+
+    #[test]
+    fn empty_tablist_headers_are_still_sent() {
+        mock::reply("host_send_tablist", Reply::Code(1));
+        send_tablist("steve", "", "§7Broken: 3").unwrap();
+        let sent: serde_json::Value = codec::decode(&mock::calls("host_send_tablist")[0]).unwrap();
+        assert_eq!(sent, serde_json::json!({ "player_uuid": "steve", "header": "", "footer": "§7Broken: 3" }));
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Cooldown {
+    last_triggered: HashMap<String, u64>,
+    duration_ms: u64,
+}
+
+impl Cooldown {
+    pub(crate) fn new(duration_ms: u64) -> Self {
+        Self { last_triggered: HashMap::new(), duration_ms }
+    }
+
+    pub(crate) fn is_ready(&self, key: &str, now_ms: u64) -> bool {
+        match self.last_triggered.get(key) {
+            Some(&last) => now_ms.saturating_sub(last) >= self.duration_ms,
+            None => true,
+        }
+    }
+
+    pub(crate) fn mark(&mut self, key: &str, now_ms: u64) {
+        self.last_triggered.insert(key.into(), now_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn cooldown_tracks_keys_separately() {
+        let mut cd = Cooldown::new(1000);
+        assert!(cd.is_ready("steve", 0));
+        cd.mark("steve", 500);
+        assert!(!cd.is_ready("steve", 1499));
+        assert!(cd.is_ready("steve", 1500));
+        assert!(cd.is_ready("alex", 600));
+    }
+
+    #[test]
+    fn clock_going_backwards_is_not_ready() {
+        let mut cd = Cooldown::new(1000);
+        cd.mark("steve", 5000);
+        assert!(!cd.is_ready("steve", 100));
+    }
+
+    proptest! {
+        #[test]
+        fn ready_exactly_once_the_window_has_passed(duration in 0..100_000u64, marked in any::<u64>(), elapsed in 0..200_000u64) {
+            let mut cd = Cooldown::new(duration);
+            cd.mark("steve", marked);
+            let now = marked.saturating_add(elapsed);
+            prop_assert_eq!(cd.is_ready("steve", now), now - marked >= duration);
+        }
+
+        #[test]
+        fn marking_one_key_leaves_the_others_ready(duration in any::<u64>(), now in any::<u64>(), key in "[a-z]{1,16}") {
+            let mut cd = Cooldown::new(duration);
+            let other = format!("{key}_");
+            cd.mark(&key, now);
+            prop_assert!(cd.is_ready(&other, now));
+        }
+
+        #[test]
+        fn the_latest_mark_wins(duration in 1..100_000u64, first in 0..1_000_000u64, later in 0..1_000_000u64) {
+            let mut cd = Cooldown::new(duration);
+            cd.mark("steve", first);
+            cd.mark("steve", first + later);
+            prop_assert!(!cd.is_ready("steve", first + later));
+        }
+    }
+}
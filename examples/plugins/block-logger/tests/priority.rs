@@ -0,0 +1,31 @@
+use block_logger_plugin::EventPriority;
+
+const ALL: [(EventPriority, &str); 6] = [
+    (EventPriority::Monitor, "monitor"),
+    (EventPriority::Lowest, "lowest"),
+    (EventPriority::Low, "low"),
+    (EventPriority::Normal, "normal"),
+    (EventPriority::High, "high"),
+    (EventPriority::Highest, "highest"),
+];
+
+#[test]
+fn priorities_round_trip_through_json() {
+    for (priority, name) in ALL {
+        let bytes = serde_json::to_vec(&priority).unwrap();
+        assert_eq!(bytes, format!("\"{name}\"").into_bytes());
+        assert_eq!(serde_json::from_slice::<EventPriority>(&bytes).unwrap(), priority);
+    }
+}
+
+#[test]
+fn monitor_runs_after_every_other_priority() {
+    let mut sorted = ALL.map(|(priority, _)| priority);
+    sorted.sort_by_key(|p| p.value());
+    assert_eq!(sorted, [EventPriority::Lowest, EventPriority::Low, EventPriority::Normal, EventPriority::High, EventPriority::Highest, EventPriority::Monitor]);
+}
+
+#[test]
+fn unknown_priorities_are_rejected() {
+    assert!(serde_json::from_str::<EventPriority>("\"urgent\"").is_err());
+}
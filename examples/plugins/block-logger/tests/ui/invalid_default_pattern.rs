@@ -0,0 +1,8 @@
+use block_logger_plugin::assert_namespaced_ids;
+
+// "minecaft" is fine as a namespace, but the missing colon is not.
+const DEFAULT_PATTERNS: &[&str] = &["minecraft:diamond_ore", "minecaft_ancient_debris"];
+
+assert_namespaced_ids!(DEFAULT_PATTERNS);
+
+fn main() {}
@@ -0,0 +1,90 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, DeriveInput, Ident, LitStr, Path};
+
+/// Implements the crate's `router::PluginEvent` trait for an event payload struct.
+///
+/// The struct must carry `#[event(name = "...")]`, which becomes its
+/// `EVENT_NAME`. `PluginEvent::handle` calls the handler `on_<name>`, or the
+/// function given by `#[event(handler = ...)]`, so the event is registered
+/// with `TypedEventRouter::register_event::<Event>()`. World-scoped events
+/// name the field holding their dimension with `#[event(dimension = field)]`;
+/// it must implement `router::Dimension`.
+#[proc_macro_derive(PluginEvent, attributes(event))]
+pub fn derive_plugin_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let EventAttr { name, handler, dimension } = event_attr(input)?;
+    let handler = match handler {
+        Some(path) => quote!(#path),
+        None => {
+            let default = default_handler(&name)?;
+            quote!(#default)
+        }
+    };
+    let dimension = dimension.map(|field| {
+        quote! {
+            fn dimension(&self) -> Option<&str> {
+                crate::router::Dimension::dimension(&self.#field)
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl crate::router::PluginEvent for #ident {
+            const EVENT_NAME: &'static str = #name;
+
+            fn handle(self) -> crate::router::HandlerResult {
+                #handler(self)
+            }
+
+            #dimension
+        }
+    })
+}
+
+struct EventAttr {
+    name: LitStr,
+    handler: Option<Path>,
+    dimension: Option<Ident>,
+}
+
+fn event_attr(input: &DeriveInput) -> syn::Result<EventAttr> {
+    let mut name = None;
+    let mut handler = None;
+    let mut dimension = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("event")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else if meta.path.is_ident("handler") {
+                handler = Some(meta.value()?.parse::<Path>()?);
+                Ok(())
+            } else if meta.path.is_ident("dimension") {
+                dimension = Some(meta.value()?.parse::<Ident>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported event attribute, expected `name`, `handler` or `dimension`"))
+            }
+        })?;
+    }
+
+    match name {
+        Some(lit) if !lit.value().is_empty() => Ok(EventAttr { name: lit, handler, dimension }),
+        Some(lit) => Err(syn::Error::new_spanned(lit, "event name must not be empty")),
+        None => Err(syn::Error::new_spanned(&input.ident, "#[derive(PluginEvent)] requires #[event(name = \"...\")]")),
+    }
+}
+
+/// `on_<name>`, spanned to the name so a missing handler is reported there.
+fn default_handler(name: &LitStr) -> syn::Result<Ident> {
+    let handler = format!("on_{}", name.value());
+    syn::parse_str::<Ident>(&handler)
+        .map(|_| format_ident!("{}", handler, span = name.span()))
+        .map_err(|_| syn::Error::new_spanned(name, format!("`{handler}` is not a valid handler name; set #[event(handler = ...)]")))
+}
@@ -0,0 +1,9 @@
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/handler_glue.rs");
+    t.pass("tests/ui/dimension_glue.rs");
+    t.compile_fail("tests/ui/missing_event_name.rs");
+    t.compile_fail("tests/ui/missing_handler.rs");
+    t.compile_fail("tests/ui/invalid_handler_name.rs");
+}
@@ -0,0 +1,51 @@
+use dragonfly_wasm_macros::PluginEvent;
+use router::PluginEvent as _;
+
+mod router {
+    pub type HandlerResult = Result<&'static str, String>;
+
+    pub trait PluginEvent {
+        const EVENT_NAME: &'static str;
+        fn handle(self) -> HandlerResult;
+
+        fn dimension(&self) -> Option<&str> {
+            None
+        }
+    }
+
+    pub trait Dimension {
+        fn dimension(&self) -> Option<&str>;
+    }
+
+    impl Dimension for String {
+        fn dimension(&self) -> Option<&str> {
+            Some(self)
+        }
+    }
+}
+
+#[derive(PluginEvent)]
+#[event(name = "weather_change", dimension = world)]
+struct WeatherChangeEvent {
+    world: String,
+}
+
+#[derive(PluginEvent)]
+#[event(name = "player_join")]
+struct PlayerJoinEvent;
+
+fn on_weather_change(_: WeatherChangeEvent) -> router::HandlerResult {
+    Ok("changed")
+}
+
+fn on_player_join(_: PlayerJoinEvent) -> router::HandlerResult {
+    Ok("joined")
+}
+
+fn main() {
+    let ev = WeatherChangeEvent { world: "nether".into() };
+    assert_eq!(ev.dimension(), Some("nether"));
+    assert_eq!(ev.handle(), Ok("changed"));
+    assert_eq!(PlayerJoinEvent.dimension(), None);
+    assert_eq!(PlayerJoinEvent.handle(), Ok("joined"));
+}
@@ -0,0 +1,34 @@
+use dragonfly_wasm_macros::PluginEvent;
+use router::PluginEvent as _;
+
+mod router {
+    pub type HandlerResult = Result<&'static str, String>;
+
+    pub trait PluginEvent {
+        const EVENT_NAME: &'static str;
+        fn handle(self) -> HandlerResult;
+    }
+}
+
+#[derive(PluginEvent)]
+#[event(name = "block_break")]
+struct BlockBreakEvent;
+
+#[derive(PluginEvent)]
+#[event(name = "sleep_event", handler = on_sleep)]
+struct SleepEvent;
+
+fn on_block_break(_: BlockBreakEvent) -> router::HandlerResult {
+    Ok("broken")
+}
+
+fn on_sleep(_: SleepEvent) -> router::HandlerResult {
+    Ok("slept")
+}
+
+fn main() {
+    assert_eq!(BlockBreakEvent::EVENT_NAME, "block_break");
+    assert_eq!(BlockBreakEvent.handle(), Ok("broken"));
+    assert_eq!(SleepEvent::EVENT_NAME, "sleep_event");
+    assert_eq!(SleepEvent.handle(), Ok("slept"));
+}
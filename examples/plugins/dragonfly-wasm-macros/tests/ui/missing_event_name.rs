@@ -0,0 +1,15 @@
+use dragonfly_wasm_macros::PluginEvent;
+
+mod router {
+    pub type HandlerResult = Result<&'static str, String>;
+
+    pub trait PluginEvent {
+        const EVENT_NAME: &'static str;
+        fn handle(self) -> HandlerResult;
+    }
+}
+
+#[derive(PluginEvent)]
+struct BlockBreakEvent;
+
+fn main() {}
@@ -0,0 +1,16 @@
+use dragonfly_wasm_macros::PluginEvent;
+
+mod router {
+    pub type HandlerResult = Result<&'static str, String>;
+
+    pub trait PluginEvent {
+        const EVENT_NAME: &'static str;
+        fn handle(self) -> HandlerResult;
+    }
+}
+
+#[derive(PluginEvent)]
+#[event(name = "block_break")]
+struct BlockBreakEvent;
+
+fn main() {}
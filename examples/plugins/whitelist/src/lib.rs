@@ -0,0 +1,336 @@
+use extism_pdk::*;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+
+mod host;
+
+const STORE_NAMESPACE: &str = "whitelist";
+const STORE_KEY: &str = "players";
+const PERM_MANAGE: &str = "whitelist.manage";
+
+thread_local! {
+    static WHITELIST: RefCell<BTreeSet<String>> = const { RefCell::new(BTreeSet::new()) };
+    static CONFIG: RefCell<Config> = RefCell::new(Config::default());
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct Config {
+    players: Vec<String>,
+    kick_message: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { players: Vec::new(), kick_message: "You are not whitelisted on this server.".into() }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Player {
+    uuid: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlayerJoinEvent {
+    player: Player,
+}
+
+#[derive(Deserialize)]
+struct CommandEvent {
+    player: Player,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Add,
+    Remove,
+}
+
+impl Action {
+    /// Applies the action to `list`, returning whether it changed.
+    fn apply(self, list: &mut BTreeSet<String>, name: &str) -> bool {
+        match self {
+            Action::Add => list.insert(name.into()),
+            Action::Remove => list.remove(name),
+        }
+    }
+
+    fn reply(self, name: &str, changed: bool) -> String {
+        match (self, changed) {
+            (Action::Add, true) => format!("{name} added to the whitelist"),
+            (Action::Add, false) => format!("{name} is already whitelisted"),
+            (Action::Remove, true) => format!("{name} removed from the whitelist"),
+            (Action::Remove, false) => format!("{name} is not whitelisted"),
+        }
+    }
+}
+
+/// Parses `/whitelist <add|remove> <name>`; names are matched case-insensitively, so they are lowercased.
+fn parse_args(args: &[String]) -> Result<(Action, String), Error> {
+    let [action, name] = args else {
+        return Err(Error::msg("usage: /whitelist <add|remove> <name>"));
+    };
+    let action = match action.as_str() {
+        "add" => Action::Add,
+        "remove" => Action::Remove,
+        other => return Err(Error::msg(format!("unknown whitelist action: {other}"))),
+    };
+    Ok((action, name.to_ascii_lowercase()))
+}
+
+#[derive(Serialize)]
+struct CommandRegisterRequest {
+    command: String,
+    description: String,
+    permission: String,
+    aliases: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct LogRequest<'a> {
+    level: &'a str,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct SendMessageRequest<'a> {
+    player_uuid: &'a str,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct KickRequest<'a> {
+    player_uuid: &'a str,
+    reason: &'a str,
+}
+
+#[derive(Serialize)]
+struct StoreRequest<'a> {
+    namespace: &'a str,
+    key: &'a str,
+    value: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct LoadRequest<'a> {
+    namespace: &'a str,
+    key: &'a str,
+}
+
+fn log(level: &str, message: &str) {
+    if let Ok(data) = serde_json::to_vec(&LogRequest { level, message }) {
+        unsafe { host::host_log(&data) }.ok();
+    }
+}
+
+fn send_message(uuid: &str, message: &str) {
+    if let Ok(data) = serde_json::to_vec(&SendMessageRequest { player_uuid: uuid, message }) {
+        unsafe { host::host_send_message(&data) }.ok();
+    }
+}
+
+fn kick(uuid: &str, reason: &str) -> Result<(), Error> {
+    let data = serde_json::to_vec(&KickRequest { player_uuid: uuid, reason })?;
+    match unsafe { host::host_kick_player(&data)? } {
+        1 => Ok(()),
+        code => Err(Error::msg(format!("host rejected kick with code {code}"))),
+    }
+}
+
+fn load_config() -> Result<Config, Error> {
+    let data = unsafe { host::host_get_config()? };
+    Ok(toml::from_str(std::str::from_utf8(&data)?)?)
+}
+
+/// Returns the stored whitelist, or `None` if it has never been saved.
+fn load_stored() -> Result<Option<BTreeSet<String>>, Error> {
+    let req = serde_json::to_vec(&LoadRequest { namespace: STORE_NAMESPACE, key: STORE_KEY })?;
+    let res = unsafe { host::host_load_data(&req)? };
+    if res.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(&res)?))
+}
+
+fn persist(list: &BTreeSet<String>) -> Result<(), Error> {
+    let value = serde_json::to_vec(list)?;
+    let req = serde_json::to_vec(&StoreRequest { namespace: STORE_NAMESPACE, key: STORE_KEY, value })?;
+    match unsafe { host::host_store_data(&req)? } {
+        1 => Ok(()),
+        code => Err(Error::msg(format!("host rejected whitelist store with code {code}"))),
+    }
+}
+
+fn is_whitelisted(name: &str) -> bool {
+    WHITELIST.with(|w| w.borrow().contains(&name.to_ascii_lowercase()))
+}
+
+#[plugin_fn]
+pub fn plugin_init() -> FnResult<()> {
+    match load_config() {
+        Ok(cfg) => CONFIG.with(|c| *c.borrow_mut() = cfg),
+        Err(e) => log("warn", &format!("failed to load config, using defaults: {e}")),
+    }
+
+    let players = match load_stored() {
+        Ok(Some(stored)) => stored,
+        Ok(None) => CONFIG.with(|c| c.borrow().players.iter().map(|p| p.to_ascii_lowercase()).collect()),
+        Err(e) => {
+            log("error", &format!("failed to load stored whitelist: {e}"));
+            BTreeSet::new()
+        }
+    };
+    log("info", &format!("whitelist initialized with {} players", players.len()));
+    WHITELIST.with(|w| *w.borrow_mut() = players);
+    Ok(())
+}
+
+#[plugin_fn]
+pub fn handle_event(envelope: Vec<u8>) -> FnResult<Vec<u8>> {
+    let Some((event_type, payload)) = split_envelope(&envelope) else {
+        return Ok(vec![0]);
+    };
+    if event_type != "player_join" {
+        return Ok(vec![0]);
+    }
+
+    let ev: PlayerJoinEvent = serde_json::from_slice(payload)?;
+    if is_whitelisted(&ev.player.name) {
+        return Ok(vec![0]);
+    }
+
+    let reason = CONFIG.with(|c| c.borrow().kick_message.clone());
+    if let Err(e) = kick(&ev.player.uuid, &reason) {
+        log("warn", &format!("failed to kick {}: {e}", ev.player.name));
+    }
+    log("info", &format!("refused {}: not whitelisted", ev.player.name));
+    Ok(vec![1])
+}
+
+/// Splits the `handle_event` envelope into the event type and its JSON payload.
+fn split_envelope(envelope: &[u8]) -> Option<(&str, &[u8])> {
+    let len = u32::from_le_bytes(envelope.get(..4)?.try_into().ok()?) as usize;
+    let end = len.checked_add(4)?;
+    let event_type = std::str::from_utf8(envelope.get(4..end)?).ok()?;
+    // Only JSON payloads are supported; this plugin does not export plugin_encoding.
+    Some((event_type, envelope.get(end + 1..)?))
+}
+
+#[plugin_fn]
+pub fn plugin_register_commands() -> FnResult<Vec<u8>> {
+    let commands = vec![CommandRegisterRequest {
+        command: "whitelist".into(),
+        description: "Add or remove whitelisted players".into(),
+        permission: PERM_MANAGE.into(),
+        aliases: vec!["wl".into()],
+    }];
+    Ok(serde_json::to_vec(&commands)?)
+}
+
+/// Stores the updated list before swapping it in, so a failed store leaves both copies unchanged.
+#[plugin_fn]
+pub fn on_command(data: Vec<u8>) -> FnResult<Vec<u8>> {
+    let ev: CommandEvent = serde_json::from_slice(&data)?;
+    let (action, name) = match parse_args(&ev.args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            send_message(&ev.player.uuid, &e.to_string());
+            return Err(e.into());
+        }
+    };
+
+    let mut list = WHITELIST.with(|w| w.borrow().clone());
+    let changed = action.apply(&mut list, &name);
+    let reply = action.reply(&name, changed);
+    if changed {
+        persist(&list)?;
+        log("info", &format!("{}: {reply}", ev.player.name));
+    }
+    send_message(&ev.player.uuid, &reply);
+
+    let res = serde_json::to_vec(&list)?;
+    WHITELIST.with(|w| *w.borrow_mut() = list);
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(a: &[&str]) -> Vec<String> {
+        a.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn envelope(event_type: &str, payload: &[u8]) -> Vec<u8> {
+        let mut out = (event_type.len() as u32).to_le_bytes().to_vec();
+        out.extend(event_type.as_bytes());
+        out.push(0);
+        out.extend(payload);
+        out
+    }
+
+    #[test]
+    fn parses_add_and_remove() {
+        assert_eq!(parse_args(&args(&["add", "Steve"])).unwrap(), (Action::Add, "steve".to_string()));
+        assert_eq!(parse_args(&args(&["remove", "alex"])).unwrap(), (Action::Remove, "alex".to_string()));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse_args(&args(&["add"])).is_err());
+        assert!(parse_args(&args(&["add", "a", "b"])).is_err());
+        assert_eq!(parse_args(&args(&["ban", "steve"])).unwrap_err().to_string(), "unknown whitelist action: ban");
+    }
+
+    #[test]
+    fn apply_reports_changes() {
+        let mut list = BTreeSet::new();
+        assert!(Action::Add.apply(&mut list, "steve"));
+        assert!(!Action::Add.apply(&mut list, "steve"));
+        assert!(Action::Remove.apply(&mut list, "steve"));
+        assert!(!Action::Remove.apply(&mut list, "steve"));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn replies_are_plain_text() {
+        assert_eq!(Action::Add.reply("steve", true), "steve added to the whitelist");
+        assert_eq!(Action::Remove.reply("steve", false), "steve is not whitelisted");
+        assert!(!Action::Add.reply("steve", false).contains('§'));
+    }
+
+    #[test]
+    fn whitelist_matches_names_case_insensitively() {
+        WHITELIST.with(|w| *w.borrow_mut() = BTreeSet::from(["steve".to_string()]));
+        assert!(is_whitelisted("Steve"));
+        assert!(!is_whitelisted("alex"));
+    }
+
+    #[test]
+    fn splits_envelope() {
+        let env = envelope("player_join", br#"{"player":{"uuid":"u","name":"Steve"}}"#);
+        let (event_type, payload) = split_envelope(&env).unwrap();
+        assert_eq!(event_type, "player_join");
+        let ev: PlayerJoinEvent = serde_json::from_slice(payload).unwrap();
+        assert_eq!(ev.player.name, "Steve");
+    }
+
+    #[test]
+    fn rejects_truncated_envelopes() {
+        assert!(split_envelope(&[]).is_none());
+        assert!(split_envelope(&[20, 0, 0, 0, b'a']).is_none());
+        assert!(split_envelope(&[0xFF, 0xFF, 0xFF, 0xFF]).is_none());
+    }
+
+    #[test]
+    fn config_defaults_the_kick_message() {
+        let cfg: Config = toml::from_str("players = [\"Steve\"]").unwrap();
+        assert_eq!(cfg.players, ["Steve"]);
+        assert_eq!(cfg.kick_message, Config::default().kick_message);
+    }
+}
@@ -1,24 +1,40 @@
 package adapter
 
 import (
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"strings"
 	"sync"
 
 	"github.com/df-mc/dragonfly/server"
+	"github.com/df-mc/dragonfly/server/block"
 	"github.com/df-mc/dragonfly/server/block/cube"
+	"github.com/df-mc/dragonfly/server/cmd"
+	"github.com/df-mc/dragonfly/server/item"
 	"github.com/df-mc/dragonfly/server/player"
+	"github.com/df-mc/dragonfly/server/player/bossbar"
+	"github.com/df-mc/dragonfly/server/player/scoreboard"
+	"github.com/df-mc/dragonfly/server/player/title"
 	"github.com/df-mc/dragonfly/server/world"
+	"github.com/df-mc/dragonfly/server/world/particle"
+	"github.com/df-mc/dragonfly/server/world/sound"
 	"github.com/go-gl/mathgl/mgl64"
+	"github.com/sandertv/gophertunnel/minecraft/protocol"
 
 	"github.com/EinBexiii/dragonfly-wasm/internal/manager"
 )
 
 type Adapter struct {
-	srv     *server.Server
-	players sync.Map
+	srv        *server.Server
+	onlineMode bool
+	players    sync.Map
 }
 
-func NewAdapter(srv *server.Server) *Adapter {
-	return &Adapter{srv: srv}
+// NewAdapter wraps srv. onlineMode reports whether srv authenticates players
+// with Xbox Live, i.e. whether its config leaves AuthDisabled unset.
+func NewAdapter(srv *server.Server, onlineMode bool) *Adapter {
+	return &Adapter{srv: srv, onlineMode: onlineMode}
 }
 
 func (a *Adapter) TrackPlayer(p *player.Player)   { a.players.Store(p.UUID().String(), p) }
@@ -43,7 +59,7 @@ func (a *Adapter) GetAllPlayers() []manager.PlayerAPI {
 
 func (a *Adapter) GetWorld(name string) (manager.WorldAPI, bool) {
 	for _, w := range []*world.World{a.srv.World(), a.srv.Nether(), a.srv.End()} {
-		if w != nil && w.Name() == name {
+		if w != nil && (dimensionName(w.Dimension()) == name || w.Name() == name) {
 			return &WorldAdapter{world: w}, true
 		}
 	}
@@ -61,6 +77,49 @@ func (a *Adapter) BroadcastMessage(msg string) {
 	})
 }
 
+func (a *Adapter) Info() manager.ServerInfo {
+	return manager.ServerInfo{
+		MinecraftVersion: protocol.CurrentVersion,
+		Software:         "Dragonfly",
+		MaxPlayers:       a.srv.MaxPlayerCount(),
+		OnlineMode:       a.onlineMode,
+	}
+}
+
+func (a *Adapter) ExecuteCommand(commandLine string) (string, error) {
+	name, args, _ := strings.Cut(strings.TrimPrefix(commandLine, "/"), " ")
+	command, ok := cmd.ByAlias(name)
+	if !ok {
+		return "", fmt.Errorf("unknown command: %s", name)
+	}
+
+	src := &consoleSource{}
+	<-a.srv.World().Exec(func(tx *world.Tx) {
+		command.Execute(args, src, tx)
+	})
+	if len(src.errors) > 0 {
+		return strings.Join(src.messages, "\n"), errors.New(strings.Join(src.errors, "\n"))
+	}
+	return strings.Join(src.messages, "\n"), nil
+}
+
+// consoleSource collects the output of commands run on behalf of a plugin.
+type consoleSource struct {
+	messages []string
+	errors   []string
+}
+
+func (*consoleSource) Position() mgl64.Vec3 { return mgl64.Vec3{} }
+
+func (s *consoleSource) SendCommandOutput(o *cmd.Output) {
+	for _, msg := range o.Messages() {
+		s.messages = append(s.messages, msg.String())
+	}
+	for _, err := range o.Errors() {
+		s.errors = append(s.errors, err.Error())
+	}
+}
+
 type PlayerAdapter struct {
 	player  *player.Player
 	adapter *Adapter
@@ -71,6 +130,121 @@ func (p *PlayerAdapter) Name() string           { return p.player.Name() }
 func (p *PlayerAdapter) SendMessage(msg string) { p.player.Message(msg) }
 func (p *PlayerAdapter) Kick(reason string)     { p.player.Disconnect(reason) }
 
+func (p *PlayerAdapter) ExecuteCommand(commandLine string) { p.player.ExecuteCommand(commandLine) }
+
+func (p *PlayerAdapter) GiveItem(itemType string, count int) (int, error) {
+	it, ok := world.ItemByName(itemType, 0)
+	if !ok {
+		return 0, fmt.Errorf("unknown item: %s", itemType)
+	}
+	return p.player.Inventory().AddItem(item.NewStack(it, count))
+}
+
+func (p *PlayerAdapter) AddExperience(amount int) {
+	if amount < 0 {
+		p.player.RemoveExperience(-amount)
+		return
+	}
+	p.player.AddExperience(amount)
+}
+
+func (p *PlayerAdapter) Inventory() []*manager.ItemStack {
+	return itemStacks(p.player.Inventory().Slots())
+}
+
+func itemStacks(slots []item.Stack) []*manager.ItemStack {
+	stacks := make([]*manager.ItemStack, len(slots))
+	for i, st := range slots {
+		if st.Empty() {
+			continue
+		}
+		name, _ := st.Item().EncodeItem()
+		stacks[i] = &manager.ItemStack{ItemType: name, Count: st.Count()}
+	}
+	return stacks
+}
+
+func (p *PlayerAdapter) SendScoreboard(title string, lines []string) {
+	sb := scoreboard.New(title)
+	for i, line := range lines {
+		sb.Set(i, line)
+	}
+	p.player.SendScoreboard(sb)
+}
+
+func (p *PlayerAdapter) RemoveScoreboard() { p.player.RemoveScoreboard() }
+
+func (p *PlayerAdapter) SendBossBar(title string, progress float64, colour string) {
+	p.player.SendBossBar(bossbar.New(title).WithHealthPercentage(progress).WithColour(bossBarColour(colour)))
+}
+
+func (p *PlayerAdapter) RemoveBossBar() { p.player.RemoveBossBar() }
+
+func bossBarColour(name string) bossbar.Colour {
+	switch name {
+	case "blue":
+		return bossbar.Blue()
+	case "red":
+		return bossbar.Red()
+	case "green":
+		return bossbar.Green()
+	case "yellow":
+		return bossbar.Yellow()
+	case "white":
+		return bossbar.White()
+	default:
+		return bossbar.Purple()
+	}
+}
+
+func (p *PlayerAdapter) PlaySound(name string) error {
+	s, ok := soundByName(name)
+	if !ok {
+		return fmt.Errorf("unknown sound: %s", name)
+	}
+	p.player.PlaySound(s)
+	return nil
+}
+
+// soundByName maps the Java-style sound names plugins use to Dragonfly sounds.
+func soundByName(name string) (world.Sound, bool) {
+	switch strings.TrimPrefix(name, "minecraft:") {
+	case "block.note_block.pling":
+		return sound.Note{Instrument: sound.Pling(), Pitch: 12}, true
+	case "entity.player.levelup":
+		return sound.LevelUp{}, true
+	case "entity.experience_orb.pickup":
+		return sound.Experience{}, true
+	case "ui.button.click":
+		return sound.Click{}, true
+	default:
+		return nil, false
+	}
+}
+
+func (p *PlayerAdapter) SendTitle(t manager.Title) {
+	p.player.SendTitle(title.New(t.Title).
+		WithSubtitle(t.Subtitle).
+		WithActionText(t.ActionBar).
+		WithFadeInDuration(t.FadeIn).
+		WithDuration(t.Stay).
+		WithFadeOutDuration(t.FadeOut))
+}
+
+// SetTabList always fails: the Bedrock player list has no header or footer.
+func (p *PlayerAdapter) SetTabList(string, string) error {
+	return errors.New("bedrock clients have no player list header or footer")
+}
+
+func (p *PlayerAdapter) SendToast(title, message string) {
+	p.player.SendToast(title, message)
+}
+
+// SetFog always fails: Dragonfly has no API for sending fog to a player.
+func (p *PlayerAdapter) SetFog(string, float64, [3]uint8) error {
+	return errors.New("dragonfly cannot send fog to players")
+}
+
 func (p *PlayerAdapter) Teleport(x, y, z float64, _ string) error {
 	p.player.Teleport(mgl64.Vec3{x, y, z})
 	return nil
@@ -94,11 +268,23 @@ func (p *PlayerAdapter) SetGameMode(mode int) {
 	p.player.SetGameMode(gm)
 }
 
+func (p *PlayerAdapter) GameMode() int {
+	id, _ := world.GameModeID(p.player.GameMode())
+	return id
+}
+
 func (p *PlayerAdapter) Position() (x, y, z float64) {
 	pos := p.player.Position()
 	return pos[0], pos[1], pos[2]
 }
 
+func (p *PlayerAdapter) Rotation() (yaw, pitch float64) {
+	rot := p.player.Rotation()
+	return rot.Yaw(), rot.Pitch()
+}
+
+func (p *PlayerAdapter) OnGround() bool { return p.player.OnGround() }
+
 func (p *PlayerAdapter) World() manager.WorldAPI {
 	if tx := p.player.Tx(); tx != nil {
 		return &WorldAdapter{world: tx.World()}
@@ -112,9 +298,22 @@ type WorldAdapter struct {
 
 func (w *WorldAdapter) Name() string { return w.world.Name() }
 
+func (w *WorldAdapter) Dimension() string { return dimensionName(w.world.Dimension()) }
+
+func dimensionName(dim world.Dimension) string {
+	switch dim {
+	case world.Nether:
+		return "nether"
+	case world.End:
+		return "end"
+	default:
+		return "overworld"
+	}
+}
+
 func (w *WorldAdapter) GetBlock(x, y, z int) (string, map[string]string) {
 	var blockType string
-	w.world.Exec(func(tx *world.Tx) {
+	<-w.world.Exec(func(tx *world.Tx) {
 		if b := tx.Block(cube.Pos{x, y, z}); b != nil {
 			blockType, _ = b.EncodeBlock()
 		} else {
@@ -125,14 +324,102 @@ func (w *WorldAdapter) GetBlock(x, y, z int) (string, map[string]string) {
 }
 
 func (w *WorldAdapter) SetBlock(x, y, z int, blockType string, _ map[string]string) error {
-	block, ok := blockByName(blockType)
+	b, ok := blockByName(blockType)
 	if !ok {
 		return nil
 	}
 	w.world.Exec(func(tx *world.Tx) {
-		tx.SetBlock(cube.Pos{x, y, z}, block, nil)
+		tx.SetBlock(cube.Pos{x, y, z}, b, nil)
 	})
 	return nil
 }
 
+func (w *WorldAdapter) Time() int         { return w.world.Time() }
+func (w *WorldAdapter) SetTime(time int) { w.world.SetTime(time) }
+
+func (w *WorldAdapter) Weather() (raining, thundering bool) {
+	spawn := w.world.Spawn()
+	<-w.world.Exec(func(tx *world.Tx) {
+		raining, thundering = tx.RainingAt(spawn), tx.ThunderingAt(spawn)
+	})
+	return raining, thundering
+}
+
+func (w *WorldAdapter) Difficulty() int { return world.DifficultyID(w.world.Difficulty()) }
+
+func (w *WorldAdapter) SetDifficulty(id int) {
+	if d, ok := world.DifficultyByID(id); ok {
+		w.world.SetDifficulty(d)
+	}
+}
+
+func (w *WorldAdapter) BlockNBT(x, y, z int) map[string]any {
+	var nbt map[string]any
+	<-w.world.Exec(func(tx *world.Tx) {
+		if b, ok := tx.Block(cube.Pos{x, y, z}).(world.NBTer); ok {
+			nbt = b.EncodeNBT()
+		}
+	})
+	return nbt
+}
+
+func (w *WorldAdapter) ContainerInventory(x, y, z int) ([]*manager.ItemStack, bool) {
+	var items []*manager.ItemStack
+	var ok bool
+	<-w.world.Exec(func(tx *world.Tx) {
+		pos := cube.Pos{x, y, z}
+		if c, isContainer := tx.Block(pos).(block.Container); isContainer {
+			items, ok = itemStacks(c.Inventory(tx, pos).Slots()), true
+		}
+	})
+	return items, ok
+}
+
+func (w *WorldAdapter) Entities(x, y, z, radius float64) []manager.Entity {
+	center := mgl64.Vec3{x, y, z}
+	box := cube.Box(x-radius, y-radius, z-radius, x+radius, y+radius, z+radius)
+
+	var entities []manager.Entity
+	<-w.world.Exec(func(tx *world.Tx) {
+		for e := range tx.EntitiesWithin(box) {
+			pos := e.Position()
+			if pos.Sub(center).Len() > radius {
+				continue
+			}
+			id := e.H().UUID()
+			entity := manager.Entity{
+				ID:   binary.BigEndian.Uint64(id[:8]),
+				Type: e.H().Type().EncodeEntity(),
+				X:    pos[0], Y: pos[1], Z: pos[2],
+			}
+			if named, ok := e.(interface{ NameTag() string }); ok {
+				entity.CustomName = named.NameTag()
+			}
+			entities = append(entities, entity)
+		}
+	})
+	return entities
+}
+
+func (w *WorldAdapter) AddParticle(name string, x, y, z float64) error {
+	pos := mgl64.Vec3{x, y, z}
+	var err error
+	<-w.world.Exec(func(tx *world.Tx) {
+		var p world.Particle
+		switch strings.TrimPrefix(name, "minecraft:") {
+		case "block":
+			p = particle.BlockBreak{Block: tx.Block(cube.PosFromVec3(pos))}
+		case "flame":
+			p = particle.Flame{}
+		case "explosion":
+			p = particle.HugeExplosion{}
+		default:
+			err = fmt.Errorf("unknown particle: %s", name)
+			return
+		}
+		tx.AddParticle(pos, p)
+	})
+	return err
+}
+
 func blockByName(_ string) (world.Block, bool) { return nil, false }
@@ -135,6 +135,10 @@ func (h *PlayerHandler) dispatchEvent(eventType plugin.EventType, data map[strin
 	return false, nil
 }
 
+func (h *PlayerHandler) HandleQuit(p *player.Player) {
+	h.dispatchEvent(plugin.EventPlayerQuit, map[string]any{"player": playerToMap(p)})
+}
+
 func (h *PlayerHandler) HandleChat(ctx *player.Context, message *string) {
 	cancelled, mods := h.dispatchEvent(plugin.EventPlayerChat, map[string]any{
 		"player":  playerToMap(ctx.Val()),
@@ -149,7 +153,12 @@ func (h *PlayerHandler) HandleChat(ctx *player.Context, message *string) {
 }
 
 func (h *PlayerHandler) HandleMove(ctx *player.Context, newPos mgl64.Vec3, newRot cube.Rotation) {
+	p := ctx.Val()
 	cancelled, _ := h.dispatchEvent(plugin.EventPlayerMove, map[string]any{
+		"player":       playerToMap(p),
+		"from":         blockPosToMap(cube.PosFromVec3(p.Position())),
+		"to":           blockPosToMap(cube.PosFromVec3(newPos)),
+		"on_ground":    p.OnGround(),
 		"new_position": vec3ToMap(newPos),
 		"new_yaw":      newRot.Yaw(),
 		"new_pitch":    newRot.Pitch(),
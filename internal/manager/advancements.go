@@ -0,0 +1,29 @@
+package manager
+
+import "sync"
+
+// advancements records the custom advancements granted to players through
+// host_grant_advancement. Bedrock has no advancements of its own, so this is
+// the only record of them.
+type advancements struct {
+	mu      sync.Mutex
+	granted map[string]map[string]struct{}
+}
+
+// grant records advancementID for the player and reports whether it is new.
+func (a *advancements) grant(playerUUID, advancementID string) bool {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+
+	if a.granted == nil {
+		a.granted = make(map[string]map[string]struct{})
+	}
+	if a.granted[playerUUID] == nil {
+		a.granted[playerUUID] = make(map[string]struct{})
+	}
+	if _, ok := a.granted[playerUUID][advancementID]; ok {
+		return false
+	}
+	a.granted[playerUUID][advancementID] = struct{}{}
+	return true
+}
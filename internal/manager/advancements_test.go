@@ -0,0 +1,16 @@
+package manager
+
+import "testing"
+
+func TestAdvancementsGrantOnce(t *testing.T) {
+	var a advancements
+	if !a.grant("steve", "blocklogger:first_protected_block") {
+		t.Fatal("expected the first grant to be new")
+	}
+	if a.grant("steve", "blocklogger:first_protected_block") {
+		t.Fatal("expected a repeated grant not to be new")
+	}
+	if !a.grant("alex", "blocklogger:first_protected_block") {
+		t.Fatal("a grant must only apply to its player")
+	}
+}
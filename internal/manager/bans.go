@@ -0,0 +1,50 @@
+package manager
+
+import (
+	"sync"
+	"time"
+)
+
+type ban struct {
+	reason    string
+	expiresAt time.Time
+}
+
+// bans holds the players banned through host_ban_player. A zero expiresAt
+// bans permanently.
+type bans struct {
+	mu      sync.Mutex
+	players map[string]ban
+}
+
+func (b *bans) add(playerUUID, reason string, expiresAt time.Time) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+
+	if b.players == nil {
+		b.players = make(map[string]ban)
+	}
+	b.players[playerUUID] = ban{reason: reason, expiresAt: expiresAt}
+}
+
+// reason returns why the player is banned, if they still are at now.
+func (b *bans) reason(playerUUID string, now time.Time) (string, bool) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+
+	bn, ok := b.players[playerUUID]
+	if !ok {
+		return "", false
+	}
+	if !bn.expiresAt.IsZero() && !now.Before(bn.expiresAt) {
+		delete(b.players, playerUUID)
+		return "", false
+	}
+	return bn.reason, true
+}
+
+// BanReason reports whether a plugin banned the player, and why. The server
+// should refuse players for whom it returns true.
+func (m *Manager) BanReason(playerUUID string) (string, bool) {
+	return m.bans.reason(playerUUID, time.Now())
+}
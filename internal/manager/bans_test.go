@@ -0,0 +1,32 @@
+package manager
+
+import (
+	"testing"
+	"time"
+)
+
+func TestBansPermanent(t *testing.T) {
+	var b bans
+	b.add("steve", "griefing", time.Time{})
+
+	reason, ok := b.reason("steve", time.Now().Add(24*time.Hour))
+	if !ok || reason != "griefing" {
+		t.Fatalf("reason = %q, %v; want griefing, true", reason, ok)
+	}
+	if _, ok := b.reason("alex", time.Now()); ok {
+		t.Fatal("expected alex not to be banned")
+	}
+}
+
+func TestBansExpire(t *testing.T) {
+	var b bans
+	now := time.Now()
+	b.add("steve", "griefing", now.Add(time.Minute))
+
+	if _, ok := b.reason("steve", now); !ok {
+		t.Fatal("expected the ban to hold before it expires")
+	}
+	if _, ok := b.reason("steve", now.Add(time.Minute)); ok {
+		t.Fatal("expected the ban to lift once it expires")
+	}
+}
@@ -0,0 +1,131 @@
+package manager
+
+import (
+	"cmp"
+	"fmt"
+	"slices"
+	"sync"
+)
+
+// maxSidebarLines is the number of lines a Bedrock sidebar can show.
+const maxSidebarLines = 15
+
+type objective struct {
+	displayName string
+	scores      map[string]int
+}
+
+// scoreboards holds the objectives plugins create. Bedrock shows one sidebar
+// per player, so the objective changed last is the one on screen.
+type scoreboards struct {
+	mu         sync.Mutex
+	objectives map[string]*objective
+	shown      string
+}
+
+func (s *scoreboards) create(name, displayName string) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	if s.objectives == nil {
+		s.objectives = make(map[string]*objective)
+	}
+	s.objectives[name] = &objective{displayName: displayName, scores: make(map[string]int)}
+	s.shown = name
+}
+
+func (s *scoreboards) setScore(name, player string, score int) bool {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	obj, ok := s.objectives[name]
+	if !ok {
+		return false
+	}
+	obj.scores[player] = score
+	s.shown = name
+	return true
+}
+
+// remove deletes the objective and reports whether it was on screen.
+func (s *scoreboards) remove(name string) (existed, shown bool) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	if _, ok := s.objectives[name]; !ok {
+		return false, false
+	}
+	delete(s.objectives, name)
+	if s.shown != name {
+		return true, false
+	}
+	s.shown = ""
+	return true, true
+}
+
+// sidebar returns the title and lines of the objective on screen, highest
+// score first.
+func (s *scoreboards) sidebar() (title string, lines []string, ok bool) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	obj, ok := s.objectives[s.shown]
+	if !ok {
+		return "", nil, false
+	}
+
+	players := make([]string, 0, len(obj.scores))
+	for player := range obj.scores {
+		players = append(players, player)
+	}
+	slices.SortFunc(players, func(a, b string) int {
+		if c := cmp.Compare(obj.scores[b], obj.scores[a]); c != 0 {
+			return c
+		}
+		return cmp.Compare(a, b)
+	})
+
+	for _, player := range players[:min(len(players), maxSidebarLines)] {
+		lines = append(lines, fmt.Sprintf("%s: %d", player, obj.scores[player]))
+	}
+	return obj.displayName, lines, true
+}
+
+// bossBars remembers which players see each boss bar, so a bar can be
+// removed from the same players it was shown to.
+type bossBars struct {
+	mu   sync.Mutex
+	bars map[string][]string
+}
+
+// set records the players seeing bar id. It fails when creating a bar that
+// exists or updating one that does not, and returns the players that no
+// longer see the bar.
+func (b *bossBars) set(id string, players []string, create bool) (dropped []string, ok bool) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+
+	if b.bars == nil {
+		b.bars = make(map[string][]string)
+	}
+	old, exists := b.bars[id]
+	if exists == create {
+		return nil, false
+	}
+	b.bars[id] = players
+	for _, uuid := range old {
+		if !slices.Contains(players, uuid) {
+			dropped = append(dropped, uuid)
+		}
+	}
+	return dropped, true
+}
+
+func (b *bossBars) remove(id string) (players []string, ok bool) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+
+	players, ok = b.bars[id]
+	delete(b.bars, id)
+	return players, ok
+}
@@ -0,0 +1,73 @@
+package manager
+
+import (
+	"slices"
+	"testing"
+)
+
+func TestScoreboardSidebarOrdersByScore(t *testing.T) {
+	var s scoreboards
+	s.create("broken", "Blocks Broken")
+	s.setScore("broken", "Steve", 3)
+	s.setScore("broken", "Alex", 7)
+
+	title, lines, ok := s.sidebar()
+	if !ok {
+		t.Fatal("expected a sidebar")
+	}
+	if title != "Blocks Broken" {
+		t.Fatalf("title = %q", title)
+	}
+	if want := []string{"Alex: 7", "Steve: 3"}; !slices.Equal(lines, want) {
+		t.Fatalf("lines = %v, want %v", lines, want)
+	}
+}
+
+func TestScoreboardSetScoreUnknownObjective(t *testing.T) {
+	var s scoreboards
+	if s.setScore("missing", "Steve", 1) {
+		t.Fatal("expected setScore on an unknown objective to fail")
+	}
+}
+
+func TestScoreboardRemoveShown(t *testing.T) {
+	var s scoreboards
+	s.create("a", "A")
+	s.create("b", "B")
+
+	if existed, shown := s.remove("a"); !existed || shown {
+		t.Fatalf("remove(a) = %v, %v; want true, false", existed, shown)
+	}
+	if existed, shown := s.remove("b"); !existed || !shown {
+		t.Fatalf("remove(b) = %v, %v; want true, true", existed, shown)
+	}
+	if _, _, ok := s.sidebar(); ok {
+		t.Fatal("expected no sidebar after removing every objective")
+	}
+}
+
+func TestBossBarSetCreateAndUpdate(t *testing.T) {
+	var b bossBars
+	if _, ok := b.set("raid", []string{"a"}, false); ok {
+		t.Fatal("updating a missing bar must fail")
+	}
+	if _, ok := b.set("raid", []string{"a", "b"}, true); !ok {
+		t.Fatal("expected create to succeed")
+	}
+	if _, ok := b.set("raid", []string{"a"}, true); ok {
+		t.Fatal("creating an existing bar must fail")
+	}
+
+	dropped, ok := b.set("raid", []string{"a"}, false)
+	if !ok {
+		t.Fatal("expected update to succeed")
+	}
+	if want := []string{"b"}; !slices.Equal(dropped, want) {
+		t.Fatalf("dropped = %v, want %v", dropped, want)
+	}
+
+	players, ok := b.remove("raid")
+	if !ok || !slices.Equal(players, []string{"a"}) {
+		t.Fatalf("remove = %v, %v", players, ok)
+	}
+}
@@ -2,19 +2,32 @@ package manager
 
 import (
 	"context"
+	"encoding/binary"
 	"encoding/json"
+	"errors"
+	"io/fs"
+	"math"
+	"os"
+	"path/filepath"
+	"slices"
+	"strconv"
+	"time"
 
 	extism "github.com/extism/go-sdk"
 	"go.uber.org/zap"
+
+	"github.com/EinBexiii/dragonfly-wasm/pkg/plugin"
 )
 
 type logRequest struct {
-	Level   string `json:"level"`
-	Message string `json:"message"`
+	Level   string            `json:"level"`
+	Message string            `json:"message"`
+	Fields  map[string]string `json:"fields,omitempty"`
 }
 
 type broadcastRequest struct {
-	Message string `json:"message"`
+	Message    string  `json:"message"`
+	Permission *string `json:"permission,omitempty"`
 }
 
 type sendMessageRequest struct {
@@ -34,6 +47,16 @@ type playerResponse struct {
 	Error     string   `json:"error,omitempty"`
 }
 
+type playerLocationResponse struct {
+	X         float64 `json:"x"`
+	Y         float64 `json:"y"`
+	Z         float64 `json:"z"`
+	Yaw       float64 `json:"yaw"`
+	Pitch     float64 `json:"pitch"`
+	Dimension string  `json:"dimension"`
+	OnGround  bool    `json:"on_ground"`
+}
+
 type position struct {
 	X float64 `json:"x"`
 	Y float64 `json:"y"`
@@ -46,14 +69,14 @@ type blockPos struct {
 	Z int `json:"z"`
 }
 
-type playersResponse struct {
-	Players []playerResponse `json:"players"`
-}
-
 type teleportRequest struct {
-	PlayerUUID string   `json:"player_uuid"`
-	Position   position `json:"position"`
-	WorldName  string   `json:"world_name"`
+	PlayerUUID string  `json:"player_uuid"`
+	X          float64 `json:"x"`
+	Y          float64 `json:"y"`
+	Z          float64 `json:"z"`
+	Yaw        float32 `json:"yaw"`
+	Pitch      float32 `json:"pitch"`
+	Dimension  string  `json:"dimension"`
 }
 
 type kickRequest struct {
@@ -61,6 +84,23 @@ type kickRequest struct {
 	Reason     string `json:"reason"`
 }
 
+type banRequest struct {
+	PlayerUUID string `json:"player_uuid"`
+	Reason     string `json:"reason"`
+	ExpiresAt  *int64 `json:"expires_at"`
+}
+
+type playerBanEvent struct {
+	Player   playerResponse `json:"player"`
+	Reason   string         `json:"reason"`
+	BannedBy string         `json:"banned_by"`
+}
+
+// jsonEvent is event data the manager dispatches itself.
+type jsonEvent []byte
+
+func (e jsonEvent) String() string { return string(e) }
+
 type setHealthRequest struct {
 	PlayerUUID string  `json:"player_uuid"`
 	Health     float32 `json:"health"`
@@ -71,9 +111,28 @@ type setGamemodeRequest struct {
 	Gamemode   int32  `json:"gamemode"`
 }
 
+type gameModeResponse struct {
+	GameMode string `json:"game_mode"`
+}
+
+var gameModeNames = []string{"survival", "creative", "adventure", "spectator"}
+
+var difficultyNames = []string{"peaceful", "easy", "normal", "hard"}
+
+type difficultyRequest struct {
+	Dimension  string `json:"dimension"`
+	Difficulty string `json:"difficulty"`
+}
+
+type difficultyResponse struct {
+	Difficulty string `json:"difficulty"`
+}
+
 type getBlockRequest struct {
-	WorldName string   `json:"world_name"`
-	Position  blockPos `json:"position"`
+	X         int    `json:"x"`
+	Y         int    `json:"y"`
+	Z         int    `json:"z"`
+	Dimension string `json:"dimension"`
 }
 
 type blockResponse struct {
@@ -83,13 +142,217 @@ type blockResponse struct {
 	Error      string            `json:"error,omitempty"`
 }
 
+type entityListRequest struct {
+	X         float64 `json:"x"`
+	Y         float64 `json:"y"`
+	Z         float64 `json:"z"`
+	Radius    float64 `json:"radius"`
+	Dimension string  `json:"dimension"`
+	Filter    *string `json:"filter"`
+}
+
+type entityResponse struct {
+	ID         uint64            `json:"id"`
+	EntityType string            `json:"entity_type"`
+	Position   dimensionBlockPos `json:"position"`
+	CustomName *string           `json:"custom_name"`
+}
+
+type dimensionBlockPos struct {
+	X         int    `json:"x"`
+	Y         int    `json:"y"`
+	Z         int    `json:"z"`
+	Dimension string `json:"dimension"`
+}
+
 type setBlockRequest struct {
-	WorldName  string            `json:"world_name"`
-	Position   blockPos          `json:"position"`
+	Position   dimensionBlockPos `json:"position"`
 	BlockType  string            `json:"block_type"`
 	Properties map[string]string `json:"properties"`
 }
 
+type worldTimeRequest struct {
+	Dimension string `json:"dimension"`
+	Time      *int64 `json:"time,omitempty"`
+}
+
+type worldTimeResponse struct {
+	Time uint64 `json:"time"`
+	Day  uint64 `json:"day"`
+}
+
+const ticksPerDay = 24000
+
+type worldBorder struct {
+	CenterX        float64 `json:"center_x"`
+	CenterZ        float64 `json:"center_z"`
+	Size           float64 `json:"size"`
+	DamagePerBlock float32 `json:"damage_per_block"`
+	SafeZone       float32 `json:"safe_zone"`
+}
+
+type worldBorderRequest struct {
+	Dimension string `json:"dimension"`
+	worldBorder
+}
+
+// defaultWorldBorder is the vanilla border, reported until a plugin sets one.
+var defaultWorldBorder = worldBorder{Size: 59999968, DamagePerBlock: 0.2, SafeZone: 5}
+
+type weatherResponse struct {
+	Raining    bool `json:"raining"`
+	Thundering bool `json:"thundering"`
+}
+
+type storeRequest struct {
+	Namespace string `json:"namespace"`
+	Key       string `json:"key"`
+	Value     []int  `json:"value"`
+}
+
+type runCommandRequest struct {
+	Command  string  `json:"command"`
+	AsPlayer *string `json:"as_player,omitempty"`
+}
+
+type runCommandResponse struct {
+	Success bool   `json:"success"`
+	Output  string `json:"output"`
+}
+
+type giveExpRequest struct {
+	PlayerUUID string `json:"player_uuid"`
+	Amount     int    `json:"amount"`
+}
+
+type inventorySlot struct {
+	Index int        `json:"index"`
+	Item  *ItemStack `json:"item"`
+}
+
+type inventoryResponse struct {
+	Slots []inventorySlot `json:"slots"`
+}
+
+type containerResponse struct {
+	Size  int             `json:"size"`
+	Slots []inventorySlot `json:"slots"`
+}
+
+type giveItemRequest struct {
+	PlayerUUID string `json:"player_uuid"`
+	Item       struct {
+		ItemType string `json:"item_type"`
+		Count    int    `json:"count"`
+	} `json:"item"`
+}
+
+type scheduleRequest struct {
+	CallbackID uint32 `json:"callback_id"`
+	DelayTicks uint64 `json:"delay_ticks"`
+	Repeat     bool   `json:"repeat"`
+}
+
+const tickDuration = 50 * time.Millisecond
+
+type pluginMessageRequest struct {
+	TargetPlugin string          `json:"target_plugin"`
+	Channel      string          `json:"channel"`
+	Payload      json.RawMessage `json:"payload"`
+}
+
+type pluginMessageEnvelope struct {
+	SourcePlugin string          `json:"source_plugin"`
+	Channel      string          `json:"channel"`
+	Payload      json.RawMessage `json:"payload"`
+}
+
+type scoreboardRequest struct {
+	Name        string `json:"name"`
+	DisplayName string `json:"display_name"`
+	Criteria    string `json:"criteria"`
+}
+
+type scoreRequest struct {
+	Objective string `json:"objective"`
+	Player    string `json:"player"`
+	Score     int    `json:"score"`
+}
+
+type bossBarRequest struct {
+	ID       string   `json:"id"`
+	Title    string   `json:"title"`
+	Progress float64  `json:"progress"`
+	Color    string   `json:"color"`
+	Style    string   `json:"style"`
+	Players  []string `json:"players"`
+}
+
+type playSoundRequest struct {
+	PlayerUUID string `json:"player_uuid"`
+	Sound      string `json:"sound"`
+}
+
+type permissionRequest struct {
+	PlayerUUID string `json:"player_uuid"`
+	Node       string `json:"node"`
+}
+
+type advancementRequest struct {
+	PlayerUUID    string `json:"player_uuid"`
+	AdvancementID string `json:"advancement_id"`
+	DisplayToast  bool   `json:"display_toast"`
+}
+
+type fogRequest struct {
+	PlayerUUID string   `json:"player_uuid"`
+	FogType    string   `json:"fog_type"`
+	Density    float64  `json:"density"`
+	Color      [3]uint8 `json:"color"`
+}
+
+type particleRequest struct {
+	Particle string  `json:"particle"`
+	X        float64 `json:"x"`
+	Y        float64 `json:"y"`
+	Z        float64 `json:"z"`
+}
+
+type titleRequest struct {
+	PlayerUUID string `json:"player_uuid"`
+	Title      string `json:"title"`
+	Subtitle   string `json:"subtitle"`
+	ActionBar  string `json:"action_bar"`
+	FadeIn     int    `json:"fade_in"`
+	Stay       int    `json:"stay"`
+	FadeOut    int    `json:"fade_out"`
+}
+
+type tablistRequest struct {
+	PlayerUUID string `json:"player_uuid"`
+	Header     string `json:"header"`
+	Footer     string `json:"footer"`
+}
+
+type pluginInfoResponse struct {
+	Name    string `json:"name"`
+	Version string `json:"version"`
+	Enabled bool   `json:"enabled"`
+}
+
+type serverInfoResponse struct {
+	MCVersion        string `json:"mc_version"`
+	ServerSoftware   string `json:"server_software"`
+	MaxPlayers       int    `json:"max_players"`
+	OnlineMode       bool   `json:"online_mode"`
+	PluginAPIVersion int    `json:"plugin_api_version"`
+}
+
+type loadRequest struct {
+	Namespace string `json:"namespace"`
+	Key       string `json:"key"`
+}
+
 func (m *Manager) createHostFunctions() []extism.HostFunction {
 	return []extism.HostFunction{
 		m.hostLog(),
@@ -97,12 +360,58 @@ func (m *Manager) createHostFunctions() []extism.HostFunction {
 		m.hostSendMessage(),
 		m.hostGetPlayer(),
 		m.hostGetOnlinePlayers(),
+		m.hostGetPlayerLocation(),
 		m.hostTeleportPlayer(),
 		m.hostKickPlayer(),
 		m.hostSetPlayerHealth(),
+		m.hostGetGamemode(),
 		m.hostSetPlayerGamemode(),
+		m.hostGiveItem(),
+		m.hostGiveExp(),
+		m.hostGetPlayerInventory(),
+		m.hostPlaySound(),
+		m.hostSendTitle(),
+		m.hostSendTablist(),
+		m.hostHasPermission(),
+		m.hostGrantPermission(),
+		m.hostGrantAdvancement(),
 		m.hostGetBlock(),
+		m.hostGetBlockNBT(),
+		m.hostGetBlockEntityInventory(),
+		m.hostGetEntityList(),
 		m.hostSetBlock(),
+		m.hostCurrentTimeMs(),
+		m.hostGetWorldTime(),
+		m.hostSetWorldTime(),
+		m.hostGetWeather(),
+		m.hostGetDifficulty(),
+		m.hostSetDifficulty(),
+		m.hostGetWorldBorder(),
+		m.hostSetWorldBorder(),
+		m.hostParticleEffect(),
+		m.hostSetFog(),
+		m.hostStoreData(),
+		m.hostLoadData(),
+		m.hostRunCommand(),
+		m.hostListPlugins(),
+		m.hostGetServerInfo(),
+		m.hostScoreboardCreate(),
+		m.hostScoreboardSetScore(),
+		m.hostScoreboardRemove(),
+		m.hostBossBarSet("host_bossbar_create", true),
+		m.hostBossBarSet("host_bossbar_update", false),
+		m.hostBossBarRemove(),
+	}
+}
+
+// createPluginHostFunctions returns the host functions whose behaviour
+// depends on which plugin calls them.
+func (m *Manager) createPluginHostFunctions(pluginID, pluginPath string) []extism.HostFunction {
+	return []extism.HostFunction{
+		m.hostGetConfig(pluginPath),
+		m.hostScheduleTask(pluginID),
+		m.hostPluginMessage(pluginID),
+		m.hostBanPlayer(pluginID),
 	}
 }
 
@@ -120,15 +429,20 @@ func (m *Manager) hostLog() extism.HostFunction {
 				return
 			}
 
+			fields := make([]zap.Field, 0, len(req.Fields))
+			for k, v := range req.Fields {
+				fields = append(fields, zap.String(k, v))
+			}
+
 			switch req.Level {
 			case "debug":
-				m.logger.Debug(req.Message)
+				m.logger.Debug(req.Message, fields...)
 			case "warn":
-				m.logger.Warn(req.Message)
+				m.logger.Warn(req.Message, fields...)
 			case "error":
-				m.logger.Error(req.Message)
+				m.logger.Error(req.Message, fields...)
 			default:
-				m.logger.Info(req.Message)
+				m.logger.Info(req.Message, fields...)
 			}
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
@@ -138,24 +452,24 @@ func (m *Manager) hostLog() extism.HostFunction {
 
 func (m *Manager) hostBroadcast() extism.HostFunction {
 	return extism.NewHostFunctionWithStack(
-		"host_broadcast",
+		"host_broadcast_message",
 		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
 			data, err := p.ReadBytes(stack[0])
 			if err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			var req broadcastRequest
 			if err := json.Unmarshal(data, &req); err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			if m.serverAPI != nil {
 				m.serverAPI.BroadcastMessage(req.Message)
 			}
-			stack[0] = 1
+			stack[0] = writeStatus(p, statusOK)
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
 		[]extism.ValueType{extism.ValueTypeI64},
@@ -169,31 +483,31 @@ func (m *Manager) hostSendMessage() extism.HostFunction {
 			data, err := p.ReadBytes(stack[0])
 			if err != nil {
 				m.logger.Debug("host_send_message: read bytes failed", zap.Error(err))
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			var req sendMessageRequest
 			if err := json.Unmarshal(data, &req); err != nil {
 				m.logger.Debug("host_send_message: unmarshal failed", zap.Error(err))
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			if m.serverAPI == nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
 			if !ok {
 				m.logger.Debug("host_send_message: player not found", zap.String("uuid", req.PlayerUUID))
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player.SendMessage(req.Message)
-			stack[0] = 1
+			stack[0] = writeStatus(p, statusOK)
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
 		[]extism.ValueType{extism.ValueTypeI64},
@@ -255,7 +569,7 @@ func (m *Manager) hostGetOnlinePlayers() extism.HostFunction {
 			}
 
 			players := m.serverAPI.GetAllPlayers()
-			resp := playersResponse{Players: make([]playerResponse, 0, len(players))}
+			resp := make([]playerResponse, 0, len(players))
 
 			for _, pl := range players {
 				x, y, z := pl.Position()
@@ -263,7 +577,7 @@ func (m *Manager) hostGetOnlinePlayers() extism.HostFunction {
 				if w := pl.World(); w != nil {
 					worldName = w.Name()
 				}
-				resp.Players = append(resp.Players, playerResponse{
+				resp = append(resp, playerResponse{
 					UUID:      pl.UUID(),
 					Name:      pl.Name(),
 					WorldName: worldName,
@@ -278,38 +592,91 @@ func (m *Manager) hostGetOnlinePlayers() extism.HostFunction {
 	)
 }
 
+// hostGetPlayerLocation answers with an empty result when the player is not
+// online, so plugins can tell that apart from a failed call.
+func (m *Manager) hostGetPlayerLocation() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_player_location",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req getPlayerRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
+			if !ok {
+				offset, err := p.WriteBytes(nil)
+				if err != nil {
+					stack[0] = 0
+					return
+				}
+				stack[0] = offset
+				return
+			}
+
+			x, y, z := player.Position()
+			yaw, pitch := player.Rotation()
+			var dimension string
+			if w := player.World(); w != nil {
+				dimension = w.Dimension()
+			}
+
+			stack[0] = writeJSON(p, playerLocationResponse{
+				X: x, Y: y, Z: z,
+				Yaw: yaw, Pitch: pitch,
+				Dimension: dimension,
+				OnGround:  player.OnGround(),
+			})
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
 func (m *Manager) hostTeleportPlayer() extism.HostFunction {
 	return extism.NewHostFunctionWithStack(
 		"host_teleport_player",
 		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
 			data, err := p.ReadBytes(stack[0])
 			if err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			var req teleportRequest
 			if err := json.Unmarshal(data, &req); err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			if m.serverAPI == nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
 			if !ok {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
-			if err := player.Teleport(req.Position.X, req.Position.Y, req.Position.Z, req.WorldName); err != nil {
-				stack[0] = 0
+			if err := player.Teleport(req.X, req.Y, req.Z, req.Dimension); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
-			stack[0] = 1
+			stack[0] = writeStatus(p, statusOK)
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
 		[]extism.ValueType{extism.ValueTypeI64},
@@ -322,29 +689,75 @@ func (m *Manager) hostKickPlayer() extism.HostFunction {
 		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
 			data, err := p.ReadBytes(stack[0])
 			if err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			var req kickRequest
 			if err := json.Unmarshal(data, &req); err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			if m.serverAPI == nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
 			if !ok {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player.Kick(req.Reason)
-			stack[0] = 1
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostBanPlayer records the ban, disconnects the player if they are online and
+// then dispatches player_ban. The event is dispatched asynchronously because
+// the banning plugin may itself subscribe to it.
+func (m *Manager) hostBanPlayer(pluginID string) extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_ban_player",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req banRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.PlayerUUID == "" {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var expiresAt time.Time
+			if req.ExpiresAt != nil {
+				expiresAt = time.UnixMilli(*req.ExpiresAt)
+			}
+			m.bans.add(req.PlayerUUID, req.Reason, expiresAt)
+
+			if m.serverAPI != nil {
+				if player, ok := m.serverAPI.GetPlayer(req.PlayerUUID); ok {
+					x, y, z := player.Position()
+					ev, err := json.Marshal(playerBanEvent{
+						Player:   playerResponse{UUID: player.UUID(), Name: player.Name(), Position: position{X: x, Y: y, Z: z}},
+						Reason:   req.Reason,
+						BannedBy: pluginID,
+					})
+					player.Kick(req.Reason)
+					if err == nil {
+						go m.dispatcher.Dispatch(m.ctx, plugin.EventPlayerBan, jsonEvent(ev))
+					}
+				}
+			}
+			stack[0] = writeStatus(p, statusOK)
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
 		[]extism.ValueType{extism.ValueTypeI64},
@@ -357,29 +770,68 @@ func (m *Manager) hostSetPlayerHealth() extism.HostFunction {
 		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
 			data, err := p.ReadBytes(stack[0])
 			if err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			var req setHealthRequest
 			if err := json.Unmarshal(data, &req); err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			if m.serverAPI == nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
 			if !ok {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player.SetHealth(float64(req.Health))
-			stack[0] = 1
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostGetGamemode() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_gamemode",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req getPlayerRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
+			if !ok {
+				stack[0] = writeError(p, "player not found")
+				return
+			}
+
+			mode := player.GameMode()
+			if mode < 0 || mode >= len(gameModeNames) {
+				stack[0] = writeError(p, "unknown game mode")
+				return
+			}
+			stack[0] = writeJSON(p, gameModeResponse{GameMode: gameModeNames[mode]})
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
 		[]extism.ValueType{extism.ValueTypeI64},
@@ -392,117 +844,1379 @@ func (m *Manager) hostSetPlayerGamemode() extism.HostFunction {
 		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
 			data, err := p.ReadBytes(stack[0])
 			if err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			var req setGamemodeRequest
 			if err := json.Unmarshal(data, &req); err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			if m.serverAPI == nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
 			if !ok {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			player.SetGameMode(int(req.Gamemode))
-			stack[0] = 1
+			stack[0] = writeStatus(p, statusOK)
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
 		[]extism.ValueType{extism.ValueTypeI64},
 	)
 }
 
-func (m *Manager) hostGetBlock() extism.HostFunction {
+// hostGiveItem answers with the number of items added to the inventory.
+func (m *Manager) hostGiveItem() extism.HostFunction {
 	return extism.NewHostFunctionWithStack(
-		"host_get_block",
+		"host_give_item",
 		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
 			data, err := p.ReadBytes(stack[0])
 			if err != nil {
-				stack[0] = writeError(p, "failed to read input")
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
-			var req getBlockRequest
-			if err := json.Unmarshal(data, &req); err != nil {
-				stack[0] = writeError(p, "failed to parse request")
+			var req giveItemRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.Item.Count <= 0 {
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			if m.serverAPI == nil {
-				stack[0] = writeError(p, "server API not available")
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
-			world, ok := m.serverAPI.GetWorld(req.WorldName)
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
 			if !ok {
-				world = m.serverAPI.GetDefaultWorld()
+				stack[0] = writeStatus(p, statusFailed)
+				return
 			}
 
-			blockType, properties := world.GetBlock(req.Position.X, req.Position.Y, req.Position.Z)
-
-			stack[0] = writeJSON(p, blockResponse{
-				BlockType:  blockType,
-				Position:   req.Position,
-				Properties: properties,
-			})
+			added, err := player.GiveItem(req.Item.ItemType, req.Item.Count)
+			if err != nil {
+				m.logger.Debug("host_give_item: failed", zap.String("item", req.Item.ItemType), zap.Error(err))
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			stack[0] = writeStatus(p, int64(added))
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
 		[]extism.ValueType{extism.ValueTypeI64},
 	)
 }
 
-func (m *Manager) hostSetBlock() extism.HostFunction {
+// hostGiveExp removes experience when the amount is negative.
+func (m *Manager) hostGiveExp() extism.HostFunction {
 	return extism.NewHostFunctionWithStack(
-		"host_set_block",
+		"host_give_exp",
 		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
 			data, err := p.ReadBytes(stack[0])
 			if err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
-			var req setBlockRequest
+			var req giveExpRequest
 			if err := json.Unmarshal(data, &req); err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
 			if m.serverAPI == nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
 
-			world, ok := m.serverAPI.GetWorld(req.WorldName)
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
 			if !ok {
-				world = m.serverAPI.GetDefaultWorld()
-			}
-
-			if err := world.SetBlock(req.Position.X, req.Position.Y, req.Position.Z, req.BlockType, req.Properties); err != nil {
-				stack[0] = 0
+				stack[0] = writeStatus(p, statusFailed)
 				return
 			}
-			stack[0] = 1
+
+			player.AddExperience(req.Amount)
+			stack[0] = writeStatus(p, statusOK)
 		},
 		[]extism.ValueType{extism.ValueTypeI64},
 		[]extism.ValueType{extism.ValueTypeI64},
 	)
 }
 
-func writeJSON(p *extism.CurrentPlugin, v any) uint64 {
-	data, err := json.Marshal(v)
-	if err != nil {
-		return 0
-	}
-	offset, err := p.WriteBytes(data)
+func (m *Manager) hostGetPlayerInventory() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_player_inventory",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req getPlayerRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
+			if !ok {
+				stack[0] = writeError(p, "player not found")
+				return
+			}
+
+			stack[0] = writeJSON(p, inventoryResponse{Slots: inventorySlots(player.Inventory())})
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func inventorySlots(items []*ItemStack) []inventorySlot {
+	slots := make([]inventorySlot, len(items))
+	for i, it := range items {
+		slots[i] = inventorySlot{Index: i, Item: it}
+	}
+	return slots
+}
+
+// hostPlaySound plays the sound to the player where they stand; the requested
+// position, volume and pitch are not applied.
+func (m *Manager) hostPlaySound() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_play_sound",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req playSoundRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
+			if !ok {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if err := player.PlaySound(req.Sound); err != nil {
+				m.logger.Debug("host_play_sound: failed", zap.String("sound", req.Sound), zap.Error(err))
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostSendTitle() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_send_title",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req titleRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
+			if !ok {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			player.SendTitle(Title{
+				Title:     req.Title,
+				Subtitle:  req.Subtitle,
+				ActionBar: req.ActionBar,
+				FadeIn:    time.Duration(req.FadeIn) * tickDuration,
+				Stay:      time.Duration(req.Stay) * tickDuration,
+				FadeOut:   time.Duration(req.FadeOut) * tickDuration,
+			})
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostSendTablist() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_send_tablist",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req tablistRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
+			if !ok {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if err := player.SetTabList(req.Header, req.Footer); err != nil {
+				m.logger.Debug("tab list not shown", zap.String("player", req.PlayerUUID), zap.Error(err))
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostHasPermission answers 1 when the player holds the node and 0 otherwise.
+func (m *Manager) hostHasPermission() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_has_permission",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, -1)
+				return
+			}
+
+			var req permissionRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, -1)
+				return
+			}
+
+			if m.permissions.has(req.PlayerUUID, req.Node) {
+				stack[0] = writeStatus(p, statusOK)
+				return
+			}
+			stack[0] = writeStatus(p, statusFailed)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostGrantPermission() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_grant_permission",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req permissionRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.PlayerUUID == "" || req.Node == "" {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			m.permissions.grant(req.PlayerUUID, req.Node)
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostGrantAdvancement records the advancement and, the first time it is
+// granted, shows it as a toast when asked to.
+func (m *Manager) hostGrantAdvancement() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_grant_advancement",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req advancementRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.AdvancementID == "" {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
+			if !ok {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.advancements.grant(req.PlayerUUID, req.AdvancementID) && req.DisplayToast {
+				player.SendToast("Advancement Made!", req.AdvancementID)
+			}
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostSetFog() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_set_fog",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req fogRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.FogType == "" || req.Density < 0 || req.Density > 1 {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			player, ok := m.serverAPI.GetPlayer(req.PlayerUUID)
+			if !ok {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if err := player.SetFog(req.FogType, req.Density, req.Color); err != nil {
+				m.logger.Debug("fog not set", zap.String("player", req.PlayerUUID), zap.Error(err))
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostGetBlock() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_block",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req getBlockRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			world, ok := m.serverAPI.GetWorld(req.Dimension)
+			if !ok {
+				world = m.serverAPI.GetDefaultWorld()
+			}
+
+			blockType, properties := world.GetBlock(req.X, req.Y, req.Z)
+
+			stack[0] = writeJSON(p, blockResponse{
+				BlockType:  blockType,
+				Position:   blockPos{X: req.X, Y: req.Y, Z: req.Z},
+				Properties: properties,
+			})
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostGetBlockNBT answers with an empty result for blocks without
+// tile-entity data.
+func (m *Manager) hostGetBlockNBT() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_block_nbt",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req getBlockRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			nbt := m.worldFor(req.Dimension).BlockNBT(req.X, req.Y, req.Z)
+			if nbt == nil {
+				offset, err := p.WriteBytes(nil)
+				if err != nil {
+					stack[0] = 0
+					return
+				}
+				stack[0] = offset
+				return
+			}
+			stack[0] = writeJSON(p, nbt)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostGetBlockEntityInventory answers with null for blocks that are not
+// containers.
+func (m *Manager) hostGetBlockEntityInventory() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_block_entity_inventory",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req getBlockRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			items, ok := m.worldFor(req.Dimension).ContainerInventory(req.X, req.Y, req.Z)
+			if !ok {
+				stack[0] = writeJSON(p, nil)
+				return
+			}
+			stack[0] = writeJSON(p, containerResponse{Size: len(items), Slots: inventorySlots(items)})
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostGetEntityList() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_entity_list",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req entityListRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.Radius < 0 {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			world := m.worldFor(req.Dimension)
+			resp := make([]entityResponse, 0)
+			for _, e := range world.Entities(req.X, req.Y, req.Z, req.Radius) {
+				if req.Filter != nil && e.Type != *req.Filter {
+					continue
+				}
+				entry := entityResponse{
+					ID:         e.ID,
+					EntityType: e.Type,
+					Position: dimensionBlockPos{
+						X:         int(math.Floor(e.X)),
+						Y:         int(math.Floor(e.Y)),
+						Z:         int(math.Floor(e.Z)),
+						Dimension: world.Dimension(),
+					},
+				}
+				if e.CustomName != "" {
+					entry.CustomName = &e.CustomName
+				}
+				resp = append(resp, entry)
+			}
+			stack[0] = writeJSON(p, resp)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostSetBlock() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_set_block",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req setBlockRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			world := m.worldFor(req.Position.Dimension)
+			if err := world.SetBlock(req.Position.X, req.Position.Y, req.Position.Z, req.BlockType, req.Properties); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostGetWorldTime() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_world_time",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req worldTimeRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			world, ok := m.serverAPI.GetWorld(req.Dimension)
+			if !ok {
+				world = m.serverAPI.GetDefaultWorld()
+			}
+
+			t := uint64(max(world.Time(), 0))
+			stack[0] = writeJSON(p, worldTimeResponse{Time: t % ticksPerDay, Day: t / ticksPerDay})
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostSetWorldTime() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_set_world_time",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req worldTimeRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.Time == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			world, ok := m.serverAPI.GetWorld(req.Dimension)
+			if !ok {
+				world = m.serverAPI.GetDefaultWorld()
+			}
+
+			world.SetTime(int(*req.Time))
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostGetWeather leaves duration_remaining_ticks out, as the server does not
+// expose how long the current weather lasts.
+func (m *Manager) hostGetWeather() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_weather",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req worldTimeRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			raining, thundering := m.worldFor(req.Dimension).Weather()
+			stack[0] = writeJSON(p, weatherResponse{Raining: raining, Thundering: thundering})
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostGetDifficulty() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_difficulty",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req difficultyRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			id := m.worldFor(req.Dimension).Difficulty()
+			if id < 0 || id >= len(difficultyNames) {
+				stack[0] = writeError(p, "unknown difficulty")
+				return
+			}
+			stack[0] = writeJSON(p, difficultyResponse{Difficulty: difficultyNames[id]})
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostSetDifficulty() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_set_difficulty",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req difficultyRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			id := slices.Index(difficultyNames, req.Difficulty)
+			if id < 0 || m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			m.worldFor(req.Dimension).SetDifficulty(id)
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// Dragonfly has no world border, so the manager only stores the border plugins
+// set, per dimension, for other plugins to read; the server does not enforce it.
+func (m *Manager) hostGetWorldBorder() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_world_border",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req worldBorderRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			border := defaultWorldBorder
+			if v, ok := m.worldBorders.Load(req.Dimension); ok {
+				border = v.(worldBorder)
+			}
+			stack[0] = writeJSON(p, border)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostSetWorldBorder() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_set_world_border",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req worldBorderRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.Size <= 0 {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			m.worldBorders.Store(req.Dimension, req.worldBorder)
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostParticleEffect shows one burst of the particle in the default world to
+// everyone nearby; offsets, speed, count and the players filter are not
+// applied.
+func (m *Manager) hostParticleEffect() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_particle_effect",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req particleRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if err := m.serverAPI.GetDefaultWorld().AddParticle(req.Particle, req.X, req.Y, req.Z); err != nil {
+				m.logger.Debug("host_particle_effect: failed", zap.String("particle", req.Particle), zap.Error(err))
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostStoreData() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_store_data",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req storeRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.Key == "" {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			key := req.Namespace + "/" + req.Key
+			if len(req.Value) == 0 {
+				m.storage.Delete(key)
+				stack[0] = writeStatus(p, statusOK)
+				return
+			}
+
+			value := make([]byte, len(req.Value))
+			for i, b := range req.Value {
+				value[i] = byte(b)
+			}
+			m.storage.Store(key, value)
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostLoadData() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_load_data",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = 0
+				return
+			}
+
+			var req loadRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = 0
+				return
+			}
+
+			var value []byte
+			if v, ok := m.storage.Load(req.Namespace + "/" + req.Key); ok {
+				value = v.([]byte)
+			}
+
+			offset, err := p.WriteBytes(value)
+			if err != nil {
+				stack[0] = 0
+				return
+			}
+			stack[0] = offset
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostRunCommand() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_run_command",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeError(p, "failed to read input")
+				return
+			}
+
+			var req runCommandRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeError(p, "failed to parse request")
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			if req.AsPlayer != nil {
+				player, ok := m.serverAPI.GetPlayer(*req.AsPlayer)
+				if !ok {
+					stack[0] = writeJSON(p, runCommandResponse{Output: "player not found"})
+					return
+				}
+				player.ExecuteCommand(req.Command)
+				stack[0] = writeJSON(p, runCommandResponse{Success: true})
+				return
+			}
+
+			output, err := m.serverAPI.ExecuteCommand(req.Command)
+			if err != nil {
+				stack[0] = writeJSON(p, runCommandResponse{Output: err.Error()})
+				return
+			}
+			stack[0] = writeJSON(p, runCommandResponse{Success: true, Output: output})
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostListPlugins lists the loaded plugins in load order. While a plugin is
+// being loaded or enabled the manager lock is held, so the list is not
+// available from plugin_init or on_enable.
+func (m *Manager) hostListPlugins() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_list_plugins",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			if !m.mu.TryRLock() {
+				stack[0] = writeError(p, "plugin list unavailable while plugins are loading")
+				return
+			}
+			resp := make([]pluginInfoResponse, 0, len(m.loadOrder))
+			for _, id := range m.loadOrder {
+				info := m.plugins[id].Info
+				resp = append(resp, pluginInfoResponse{
+					Name:    info.Manifest.Name,
+					Version: info.Manifest.Version.String(),
+					Enabled: info.State == plugin.StateEnabled,
+				})
+			}
+			m.mu.RUnlock()
+
+			stack[0] = writeJSON(p, resp)
+		},
+		[]extism.ValueType{},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostGetServerInfo() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_server_info",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			if m.serverAPI == nil {
+				stack[0] = writeError(p, "server API not available")
+				return
+			}
+
+			info := m.serverAPI.Info()
+			stack[0] = writeJSON(p, serverInfoResponse{
+				MCVersion:        info.MinecraftVersion,
+				ServerSoftware:   info.Software,
+				MaxPlayers:       info.MaxPlayers,
+				OnlineMode:       info.OnlineMode,
+				PluginAPIVersion: plugin.CurrentAPIVersion,
+			})
+		},
+		[]extism.ValueType{},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostScoreboardCreate() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_scoreboard_create",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req scoreboardRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.Name == "" {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			m.scoreboards.create(req.Name, req.DisplayName)
+			m.showSidebar()
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostScoreboardSetScore() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_scoreboard_set_score",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req scoreRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if !m.scoreboards.setScore(req.Objective, req.Player, req.Score) {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			m.showSidebar()
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostScoreboardRemove() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_scoreboard_remove",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req scoreboardRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			existed, shown := m.scoreboards.remove(req.Name)
+			if !existed {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			if shown && m.serverAPI != nil {
+				for _, player := range m.serverAPI.GetAllPlayers() {
+					player.RemoveScoreboard()
+				}
+			}
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// hostBossBarSet backs both host_bossbar_create and host_bossbar_update,
+// which share a payload and differ only in whether the bar may exist yet.
+func (m *Manager) hostBossBarSet(name string, create bool) extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		name,
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req bossBarRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.ID == "" {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			if m.serverAPI == nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			dropped, ok := m.bossBars.set(req.ID, req.Players, create)
+			if !ok {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			m.forPlayers(dropped, PlayerAPI.RemoveBossBar)
+			m.forPlayers(req.Players, func(player PlayerAPI) {
+				player.SendBossBar(req.Title, req.Progress, req.Color)
+			})
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostBossBarRemove() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_bossbar_remove",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req bossBarRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			players, ok := m.bossBars.remove(req.ID)
+			if !ok {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			m.forPlayers(players, PlayerAPI.RemoveBossBar)
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// forPlayers calls fn for each of the players that is online.
+func (m *Manager) forPlayers(uuids []string, fn func(PlayerAPI)) {
+	if m.serverAPI == nil {
+		return
+	}
+	for _, uuid := range uuids {
+		if player, ok := m.serverAPI.GetPlayer(uuid); ok {
+			fn(player)
+		}
+	}
+}
+
+// showSidebar sends the objective on screen to every online player.
+func (m *Manager) showSidebar() {
+	if m.serverAPI == nil {
+		return
+	}
+	title, lines, ok := m.scoreboards.sidebar()
+	if !ok {
+		return
+	}
+	for _, player := range m.serverAPI.GetAllPlayers() {
+		player.SendScoreboard(title, lines)
+	}
+}
+
+func (m *Manager) hostScheduleTask(pluginID string) extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_schedule_task",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req scheduleRequest
+			if err := json.Unmarshal(data, &req); err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			go m.runScheduledTask(pluginID, req)
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// runScheduledTask calls the plugin's on_scheduled_task export once the delay
+// has passed, and again after every further delay for repeating tasks, until
+// the plugin is unloaded or the manager closes.
+func (m *Manager) runScheduledTask(pluginID string, req scheduleRequest) {
+	ticker := time.NewTicker(time.Duration(max(req.DelayTicks, 1)) * tickDuration)
+	defer ticker.Stop()
+
+	taskID := []byte(strconv.FormatUint(uint64(req.CallbackID), 10))
+	for {
+		select {
+		case <-m.ctx.Done():
+			return
+		case <-ticker.C:
+		}
+
+		loaded, ok := m.GetPlugin(pluginID)
+		if !ok {
+			return
+		}
+
+		loaded.mu.Lock()
+		if loaded.Info.State == plugin.StateEnabled && loaded.Instance.FunctionExists("on_scheduled_task") {
+			if _, _, err := loaded.Instance.Call("on_scheduled_task", taskID); err != nil {
+				loaded.Info.Metrics.RecordError(err)
+				m.logger.Warn("scheduled task failed", zap.String("plugin", pluginID), zap.Uint32("task", req.CallbackID), zap.Error(err))
+			}
+		}
+		loaded.mu.Unlock()
+
+		if !req.Repeat {
+			return
+		}
+	}
+}
+
+// hostPluginMessage delivers the message asynchronously: the sender may hold
+// locks the receiver needs, and may even be the receiver.
+func (m *Manager) hostPluginMessage(pluginID string) extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_plugin_message",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := p.ReadBytes(stack[0])
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			var req pluginMessageRequest
+			if err := json.Unmarshal(data, &req); err != nil || req.TargetPlugin == "" {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+			if len(req.Payload) == 0 || string(req.Payload) == "null" {
+				req.Payload = json.RawMessage("[]")
+			}
+
+			envelope, err := json.Marshal(pluginMessageEnvelope{SourcePlugin: pluginID, Channel: req.Channel, Payload: req.Payload})
+			if err != nil {
+				stack[0] = writeStatus(p, statusFailed)
+				return
+			}
+
+			go m.deliverPluginMessage(req.TargetPlugin, envelope)
+			stack[0] = writeStatus(p, statusOK)
+		},
+		[]extism.ValueType{extism.ValueTypeI64},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) deliverPluginMessage(target string, envelope []byte) {
+	loaded, ok := m.GetPlugin(target)
+	if !ok {
+		m.logger.Debug("plugin message: target not loaded", zap.String("target", target))
+		return
+	}
+
+	loaded.mu.Lock()
+	defer loaded.mu.Unlock()
+
+	if loaded.Info.State != plugin.StateEnabled || !loaded.Instance.FunctionExists("on_plugin_message") {
+		return
+	}
+	if _, _, err := loaded.Instance.Call("on_plugin_message", envelope); err != nil {
+		loaded.Info.Metrics.RecordError(err)
+		m.logger.Warn("plugin message handler failed", zap.String("target", target), zap.Error(err))
+	}
+}
+
+func (m *Manager) hostGetConfig(pluginPath string) extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_get_config",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			data, err := os.ReadFile(filepath.Join(pluginPath, "config.toml"))
+			if err != nil && !errors.Is(err, fs.ErrNotExist) {
+				m.logger.Warn("host_get_config: read failed", zap.String("path", pluginPath), zap.Error(err))
+			}
+
+			offset, err := p.WriteBytes(data)
+			if err != nil {
+				stack[0] = 0
+				return
+			}
+			stack[0] = offset
+		},
+		[]extism.ValueType{},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+func (m *Manager) hostCurrentTimeMs() extism.HostFunction {
+	return extism.NewHostFunctionWithStack(
+		"host_current_time_ms",
+		func(_ context.Context, p *extism.CurrentPlugin, stack []uint64) {
+			buf := binary.LittleEndian.AppendUint64(nil, uint64(time.Now().UnixMilli()))
+			offset, err := p.WriteBytes(buf)
+			if err != nil {
+				stack[0] = 0
+				return
+			}
+			stack[0] = offset
+		},
+		[]extism.ValueType{},
+		[]extism.ValueType{extism.ValueTypeI64},
+	)
+}
+
+// worldFor returns the world of the named dimension, or the default world
+// when there is none.
+func (m *Manager) worldFor(dimension string) WorldAPI {
+	if world, ok := m.serverAPI.GetWorld(dimension); ok {
+		return world
+	}
+	return m.serverAPI.GetDefaultWorld()
+}
+
+func writeJSON(p *extism.CurrentPlugin, v any) uint64 {
+	data, err := json.Marshal(v)
+	if err != nil {
+		return 0
+	}
+	offset, err := p.WriteBytes(data)
+	if err != nil {
+		return 0
+	}
+	return offset
+}
+
+const (
+	statusFailed int64 = 0
+	statusOK     int64 = 1
+)
+
+// writeStatus returns an s64 status to the plugin. Like every non-void host
+// result it is read from plugin memory, so it is written there as 8
+// little-endian bytes rather than returned on the stack.
+func writeStatus(p *extism.CurrentPlugin, code int64) uint64 {
+	offset, err := p.WriteBytes(binary.LittleEndian.AppendUint64(nil, uint64(code)))
 	if err != nil {
 		return 0
 	}
@@ -2,6 +2,7 @@ package manager
 
 import (
 	"context"
+	"encoding/binary"
 	"errors"
 	"fmt"
 	"os"
@@ -21,16 +22,24 @@ import (
 )
 
 type Manager struct {
-	config     *config.Config
-	logger     *zap.Logger
-	dispatcher *events.Dispatcher
-	plugins    map[string]*LoadedPlugin
-	loadOrder  []string
-	mu         sync.RWMutex
-	hostFuncs  []extism.HostFunction
-	serverAPI  ServerAPI
-	ctx        context.Context
-	cancel     context.CancelFunc
+	config       *config.Config
+	logger       *zap.Logger
+	dispatcher   *events.Dispatcher
+	plugins      map[string]*LoadedPlugin
+	loadOrder    []string
+	mu           sync.RWMutex
+	hostFuncs    []extism.HostFunction
+	serverAPI    ServerAPI
+	storage      sync.Map
+	scoreboards  scoreboards
+	bossBars     bossBars
+	permissions  permissions
+	advancements advancements
+	bans         bans
+	// worldBorders maps a dimension to the worldBorder a plugin set for it.
+	worldBorders sync.Map
+	ctx          context.Context
+	cancel       context.CancelFunc
 }
 
 type LoadedPlugin struct {
@@ -42,9 +51,14 @@ type LoadedPlugin struct {
 type ServerAPI interface {
 	GetPlayer(uuid string) (PlayerAPI, bool)
 	GetAllPlayers() []PlayerAPI
+	// GetWorld looks a world up by dimension ("overworld", "nether" or
+	// "end") or by world name.
 	GetWorld(name string) (WorldAPI, bool)
 	GetDefaultWorld() WorldAPI
 	BroadcastMessage(msg string)
+	// ExecuteCommand runs commandLine as the console and returns its output.
+	ExecuteCommand(commandLine string) (string, error)
+	Info() ServerInfo
 }
 
 type PlayerAPI interface {
@@ -53,16 +67,86 @@ type PlayerAPI interface {
 	SendMessage(msg string)
 	Teleport(x, y, z float64, worldName string) error
 	Kick(reason string)
+	ExecuteCommand(commandLine string)
+	// GiveItem adds count items of itemType to the inventory and returns how
+	// many fit.
+	GiveItem(itemType string, count int) (int, error)
+	// AddExperience adds amount experience points, or removes them when
+	// amount is negative.
+	AddExperience(amount int)
+	// Inventory returns every slot of the player's inventory; empty slots are nil.
+	Inventory() []*ItemStack
+	SendScoreboard(title string, lines []string)
+	RemoveScoreboard()
+	// SendBossBar shows a boss bar filled to progress, between 0 and 1.
+	SendBossBar(title string, progress float64, colour string)
+	RemoveBossBar()
+	PlaySound(name string) error
+	SendTitle(t Title)
+	// SetTabList sets the player list header and footer, failing on servers
+	// whose clients cannot show them.
+	SetTabList(header, footer string) error
+	SendToast(title, message string)
+	// SetFog changes the fog the player sees, failing on servers that cannot
+	// send it.
+	SetFog(fogType string, density float64, colour [3]uint8) error
 	SetHealth(health float64)
 	SetGameMode(mode int)
+	GameMode() int
 	Position() (x, y, z float64)
+	Rotation() (yaw, pitch float64)
+	OnGround() bool
 	World() WorldAPI
 }
 
+type ItemStack struct {
+	ItemType string `json:"item_type"`
+	Count    int    `json:"count"`
+}
+
+type ServerInfo struct {
+	MinecraftVersion string
+	Software         string
+	MaxPlayers       int
+	OnlineMode       bool
+}
+
+type Entity struct {
+	ID         uint64
+	Type       string
+	X, Y, Z    float64
+	CustomName string
+}
+
+// Title is a title, subtitle and action bar shown together; empty lines are
+// left unchanged.
+type Title struct {
+	Title, Subtitle, ActionBar string
+	FadeIn, Stay, FadeOut      time.Duration
+}
+
 type WorldAPI interface {
 	Name() string
+	// Dimension is "overworld", "nether" or "end".
+	Dimension() string
 	GetBlock(x, y, z int) (blockType string, properties map[string]string)
 	SetBlock(x, y, z int, blockType string, properties map[string]string) error
+	Time() int
+	SetTime(time int)
+	// Weather reports the weather at the world spawn.
+	Weather() (raining, thundering bool)
+	// Difficulty and SetDifficulty use the Bedrock difficulty IDs, 0
+	// (peaceful) to 3 (hard).
+	Difficulty() int
+	SetDifficulty(id int)
+	AddParticle(name string, x, y, z float64) error
+	// BlockNBT returns the block's tile-entity data, or nil if it has none.
+	BlockNBT(x, y, z int) map[string]any
+	// ContainerInventory returns the slots of the container block at the
+	// position, or false if the block is not a container.
+	ContainerInventory(x, y, z int) ([]*ItemStack, bool)
+	// Entities returns the entities within radius blocks of the point.
+	Entities(x, y, z, radius float64) []Entity
 }
 
 func New(cfg *config.Config, logger *zap.Logger, serverAPI ServerAPI) *Manager {
@@ -240,7 +324,8 @@ func (m *Manager) loadPlugin(ctx context.Context, manifest *plugin.Manifest, plu
 		},
 	}
 
-	instance, err := extism.NewPlugin(ctx, extismManifest, extism.PluginConfig{EnableWasi: true}, m.hostFuncs)
+	hostFuncs := append(slices.Clone(m.hostFuncs), m.createPluginHostFunctions(manifest.ID, pluginPath)...)
+	instance, err := extism.NewPlugin(ctx, extismManifest, extism.PluginConfig{EnableWasi: true}, hostFuncs)
 	if err != nil {
 		info.State = plugin.StateError
 		return fmt.Errorf("create WASM instance: %w", err)
@@ -254,6 +339,12 @@ func (m *Manager) loadPlugin(ctx context.Context, manifest *plugin.Manifest, plu
 		instance.Close()
 		return errors.New("missing required export: handle_event")
 	}
+	if instance.FunctionExists("plugin_encoding") {
+		if _, out, err := instance.Call("plugin_encoding", nil); err != nil || len(out) != 1 || out[0] != payloadEncodingJSON {
+			instance.Close()
+			return errors.New("plugin requires an unsupported payload encoding")
+		}
+	}
 
 	loaded := &LoadedPlugin{Info: info, Instance: instance}
 
@@ -303,8 +394,7 @@ func (m *Manager) createEventHandler(loaded *LoadedPlugin, eventType plugin.Even
 		defer cancel()
 
 		start := time.Now()
-		envelope := append([]byte(eventType), 0)
-		envelope = append(envelope, data...)
+		envelope := encodeEnvelope(eventType, data)
 
 		resultCh := make(chan struct {
 			output []byte
@@ -331,19 +421,26 @@ func (m *Manager) createEventHandler(loaded *LoadedPlugin, eventType plugin.Even
 				return nil, result.err
 			}
 
-			eventResult := parseEventResult(result.output)
+			eventResult := events.ParseEventResult(result.output)
+			if eventResult.Error != "" {
+				err := errors.New(eventResult.Error)
+				loaded.Info.Metrics.RecordError(err)
+				return nil, err
+			}
 			loaded.Info.Metrics.RecordEvent(eventType, eventResult.Cancelled)
 			return eventResult, nil
 		}
 	}
 }
 
-func parseEventResult(data []byte) *events.EventResult {
-	result := &events.EventResult{Modifications: make(map[string]string)}
-	if len(data) > 0 {
-		result.Cancelled = data[0] == 1
-	}
-	return result
+const payloadEncodingJSON byte = 0
+
+func encodeEnvelope(eventType plugin.EventType, data []byte) []byte {
+	envelope := make([]byte, 4, 5+len(eventType)+len(data))
+	binary.LittleEndian.PutUint32(envelope, uint32(len(eventType)))
+	envelope = append(envelope, eventType...)
+	envelope = append(envelope, payloadEncodingJSON)
+	return append(envelope, data...)
 }
 
 func (m *Manager) EnablePlugin(id string) error {
@@ -0,0 +1,47 @@
+package manager
+
+import (
+	"strings"
+	"sync"
+)
+
+// permissions holds the nodes granted to players through host_grant_permission.
+// A granted "a.b.*" node also grants every node below "a.b".
+type permissions struct {
+	mu     sync.RWMutex
+	grants map[string]map[string]struct{}
+}
+
+func (p *permissions) grant(playerUUID, node string) {
+	p.mu.Lock()
+	defer p.mu.Unlock()
+
+	if p.grants == nil {
+		p.grants = make(map[string]map[string]struct{})
+	}
+	if p.grants[playerUUID] == nil {
+		p.grants[playerUUID] = make(map[string]struct{})
+	}
+	p.grants[playerUUID][node] = struct{}{}
+}
+
+func (p *permissions) has(playerUUID, node string) bool {
+	p.mu.RLock()
+	defer p.mu.RUnlock()
+
+	nodes := p.grants[playerUUID]
+	if _, ok := nodes[node]; ok {
+		return true
+	}
+	for prefix := node; ; {
+		i := strings.LastIndexByte(prefix, '.')
+		if i < 0 {
+			_, ok := nodes["*"]
+			return ok
+		}
+		prefix = prefix[:i]
+		if _, ok := nodes[prefix+".*"]; ok {
+			return true
+		}
+	}
+}
@@ -0,0 +1,35 @@
+package manager
+
+import "testing"
+
+func TestPermissionsGrant(t *testing.T) {
+	var p permissions
+	if p.has("steve", "blocklogger.bypass") {
+		t.Fatal("expected no permission before granting")
+	}
+
+	p.grant("steve", "blocklogger.bypass")
+	if !p.has("steve", "blocklogger.bypass") {
+		t.Fatal("expected the granted node")
+	}
+	if p.has("alex", "blocklogger.bypass") {
+		t.Fatal("a grant must only apply to its player")
+	}
+}
+
+func TestPermissionsWildcard(t *testing.T) {
+	var p permissions
+	p.grant("steve", "blocklogger.*")
+
+	if !p.has("steve", "blocklogger.bypass") || !p.has("steve", "blocklogger.admin.reload") {
+		t.Fatal("expected the wildcard to grant nodes below it")
+	}
+	if p.has("steve", "other.node") {
+		t.Fatal("the wildcard must not grant unrelated nodes")
+	}
+
+	p.grant("alex", "*")
+	if !p.has("alex", "anything.at.all") {
+		t.Fatal("expected * to grant every node")
+	}
+}
@@ -2,6 +2,7 @@ package runtime
 
 import (
 	"context"
+	"errors"
 	"fmt"
 	"sync"
 	"time"
@@ -104,7 +105,13 @@ func (i *Instance) HandleEvent(ctx context.Context, event plugin.EventType, data
 		return nil, err
 	}
 
-	eventResult := parseEventResult(result)
+	eventResult := events.ParseEventResult(result)
+	if eventResult.Error != "" {
+		err := errors.New(eventResult.Error)
+		i.errorCount++
+		i.info.Metrics.RecordError(err)
+		return nil, err
+	}
 	i.info.Metrics.RecordEvent(event, eventResult.Cancelled)
 	return eventResult, nil
 }
@@ -180,11 +187,3 @@ func (i *Instance) State() plugin.State {
 	defer i.mu.RUnlock()
 	return i.info.State
 }
-
-func parseEventResult(data []byte) *events.EventResult {
-	result := &events.EventResult{Modifications: make(map[string]string)}
-	if len(data) > 0 && data[0] == 1 {
-		result.Cancelled = true
-	}
-	return result
-}
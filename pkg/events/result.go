@@ -0,0 +1,58 @@
+package events
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+// ErrorResultMagic prefixes a handle_event result that reports a plugin error
+// instead of a cancel flag.
+const ErrorResultMagic = 0xFF
+
+type errorResult struct {
+	ErrorCode uint32 `json:"error_code"`
+	Message   string `json:"message"`
+}
+
+// ParseEventResult decodes the bytes a plugin returns from an event handler:
+// a cancel flag, optionally followed by a JSON object of modifications, or
+// ErrorResultMagic followed by a JSON error result.
+func ParseEventResult(data []byte) *EventResult {
+	result := &EventResult{Modifications: make(map[string]string)}
+	if len(data) == 0 {
+		return result
+	}
+
+	switch data[0] {
+	case 1:
+		result.Cancelled = true
+		return result
+	case ErrorResultMagic:
+		var res errorResult
+		if err := json.Unmarshal(data[1:], &res); err != nil {
+			result.Error = "plugin reported an error"
+		} else {
+			result.Error = fmt.Sprintf("plugin error %d: %s", res.ErrorCode, res.Message)
+		}
+		return result
+	}
+	if len(data) == 1 {
+		return result
+	}
+
+	// Plugins may return nested modification values; flatten them so
+	// handlers that only understand string maps keep working.
+	var raw map[string]json.RawMessage
+	if err := json.Unmarshal(data[1:], &raw); err != nil {
+		return result
+	}
+	for k, v := range raw {
+		var s string
+		if err := json.Unmarshal(v, &s); err == nil {
+			result.Modifications[k] = s
+		} else {
+			result.Modifications[k] = string(v)
+		}
+	}
+	return result
+}
@@ -0,0 +1,58 @@
+package events
+
+import "testing"
+
+func TestParseEventResultCancelled(t *testing.T) {
+	result := ParseEventResult([]byte{1})
+	if !result.Cancelled {
+		t.Fatal("expected cancelled result")
+	}
+	if len(result.Modifications) != 0 {
+		t.Fatalf("expected no modifications, got %v", result.Modifications)
+	}
+}
+
+func TestParseEventResultModifications(t *testing.T) {
+	data := append([]byte{0}, `{"message":"hi","extra":{"a":1}}`...)
+	result := ParseEventResult(data)
+	if result.Cancelled || result.Error != "" {
+		t.Fatalf("unexpected result %+v", result)
+	}
+	if got := result.Modifications["message"]; got != "hi" {
+		t.Fatalf("message = %q, want %q", got, "hi")
+	}
+	if got := result.Modifications["extra"]; got != `{"a":1}` {
+		t.Fatalf("extra = %q, want %q", got, `{"a":1}`)
+	}
+}
+
+func TestParseEventResultError(t *testing.T) {
+	data := append([]byte{ErrorResultMagic}, `{"error_code":2,"message":"chat: invalid payload"}`...)
+	result := ParseEventResult(data)
+	if result.Cancelled {
+		t.Fatal("an error result must not cancel the event")
+	}
+	if len(result.Modifications) != 0 {
+		t.Fatalf("an error result must not carry modifications, got %v", result.Modifications)
+	}
+	if want := "plugin error 2: chat: invalid payload"; result.Error != want {
+		t.Fatalf("Error = %q, want %q", result.Error, want)
+	}
+}
+
+func TestParseEventResultMalformedError(t *testing.T) {
+	result := ParseEventResult([]byte{ErrorResultMagic, '{'})
+	if result.Error == "" {
+		t.Fatal("expected an error for a malformed error result")
+	}
+	if len(result.Modifications) != 0 {
+		t.Fatalf("expected no modifications, got %v", result.Modifications)
+	}
+}
+
+func TestParseEventResultEmpty(t *testing.T) {
+	result := ParseEventResult(nil)
+	if result.Cancelled || result.Error != "" || len(result.Modifications) != 0 {
+		t.Fatalf("unexpected result %+v", result)
+	}
+}
@@ -7,6 +7,9 @@ import (
 	"regexp"
 )
 
+// CurrentAPIVersion is the major plugin API version this host implements.
+const CurrentAPIVersion = 1
+
 type Version struct {
 	Major int `toml:"major" json:"major"`
 	Minor int `toml:"minor" json:"minor"`
@@ -41,25 +44,59 @@ const (
 	EventPlayerRespawn      EventType = "player_respawn"
 	EventPlayerDeath        EventType = "player_death"
 	EventPlayerHurt         EventType = "player_hurt"
+	EventFallDamage         EventType = "fall_damage"
 	EventPlayerHeal         EventType = "player_heal"
 	EventPlayerAttackEntity EventType = "player_attack_entity"
-
-	EventBlockBreak    EventType = "block_break"
-	EventBlockPlace    EventType = "block_place"
-	EventBlockInteract EventType = "block_interact"
+	EventPlayerInteract     EventType = "player_interact"
+	EventPlayerExp          EventType = "player_exp"
+	EventSleep              EventType = "sleep_event"
+	EventFogChange          EventType = "fog_change"
+	EventPlayerBan          EventType = "player_ban"
+	EventDimensionTransfer  EventType = "dimension_transfer"
+
+	EventBlockBreak     EventType = "block_break"
+	EventBlockPlace     EventType = "block_place"
+	EventBlockInteract  EventType = "block_interact"
+	EventBlockExplode   EventType = "block_explode"
+	EventFurnace        EventType = "furnace_event"
+	EventBlockSpread    EventType = "block_spread"
+	EventNoteBlockPlay  EventType = "note_block_play"
+	EventPistonExtend   EventType = "piston_extend"
+	EventPistonRetract  EventType = "piston_retract"
+	EventLiquidFlow     EventType = "liquid_flow"
+	EventCropGrow       EventType = "crop_grow"
+	EventContainerOpen  EventType = "container_open"
+	EventContainerClose EventType = "container_close"
 
 	EventItemUse         EventType = "item_use"
 	EventItemUseOnBlock  EventType = "item_use_on_block"
 	EventItemUseOnEntity EventType = "item_use_on_entity"
 	EventItemConsume     EventType = "item_consume"
+	EventTridentThrow    EventType = "trident_throw"
 	EventItemDrop        EventType = "item_drop"
 	EventItemPickup      EventType = "item_pickup"
+	EventInventoryChange EventType = "inventory_change"
+	EventEnchantItem     EventType = "enchant_item"
+	EventGrindstoneUse   EventType = "grindstone_use"
+	EventFishing         EventType = "fishing_event"
+	EventCraftItem       EventType = "craft_item"
+	EventBookWrite       EventType = "book_write"
 
 	EventEntitySpawn    EventType = "entity_spawn"
 	EventEntityDespawn  EventType = "entity_despawn"
+	EventEntityDamage   EventType = "entity_damage"
+	EventProjectileHit  EventType = "projectile_hit"
+	EventPotionEffect   EventType = "potion_effect"
+	EventEntityModify   EventType = "entity_modify"
+	EventSheepDye       EventType = "sheep_dye"
+	EventVillagerTrade  EventType = "villager_trade"
 	EventCommand        EventType = "command"
 	EventSignEdit       EventType = "sign_edit"
 	EventServerTransfer EventType = "server_transfer"
+	EventWeatherChange  EventType = "weather_change"
+	EventWorldBorder    EventType = "world_border_change"
+	EventPortalCreate   EventType = "portal_create"
+	EventVehicle        EventType = "vehicle_event"
 )
 
 type Priority int